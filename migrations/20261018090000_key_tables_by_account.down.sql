@@ -0,0 +1,359 @@
+-- folder names and subscriptions were unique across accounts, the first copy of each is kept.
+-- feeds and entries are fetched again. entry status has nowhere to go without them.
+CREATE TABLE folders_backup AS SELECT * FROM folders;
+
+CREATE TABLE subscriptions_backup AS SELECT * FROM subscriptions;
+
+DROP VIEW IF EXISTS starred_view;
+
+DROP VIEW IF EXISTS unread_view;
+
+DROP VIEW IF EXISTS today_view;
+
+DROP VIEW IF EXISTS feed_unread_entries;
+
+DROP VIEW IF EXISTS feed_all_entries;
+
+DROP VIEW IF EXISTS folder_unread_entries;
+
+DROP VIEW IF EXISTS folder_all_entries;
+
+DROP TABLE IF EXISTS entry_status;
+
+DROP INDEX IF EXISTS idx_subscriptions_xml_url;
+
+DROP INDEX IF EXISTS idx_contents;
+
+DROP INDEX IF EXISTS idx_entries;
+
+DROP INDEX IF EXISTS idx_feeds;
+
+DROP TABLE IF EXISTS feed_subcategories;
+
+DROP TABLE IF EXISTS feed_categories;
+
+DROP TABLE IF EXISTS feed_generators;
+
+DROP TABLE IF EXISTS feed_media_ratings;
+
+DROP TABLE IF EXISTS entry_links;
+
+DROP TABLE IF EXISTS entry_persons;
+
+DROP TABLE IF EXISTS contents;
+
+DROP TABLE IF EXISTS entries;
+
+DROP TABLE IF EXISTS feeds;
+
+DROP INDEX IF EXISTS idx_subscriptions_title;
+
+DROP INDEX IF EXISTS idx_folders_name;
+
+DROP TABLE IF EXISTS subscriptions;
+
+DROP TABLE IF EXISTS folders;
+
+-- folder_name is used in Outline both title and text types. <outline text="Group Name" title="Group Name">
+-- folder_name can be NULL to match Rust's None == feed placed in Account's root
+CREATE TABLE IF NOT EXISTS folders (
+    account_id INTEGER REFERENCES accounts (id) ON DELETE CASCADE,
+    name VARCHAR(50) UNIQUE DEFAULT NULL,
+    PRIMARY KEY (account_id, name)
+);
+
+-- <outline text="Feed Name" type="rss" xmlUrl="https://example.com/rss.xml"
+-- description="" htmlUrl="https://example.com/" title="Feed Name" version="RSS"/>
+CREATE TABLE IF NOT EXISTS subscriptions (
+    account_id INTEGER REFERENCES accounts (id) ON DELETE CASCADE,
+    parent_folder VARCHAR(50) REFERENCES folders (name) ON DELETE CASCADE,
+    xml_url VARCHAR(255) UNIQUE NOT NULL,
+    html_url VARCHAR(255) UNIQUE NOT NULL,
+    title VARCHAR(255) UNIQUE NOT NULL,
+    TEXT VARCHAR(255) UNIQUE NOT NULL,
+    description TEXT,
+    TYPE VARCHAR(255),
+    version VARCHAR(255),
+    is_comment BOOLEAN,
+    is_breakpoint BOOLEAN,
+    created TEXT,
+    category TEXT,
+    language TEXT,
+    PRIMARY KEY (account_id, xml_url)
+);
+
+CREATE INDEX IF NOT EXISTS idx_folders_name ON folders (name);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_title ON subscriptions (title);
+
+INSERT OR IGNORE INTO folders (account_id, name)
+SELECT account_id, name FROM folders_backup
+ORDER BY rowid;
+
+INSERT OR IGNORE INTO subscriptions (account_id, parent_folder, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language)
+SELECT account_id, parent_folder, xml_url, COALESCE(html_url, xml_url), COALESCE(title, TEXT), TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language
+FROM subscriptions_backup
+WHERE parent_folder IS NULL OR parent_folder IN (SELECT name FROM folders)
+ORDER BY rowid;
+
+DROP TABLE subscriptions_backup;
+
+DROP TABLE folders_backup;
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Feed.html
+CREATE TABLE IF NOT EXISTS feeds (
+    id TEXT UNIQUE NOT NULL,
+    account_id INTEGER REFERENCES accounts (id) ON DELETE CASCADE,
+    parent_folder VARCHAR(50) REFERENCES folders (name) ON DELETE CASCADE,
+    title VARCHAR(300) NOT NULL,
+    home VARCHAR(300) NOT NULL,
+    logo VARCHAR(400) DEFAULT '/placeholder.png',
+    icon VARCHAR(400) DEFAULT '/placeholder.png',
+    published TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    authors TEXT NOT NULL,
+    description TEXT,
+    language TEXT,
+    rights TEXT,
+    ttl INTEGER,
+    feed_type VARCHAR(15) CHECK (
+        feed_type IN ('Atom', 'JSON', 'RSS0', 'RSS1', 'RSS2')
+    ),
+    PRIMARY KEY (account_id, id)
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Entry.html
+CREATE TABLE IF NOT EXISTS entries (
+    id TEXT PRIMARY KEY,
+    feed_id TEXT REFERENCES feeds (id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    authors TEXT NOT NULL,
+    published TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    read_status VARCHAR(10) CHECK (read_status IN ('unread', 'read')) DEFAULT 'unread',
+    starred_status VARCHAR(10) CHECK (starred_status IN ('unstarred', 'starred')) DEFAULT 'unstarred',
+    source TEXT,
+    rights TEXT,
+    language TEXT,
+    base TEXT
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Content.html
+CREATE TABLE IF NOT EXISTS contents (
+    entry_id TEXT REFERENCES entries (id) ON DELETE CASCADE,
+    content_body TEXT,
+    content_type VARCHAR(100) NOT NULL,
+    content_length INTEGER CHECK (content_length >= 0),
+    source_link TEXT
+);
+
+CREATE TABLE IF NOT EXISTS entry_persons (
+    entry_id TEXT REFERENCES entries (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    email TEXT,
+    uri TEXT,
+    role TEXT CHECK (role IN ('author', 'contributor')) DEFAULT 'author'
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Link.html
+CREATE TABLE IF NOT EXISTS entry_links (
+    entry_id TEXT REFERENCES entries (id) ON DELETE CASCADE,
+    href TEXT NOT NULL,
+    rel TEXT,
+    media_type TEXT,
+    hreflang TEXT,
+    title TEXT,
+    length INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_feeds ON feeds (id);
+
+CREATE INDEX IF NOT EXISTS idx_entries ON entries (id);
+
+CREATE INDEX IF NOT EXISTS idx_contents ON contents (entry_id);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Person.html
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.MediaRating.html
+CREATE TABLE IF NOT EXISTS feed_media_ratings (
+    feed_id TEXT REFERENCES feeds (id) ON DELETE CASCADE,
+    urn TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Generator.html
+CREATE TABLE IF NOT EXISTS feed_generators (
+    feed_id TEXT REFERENCES feeds (id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    uri TEXT,
+    version TEXT
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Category.html
+CREATE TABLE IF NOT EXISTS feed_categories (
+    id SERIAL PRIMARY KEY,
+    feed_id TEXT REFERENCES feeds (id) ON DELETE CASCADE,
+    term TEXT NOT NULL,
+    scheme TEXT,
+    label TEXT
+);
+
+-- https://docs.rs/feed-rs/2.3.1/feed_rs/model/struct.Category.html
+CREATE TABLE IF NOT EXISTS feed_subcategories (
+    category_id INTEGER REFERENCES feed_categories (id) ON DELETE CASCADE,
+    term TEXT NOT NULL,
+    scheme TEXT,
+    label TEXT
+);
+
+CREATE VIEW IF NOT EXISTS folder_all_entries AS
+SELECT
+    feeds.parent_folder AS feed_parent_folder,
+    feeds.id AS feed_name,
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            e.read_status = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+    INNER JOIN feeds ON e.feed_id = feeds.id
+    INNER JOIN folders ON feeds.parent_folder = folders.name
+    INNER JOIN accounts AS a ON feeds.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND folders.name = 'Folder 1'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS folder_unread_entries AS
+SELECT
+    feeds.parent_folder AS feed_parent_folder,
+    feeds.id AS feed_name,
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+    INNER JOIN feeds ON e.feed_id = feeds.id
+    INNER JOIN folders ON feeds.parent_folder = folders.name
+    INNER JOIN accounts AS a ON feeds.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND folders.name = 'Folder 1'
+    AND e.read_status = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS feed_all_entries AS
+SELECT
+    f.parent_folder AS feed_parent_folder,
+    f.id AS feed_name,
+    e.id AS entry_name,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            e.read_status = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+    INNER JOIN feeds AS f ON e.feed_id = f.id
+    INNER JOIN accounts AS a ON f.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND f.id = 'Feed 1'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS feed_unread_entries AS
+SELECT
+    f.parent_folder AS feed_parent_folder,
+    f.id AS feed_name,
+    e.id AS entry_name,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+    INNER JOIN feeds AS f ON e.feed_id = f.id
+    INNER JOIN accounts AS a ON f.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND f.id = 'Feed 1'
+    AND e.read_status = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS today_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            e.read_status = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+WHERE
+    DATE(e.published) = CURRENT_DATE
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS unread_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+WHERE
+    e.read_status = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS starred_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    e.read_status AS entry_read_status,
+    e.starred_status AS entry_starred_status,
+    COUNT(*) OVER () AS starred_count
+FROM
+    entries AS e
+WHERE
+    e.starred_status = 'starred'
+ORDER BY
+    e.published DESC;
@@ -0,0 +1,435 @@
+-- folders, subscriptions, feeds and entries were keyed by globally unique names,
+-- so two accounts could not share a folder name, a feed or an entry.
+-- everything below is keyed by account first.
+-- position keeps the order of the OPML outlines and of the feeds entries.
+-- folders and subscriptions are copied over. rows had no order, they keep the one they were stored in.
+-- feeds and entries had no raw column for the core to load them from, they are fetched again.
+-- the read and starred status of their entries can't be, so it's copied to entry_status.
+-- the views reading the status are rebuilt on entry_status.
+CREATE TABLE folders_backup AS SELECT * FROM folders;
+
+CREATE TABLE subscriptions_backup AS SELECT * FROM subscriptions;
+
+CREATE TABLE entry_status_backup AS
+SELECT
+    feeds.account_id,
+    entries.id AS entry_id,
+    entries.read_status,
+    entries.starred_status
+FROM
+    entries
+    INNER JOIN feeds ON entries.feed_id = feeds.id
+WHERE
+    feeds.account_id IS NOT NULL
+    AND (
+        entries.read_status = 'read'
+        OR entries.starred_status = 'starred'
+    );
+
+DROP VIEW IF EXISTS starred_view;
+
+DROP VIEW IF EXISTS unread_view;
+
+DROP VIEW IF EXISTS today_view;
+
+DROP VIEW IF EXISTS feed_unread_entries;
+
+DROP VIEW IF EXISTS feed_all_entries;
+
+DROP VIEW IF EXISTS folder_unread_entries;
+
+DROP VIEW IF EXISTS folder_all_entries;
+
+DROP INDEX IF EXISTS idx_contents;
+
+DROP INDEX IF EXISTS idx_entries;
+
+DROP INDEX IF EXISTS idx_feeds;
+
+DROP INDEX IF EXISTS idx_subscriptions_title;
+
+DROP INDEX IF EXISTS idx_folders_name;
+
+DROP TABLE IF EXISTS feed_subcategories;
+
+DROP TABLE IF EXISTS feed_categories;
+
+DROP TABLE IF EXISTS feed_generators;
+
+DROP TABLE IF EXISTS feed_media_ratings;
+
+DROP TABLE IF EXISTS entry_links;
+
+DROP TABLE IF EXISTS entry_persons;
+
+DROP TABLE IF EXISTS contents;
+
+DROP TABLE IF EXISTS entries;
+
+DROP TABLE IF EXISTS feeds;
+
+DROP TABLE IF EXISTS subscriptions;
+
+DROP TABLE IF EXISTS folders;
+
+-- folder_name is used in Outline both title and text types. <outline text="Group Name" title="Group Name">
+CREATE TABLE IF NOT EXISTS folders (
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (account_id, name)
+);
+
+-- parent_folder can be NULL to match Rust's None == feed placed in Account's root
+CREATE TABLE IF NOT EXISTS subscriptions (
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    parent_folder VARCHAR(50) DEFAULT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    xml_url VARCHAR(255) NOT NULL,
+    html_url VARCHAR(255),
+    title VARCHAR(255),
+    TEXT VARCHAR(255) NOT NULL,
+    description TEXT,
+    TYPE VARCHAR(255),
+    version VARCHAR(255),
+    is_comment BOOLEAN,
+    is_breakpoint BOOLEAN,
+    created TEXT,
+    category TEXT,
+    language TEXT,
+    url TEXT,
+    FOREIGN KEY (account_id, parent_folder) REFERENCES folders (account_id, name) ON DELETE CASCADE
+);
+
+-- raw: the whole feed_rs::model::Feed as JSON, without entries
+CREATE TABLE IF NOT EXISTS feeds (
+    id TEXT NOT NULL,
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    parent_folder VARCHAR(50) DEFAULT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    title VARCHAR(300) NOT NULL,
+    home VARCHAR(300) NOT NULL,
+    logo VARCHAR(400) DEFAULT '/placeholder.png',
+    icon VARCHAR(400) DEFAULT '/placeholder.png',
+    published TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    authors TEXT NOT NULL,
+    description TEXT,
+    language TEXT,
+    rights TEXT,
+    ttl INTEGER,
+    feed_type VARCHAR(15) CHECK (
+        feed_type IN ('Atom', 'JSON', 'RSS0', 'RSS1', 'RSS2')
+    ),
+    raw TEXT NOT NULL,
+    PRIMARY KEY (account_id, id)
+);
+
+-- raw: the whole feed_rs::model::Entry as JSON
+CREATE TABLE IF NOT EXISTS entries (
+    id TEXT NOT NULL,
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    title TEXT NOT NULL,
+    authors TEXT NOT NULL,
+    published TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    source TEXT,
+    rights TEXT,
+    language TEXT,
+    base TEXT,
+    raw TEXT NOT NULL,
+    PRIMARY KEY (account_id, feed_id, id),
+    FOREIGN KEY (account_id, feed_id) REFERENCES feeds (account_id, id) ON DELETE CASCADE
+);
+
+-- the status users give entries, keyed the way the core tracks it: by account and entry id.
+-- it isn't tied to the entries rows, as a feed can drop an entry and serve it again.
+-- entries which are unread and unstarred have no row.
+CREATE TABLE IF NOT EXISTS entry_status (
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    entry_id TEXT NOT NULL,
+    read_status VARCHAR(10) NOT NULL CHECK (read_status IN ('unread', 'read')) DEFAULT 'unread',
+    starred_status VARCHAR(10) NOT NULL CHECK (starred_status IN ('unstarred', 'starred')) DEFAULT 'unstarred',
+    PRIMARY KEY (account_id, entry_id)
+);
+
+CREATE TABLE IF NOT EXISTS contents (
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    entry_id TEXT NOT NULL,
+    content_body TEXT,
+    content_type VARCHAR(100) NOT NULL,
+    content_length INTEGER CHECK (content_length >= 0),
+    source_link TEXT,
+    FOREIGN KEY (account_id, feed_id, entry_id) REFERENCES entries (account_id, feed_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS entry_persons (
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    entry_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT,
+    uri TEXT,
+    role TEXT CHECK (role IN ('author', 'contributor')) DEFAULT 'author',
+    FOREIGN KEY (account_id, feed_id, entry_id) REFERENCES entries (account_id, feed_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS entry_links (
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    entry_id TEXT NOT NULL,
+    href TEXT NOT NULL,
+    rel TEXT,
+    media_type TEXT,
+    hreflang TEXT,
+    title TEXT,
+    length INTEGER,
+    FOREIGN KEY (account_id, feed_id, entry_id) REFERENCES entries (account_id, feed_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS feed_media_ratings (
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    urn TEXT NOT NULL,
+    value TEXT NOT NULL,
+    FOREIGN KEY (account_id, feed_id) REFERENCES feeds (account_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS feed_generators (
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    uri TEXT,
+    version TEXT,
+    FOREIGN KEY (account_id, feed_id) REFERENCES feeds (account_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS feed_categories (
+    id INTEGER PRIMARY KEY,
+    account_id INTEGER NOT NULL,
+    feed_id TEXT NOT NULL,
+    term TEXT NOT NULL,
+    scheme TEXT,
+    label TEXT,
+    FOREIGN KEY (account_id, feed_id) REFERENCES feeds (account_id, id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS feed_subcategories (
+    category_id INTEGER REFERENCES feed_categories (id) ON DELETE CASCADE,
+    term TEXT NOT NULL,
+    scheme TEXT,
+    label TEXT
+);
+
+INSERT INTO folders (account_id, name, position)
+SELECT account_id, name, 0 FROM folders_backup
+WHERE account_id IS NOT NULL AND name IS NOT NULL
+ORDER BY rowid;
+
+-- a subscription in a folder of another account goes to its account's root
+INSERT INTO subscriptions (account_id, parent_folder, position, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language)
+SELECT account_id, CASE WHEN EXISTS (
+        SELECT 1 FROM folders
+        WHERE folders.account_id = subscriptions_backup.account_id
+        AND folders.name = subscriptions_backup.parent_folder
+    ) THEN parent_folder ELSE NULL END, 0, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language
+FROM subscriptions_backup
+WHERE account_id IS NOT NULL
+ORDER BY rowid;
+
+INSERT INTO entry_status (account_id, entry_id, read_status, starred_status)
+SELECT account_id, entry_id, read_status, starred_status FROM entry_status_backup;
+
+DROP TABLE entry_status_backup;
+
+DROP TABLE subscriptions_backup;
+
+DROP TABLE folders_backup;
+
+CREATE INDEX IF NOT EXISTS idx_folders_name ON folders (name);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_title ON subscriptions (title);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_xml_url ON subscriptions (account_id, xml_url);
+
+CREATE INDEX IF NOT EXISTS idx_feeds ON feeds (id);
+
+CREATE INDEX IF NOT EXISTS idx_entries ON entries (id);
+
+CREATE INDEX IF NOT EXISTS idx_contents ON contents (account_id, feed_id, entry_id);
+
+CREATE VIEW IF NOT EXISTS folder_all_entries AS
+SELECT
+    feeds.parent_folder AS feed_parent_folder,
+    feeds.id AS feed_name,
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            COALESCE(s.read_status, 'unread') = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+    INNER JOIN feeds ON e.account_id = feeds.account_id
+    AND e.feed_id = feeds.id
+    INNER JOIN folders ON feeds.account_id = folders.account_id
+    AND feeds.parent_folder = folders.name
+    INNER JOIN accounts AS a ON feeds.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND folders.name = 'Folder 1'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS folder_unread_entries AS
+SELECT
+    feeds.parent_folder AS feed_parent_folder,
+    feeds.id AS feed_name,
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+    INNER JOIN feeds ON e.account_id = feeds.account_id
+    AND e.feed_id = feeds.id
+    INNER JOIN folders ON feeds.account_id = folders.account_id
+    AND feeds.parent_folder = folders.name
+    INNER JOIN accounts AS a ON feeds.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND folders.name = 'Folder 1'
+    AND COALESCE(s.read_status, 'unread') = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS feed_all_entries AS
+SELECT
+    f.parent_folder AS feed_parent_folder,
+    f.id AS feed_name,
+    e.id AS entry_name,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            COALESCE(s.read_status, 'unread') = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+    INNER JOIN feeds AS f ON e.account_id = f.account_id
+    AND e.feed_id = f.id
+    INNER JOIN accounts AS a ON f.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND f.id = 'Feed 1'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS feed_unread_entries AS
+SELECT
+    f.parent_folder AS feed_parent_folder,
+    f.id AS feed_name,
+    e.id AS entry_name,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+    INNER JOIN feeds AS f ON e.account_id = f.account_id
+    AND e.feed_id = f.id
+    INNER JOIN accounts AS a ON f.account_id = a.id
+WHERE
+    a.name = 'Local'
+    AND f.id = 'Feed 1'
+    AND COALESCE(s.read_status, 'unread') = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS today_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) FILTER (
+        WHERE
+            COALESCE(s.read_status, 'unread') = 'unread'
+    ) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+WHERE
+    DATE(e.published) = CURRENT_DATE
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS unread_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) OVER () AS unread_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+WHERE
+    COALESCE(s.read_status, 'unread') = 'unread'
+ORDER BY
+    e.published DESC;
+
+CREATE VIEW IF NOT EXISTS starred_view AS
+SELECT
+    e.id AS entry_id,
+    e.title AS entry_title,
+    e.authors AS entry_author,
+    e.published AS entry_published,
+    e.summary AS entry_summary,
+    COALESCE(s.read_status, 'unread') AS entry_read_status,
+    COALESCE(s.starred_status, 'unstarred') AS entry_starred_status,
+    COUNT(*) OVER () AS starred_count
+FROM
+    entries AS e
+    LEFT JOIN entry_status AS s ON s.account_id = e.account_id
+    AND s.entry_id = e.id
+WHERE
+    COALESCE(s.starred_status, 'unstarred') = 'starred'
+ORDER BY
+    e.published DESC;
@@ -0,0 +1,3 @@
+ALTER TABLE subscriptions DROP COLUMN last_modified;
+
+ALTER TABLE subscriptions DROP COLUMN etag;
@@ -0,0 +1,4 @@
+-- validators from the last successful fetch, sent back as If-None-Match and If-Modified-Since
+ALTER TABLE subscriptions ADD COLUMN etag TEXT DEFAULT NULL;
+
+ALTER TABLE subscriptions ADD COLUMN last_modified TEXT DEFAULT NULL;
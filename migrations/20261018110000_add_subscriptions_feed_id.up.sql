@@ -0,0 +1,2 @@
+-- the feed fetched from xml_url. feed ids come from the feed itself and can change, xml_url doesn't.
+ALTER TABLE subscriptions ADD COLUMN feed_id TEXT DEFAULT NULL;
@@ -0,0 +1,61 @@
+-- nested folders can't be keyed by name. their subscriptions go to their root level ancestor.
+CREATE TABLE folders_backup AS SELECT * FROM folders;
+
+CREATE TABLE subscriptions_backup AS SELECT * FROM subscriptions;
+
+DROP INDEX IF EXISTS idx_subscriptions_xml_url;
+
+DROP INDEX IF EXISTS idx_subscriptions_title;
+
+DROP INDEX IF EXISTS idx_folders_name;
+
+DROP TABLE subscriptions;
+
+DROP TABLE folders;
+
+CREATE TABLE IF NOT EXISTS folders (
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (account_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+    parent_folder VARCHAR(50) DEFAULT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    xml_url VARCHAR(255) NOT NULL,
+    html_url VARCHAR(255),
+    title VARCHAR(255),
+    TEXT VARCHAR(255) NOT NULL,
+    description TEXT,
+    TYPE VARCHAR(255),
+    version VARCHAR(255),
+    is_comment BOOLEAN,
+    is_breakpoint BOOLEAN,
+    created TEXT,
+    category TEXT,
+    language TEXT,
+    url TEXT,
+    etag TEXT DEFAULT NULL,
+    last_modified TEXT DEFAULT NULL,
+    feed_id TEXT DEFAULT NULL,
+    FOREIGN KEY (account_id, parent_folder) REFERENCES folders (account_id, name) ON DELETE CASCADE
+);
+
+INSERT INTO folders (account_id, name, position)
+SELECT account_id, name, position FROM folders_backup WHERE parent_folder IS NULL;
+
+INSERT INTO subscriptions (account_id, parent_folder, position, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language, url, etag, last_modified, feed_id)
+SELECT account_id, json_extract(parent_folder, '$[0]'), position, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language, url, etag, last_modified, feed_id
+FROM subscriptions_backup;
+
+DROP TABLE subscriptions_backup;
+
+DROP TABLE folders_backup;
+
+CREATE INDEX IF NOT EXISTS idx_folders_name ON folders (name);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_title ON subscriptions (title);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_xml_url ON subscriptions (account_id, xml_url);
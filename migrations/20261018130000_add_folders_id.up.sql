@@ -0,0 +1,5 @@
+-- a stable id for each folder, so the core can address a folder through renames and moves.
+-- folders saved before this get a random id, the core keeps it from then on.
+ALTER TABLE folders ADD COLUMN id TEXT DEFAULT NULL;
+
+UPDATE folders SET id = lower(hex(randomblob(16))) WHERE id IS NULL;
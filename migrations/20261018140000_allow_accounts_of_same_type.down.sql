@@ -0,0 +1,106 @@
+-- only the first account of each type is kept. deleting the others cascades to their data.
+DELETE FROM accounts
+WHERE id NOT IN (SELECT min(id) FROM accounts GROUP BY name);
+
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TABLE accounts_backup AS SELECT * FROM accounts;
+
+CREATE TABLE folders_backup AS SELECT * FROM folders;
+
+CREATE TABLE subscriptions_backup AS SELECT * FROM subscriptions;
+
+CREATE TABLE feeds_backup AS SELECT * FROM feeds;
+
+CREATE TABLE entries_backup AS SELECT * FROM entries;
+
+CREATE TABLE entry_status_backup AS SELECT * FROM entry_status;
+
+CREATE TABLE contents_backup AS SELECT * FROM contents;
+
+CREATE TABLE entry_persons_backup AS SELECT * FROM entry_persons;
+
+CREATE TABLE entry_links_backup AS SELECT * FROM entry_links;
+
+CREATE TABLE feed_media_ratings_backup AS SELECT * FROM feed_media_ratings;
+
+CREATE TABLE feed_generators_backup AS SELECT * FROM feed_generators;
+
+CREATE TABLE feed_categories_backup AS SELECT * FROM feed_categories;
+
+CREATE TABLE feed_subcategories_backup AS SELECT * FROM feed_subcategories;
+
+DROP INDEX IF EXISTS idx_account_name;
+
+DROP TABLE accounts;
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- name: to use with Rust related actions and safe db actions
+    name VARCHAR(50) UNIQUE NOT NULL CHECK (
+        name IN (
+            'Local',
+            'iCloud',
+            'Live 365',
+            'Google Sync',
+            'Ubuntu One'
+        )
+    ),
+    -- alias: for display (preserving account_name functionality)
+    alias VARCHAR(50) DEFAULT name UNIQUE
+);
+
+INSERT INTO accounts (id, name, alias)
+SELECT id, name, alias FROM accounts_backup;
+
+INSERT INTO folders SELECT * FROM folders_backup;
+
+INSERT INTO subscriptions SELECT * FROM subscriptions_backup;
+
+INSERT INTO feeds SELECT * FROM feeds_backup;
+
+INSERT INTO entries SELECT * FROM entries_backup;
+
+INSERT INTO entry_status SELECT * FROM entry_status_backup;
+
+INSERT INTO contents SELECT * FROM contents_backup;
+
+INSERT INTO entry_persons SELECT * FROM entry_persons_backup;
+
+INSERT INTO entry_links SELECT * FROM entry_links_backup;
+
+INSERT INTO feed_media_ratings SELECT * FROM feed_media_ratings_backup;
+
+INSERT INTO feed_generators SELECT * FROM feed_generators_backup;
+
+INSERT INTO feed_categories SELECT * FROM feed_categories_backup;
+
+INSERT INTO feed_subcategories SELECT * FROM feed_subcategories_backup;
+
+DROP TABLE feed_subcategories_backup;
+
+DROP TABLE feed_categories_backup;
+
+DROP TABLE feed_generators_backup;
+
+DROP TABLE feed_media_ratings_backup;
+
+DROP TABLE entry_links_backup;
+
+DROP TABLE entry_persons_backup;
+
+DROP TABLE contents_backup;
+
+DROP TABLE entries_backup;
+
+DROP TABLE entry_status_backup;
+
+DROP TABLE feeds_backup;
+
+DROP TABLE subscriptions_backup;
+
+DROP TABLE folders_backup;
+
+DROP TABLE accounts_backup;
+
+CREATE INDEX IF NOT EXISTS idx_account_name ON accounts (name);
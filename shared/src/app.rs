@@ -3,7 +3,7 @@ use crux_core::{
     render::{render, RenderOperation},
     Command,
 };
-use crux_http::{command::Http, protocol::HttpRequest};
+use crux_http::protocol::HttpRequest;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -17,10 +17,47 @@ mod accounts;
 pub use accounts::*;
 
 mod subscriptions;
-use subscriptions::*;
+pub use subscriptions::*;
 
 mod feeds;
-use feeds::*;
+pub use feeds::*;
+
+mod storage;
+pub use storage::*;
+
+mod smart_feeds;
+pub use smart_feeds::*;
+
+mod timer;
+pub use timer::*;
+
+mod refresh;
+pub use refresh::*;
+
+mod http_cache;
+pub use http_cache::*;
+
+mod discovery;
+pub use discovery::*;
+
+mod sidebar;
+pub use sidebar::*;
+
+mod entries;
+pub use entries::*;
+
+mod sanitise;
+
+mod entry_text;
+
+mod validation;
+pub use validation::*;
+
+mod file;
+pub use file::*;
+
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
 
 // ANCHOR: events
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -28,35 +65,90 @@ use feeds::*;
 #[non_exhaustive]
 pub enum Event {
     // EVENTS FROM THE SHELL
+    LoadState,
     // GetPreferences,
     // SetPreferences(Preferences),
     CreateAccount(AccountType),
+    CreateNamedAccount(AccountType, AccountName),
     DeleteAccount(Account),
-    RenameAccount(OldAccountName, NewAccountName),
-    ImportSubscriptions(Account, OpmlFileContent),
+    RenameAccount(Account, NewAccountName),
+    ImportSubscriptions(Account, OpmlFileContent, ImportMode, NestedFolders),
+    PreviewImport(Account, OpmlFileContent, ImportMode, NestedFolders),
+    ConfirmImport(
+        Account,
+        OpmlFileContent,
+        ImportMode,
+        NestedFolders,
+        Vec<SubscriptionLink>,
+    ),
     ExportSubscriptions(Account, OpmlFileName),
-    AddNewFolder(Account, FolderName),
-    DeleteFolder(Account, FolderName),
-    RenameFolder(Account, OldFolderName, NewFolderName),
+    ExportAllSubscriptions(OpmlFileName),
+    ImportAllSubscriptions(OpmlFileContent),
+    AddNewFolder(Account, Option<FolderId>, FolderName),
+    DeleteFolder(Account, FolderId, FolderSubscriptions),
+    RenameFolder(Account, FolderId, NewFolderName),
     AddSubscription(
         Account,
-        Option<FolderName>,
+        Option<FolderId>,
         SubscriptionTitle,
         SubscriptionLink,
     ),
-    DeleteSubscription(Account, Option<FolderName>, SubscriptionTitle),
-    RenameSubscription(
+    DeleteSubscription(Account, SubscriptionId),
+    RenameSubscription(Account, SubscriptionId, NewSubscriptionName),
+    MoveSubscription(Account, SubscriptionId, NewFolder),
+    MoveSubscriptions(Account, Vec<SubscriptionId>, NewFolder),
+    GetFeed(Account, SubscriptionId),
+    MarkEntryRead(Account, EntryId),
+    MarkEntryUnread(Account, EntryId),
+    ToggleStar(Account, EntryId),
+    MarkFeedRead(Account, SubscriptionId),
+    MarkFolderRead(Account, FolderId),
+    MarkAccountRead(Account),
+    SelectSmartFeed(SmartFeed),
+    SelectAccount(Account),
+    SelectFolder(Account, FolderId),
+    SelectSubscription(Account, SubscriptionId),
+    SelectEntry(Account, EntryId),
+    SelectEntryPage(usize),
+    ScheduleRefresh,
+    CancelRefresh,
+    SetRefreshInterval(RefreshInterval),
+    SetRefreshConcurrency(RefreshConcurrency),
+    RefreshAccount(Account),
+    RefreshAll,
+    DiscoverFeeds(SubscriptionLink),
+    AddValidatedSubscription(Account, Option<FolderId>, SubscriptionLink),
+
+    // EVENTS LOCAL TO THE CORE
+    SetFeed(
         Account,
-        Option<FolderName>,
         SubscriptionLink,
-        OldSubscriptionName,
-        NewSubscriptionName,
+        crux_http::Result<crux_http::Response<Vec<u8>>>,
+    ),
+    SetState(StorageResult),
+    StateSaved(StorageResult),
+    SubscriptionsExported(FileResult),
+    RefreshTimerFired(TimerOutput),
+    FeedRefreshed(
+        AccountId,
+        SubscriptionLink,
+        crux_http::Result<crux_http::Response<Vec<u8>>>,
+    ),
+    SetDiscoveredSite(
+        SubscriptionLink,
+        crux_http::Result<crux_http::Response<Vec<u8>>>,
+    ),
+    SetDiscoveredFeed(
+        SubscriptionLink,
+        SubscriptionLink,
+        crux_http::Result<crux_http::Response<Vec<u8>>>,
+    ),
+    SetValidatedSubscription(
+        Account,
+        Option<FolderId>,
+        SubscriptionLink,
+        crux_http::Result<crux_http::Response<Vec<u8>>>,
     ),
-    MoveSubscription(Account, Subscription, OldFolder, NewFolder),
-    GetFeed(Account, SubscriptionLink),
-
-    // EVENTS LOCAL TO THE CORE
-    SetFeed(Account, crux_http::Result<crux_http::Response<Vec<u8>>>),
 }
 // ANCHOR_END: events
 
@@ -67,6 +159,9 @@ pub enum Event {
 pub enum Effect {
     Render(RenderOperation),
     Http(HttpRequest),
+    Storage(StorageOperation),
+    Timer(TimerOperation),
+    File(FileOperation),
 }
 // ANCHOR_END: effects and capabilities
 
@@ -89,6 +184,22 @@ pub struct Model {
     pub account_name: String,                 // extrapolated from account
     pub folder_name: FolderName,              // root or folder if None -> nothing? root? phantom?
     pub subscription_name: SubscriptionTitle, // extrapolated from feed
+    pub smart_feed: Option<SmartFeed>,
+    pub sidebar_selection: Option<SidebarItem>, // None when nothing in the sidebar is selected
+    pub entry_page: usize,                      // of the entries for the sidebar selection
+    pub selected_entry: Option<SelectedEntry>,  // None when no article is open
+    pub article_content: Option<ArticleContent>, // of the selected entry
+    pub new_entries_count: NewEntriesCount,     // from the last feed fetched on demand
+    pub discovery: Discovery,
+    pub subscription_error: Option<SubscriptionError>, // from the last validated subscription
+    pub import_report: ImportReport,                   // from the last import
+    pub import_preview: Option<ImportPreview>,         // None when no import is pending
+    pub refresh_timer: Option<TimerId>,                // None when no refresh is scheduled
+    pub refresh_timers_started: u64,
+    pub refresh_progress: RefreshProgress,
+    pub refresh_concurrency: RefreshConcurrency,
+    pub refresh_queue: RefreshQueue, // links waiting for a free Http slot
+    pub refresh_in_flight: usize,    // fetches sent and not yet come back
 }
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
@@ -103,10 +214,21 @@ pub struct Notification {
 #[non_exhaustive]
 pub struct ViewModel {
     pub notification: Notification,
-    pub account_name: String,    // extrapolated from account
-    pub folder_name: FolderName, // root or folder if None -> nothing? root? phantom?
+    pub account_name: String,                 // extrapolated from account
+    pub folder_name: FolderName,              // root or folder if None -> nothing? root? phantom?
     pub subscription_name: SubscriptionTitle, // extrapolated from feed
-                                 // pub accounts: Accounts,
+    pub sidebar: SidebarView,
+    pub unread_count: usize,
+    pub starred_count: usize,
+    pub smart_feeds: SmartFeedsView,
+    pub entry_list: EntryListView,
+    pub article: Option<ArticleView>,
+    pub refresh_progress: RefreshProgress,
+    pub new_entries_count: NewEntriesCount,
+    pub discovery: Discovery,
+    pub subscription_error: Option<SubscriptionError>,
+    pub import_report: ImportReport,
+    pub import_preview: Option<ImportPreview>,
 }
 // ANCHOR_END: view model
 
@@ -128,6 +250,8 @@ impl crux_core::App for App {
         _caps: &(), // will be deprecated, so prefix with underscore for now
     ) -> Command<Effect, Event> {
         match event {
+            Event::LoadState => Storage::load().then_send(Event::SetState),
+
             // Event::GetPreferences => match settings::read_config() {
             //     Ok(preferences) => {
             //         model.preferences = preferences;
@@ -146,8 +270,30 @@ impl crux_core::App for App {
                 match Accounts::create(&model.accounts, &account_type) {
                     Ok(accounts) => {
                         model.accounts = accounts;
+                        // NOTE new accounts are added last
+                        model
+                            .save_account(model.accounts.acct.len() - 1)
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Account Error".to_string(),
+                            message: error.to_string(),
+                        };
                         render()
                     }
+                }
+            }
+
+            Event::CreateNamedAccount(account_type, account_name) => {
+                match Accounts::create_named(&model.accounts, &account_type, &account_name) {
+                    Ok(accounts) => {
+                        model.accounts = accounts;
+                        // NOTE new accounts are added last
+                        model
+                            .save_account(model.accounts.acct.len() - 1)
+                            .and(render())
+                    }
                     Err(error) => {
                         model.notification = Notification {
                             title: "Account Error".to_string(),
@@ -160,14 +306,17 @@ impl crux_core::App for App {
 
             Event::DeleteAccount(account) => {
                 model.accounts = Accounts::delete(&model.accounts, &account);
-                render()
+                Model::delete_stored_account(&account.id).and(render())
             }
 
-            Event::RenameAccount(old_account_name, new_account_name) => {
-                match Accounts::rename(&model.accounts, &old_account_name, &new_account_name) {
+            Event::RenameAccount(account, new_account_name) => {
+                match Accounts::rename(&model.accounts, &account, &new_account_name) {
                     Ok(accounts) => {
                         model.accounts = accounts;
-                        render()
+                        match Accounts::find_by_index(&model.accounts, &account) {
+                            Some(account_index) => model.save_account(account_index).and(render()),
+                            None => render(),
+                        }
                     }
                     Err(error) => {
                         model.notification = Notification {
@@ -179,17 +328,79 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::ImportSubscriptions(account, opml_file_content) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::ImportSubscriptions(account, opml_file_content, import_mode, nested_folders) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::import(
                     &model.accounts.acct[account_index].subs,
                     &opml_file_content,
+                    &import_mode,
+                    &nested_folders,
                 ) {
-                    // TODO on duplicates, prompt user for merge or replace
-                    Ok(subsscriptions) => {
-                        model.accounts.acct[account_index].subs = subsscriptions;
+                    Ok((subscriptions, import_report)) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model.import_report = import_report;
+                        model.save_subscriptions(account_index).and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Import Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::PreviewImport(account, opml_file_content, import_mode, nested_folders) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::preview_import(
+                    &model.accounts.acct[account_index].subs,
+                    &opml_file_content,
+                    &import_mode,
+                    &nested_folders,
+                ) {
+                    Ok(import_preview) => {
+                        model.import_preview = Some(import_preview);
                         render()
                     }
+                    Err(error) => {
+                        model.import_preview = None;
+                        model.notification = Notification {
+                            title: "Import Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::ConfirmImport(
+                account,
+                opml_file_content,
+                import_mode,
+                nested_folders,
+                selection,
+            ) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                model.import_preview = None;
+                match Subscriptions::confirm_import(
+                    &model.accounts.acct[account_index].subs,
+                    &opml_file_content,
+                    &import_mode,
+                    &nested_folders,
+                    &selection,
+                ) {
+                    Ok((subscriptions, import_report)) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model.import_report = import_report;
+                        model.save_subscriptions(account_index).and(render())
+                    }
                     Err(error) => {
                         model.notification = Notification {
                             title: "Import Error".to_string(),
@@ -201,21 +412,18 @@ impl crux_core::App for App {
             }
 
             Event::ExportSubscriptions(account, opml_file_name) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::export(
                     &model.accounts.acct[account_index].subs,
                     &opml_file_name,
                 ) {
-                    Ok(_) => render(),
-                    // Ok(success) => {
-                    //     model.notification = Notification {
-                    //         title: "Subscriptions Exported".to_string(),
-                    //         message: success.to_string(),
-                    //     };
-                    //     render()
-                    // }
+                    Ok(opml_file_content) => {
+                        File::save(&opml_file_name, opml_file_content.into_bytes())
+                            .then_send(Event::SubscriptionsExported)
+                    }
                     Err(error) => {
-                        // TODO once shell is implemented, check failures
                         model.notification = Notification {
                             title: "Export Error".to_string(),
                             message: error.to_string(),
@@ -225,15 +433,73 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::AddNewFolder(account, folder_name) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::ExportAllSubscriptions(opml_file_name) => {
+                match Accounts::export(&model.accounts, &opml_file_name) {
+                    Ok(opml_file_content) => {
+                        File::save(&opml_file_name, opml_file_content.into_bytes())
+                            .then_send(Event::SubscriptionsExported)
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Export Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::ImportAllSubscriptions(opml_file_content) => {
+                match Accounts::import(&model.accounts, &opml_file_content) {
+                    Ok((accounts, import_report)) => {
+                        model.accounts = accounts;
+                        model.import_report = import_report;
+                        // NOTE any account can be created or merged into
+                        Command::all((0..model.accounts.acct.len()).map(|account_index| {
+                            model
+                                .save_account(account_index)
+                                .then(model.save_subscriptions(account_index))
+                        }))
+                        .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Import Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::SubscriptionsExported(Ok(FileResponse::Saved { file_name })) => {
+                model.notification = Notification {
+                    title: "Subscriptions Exported".to_string(),
+                    message: format!("Subscriptions successfully exported to \"{file_name}\"."),
+                };
+                render()
+            }
+
+            Event::SubscriptionsExported(Err(error)) => {
+                model.notification = Notification {
+                    title: "Export Error".to_string(),
+                    message: error.to_string(),
+                };
+                render()
+            }
+
+            Event::AddNewFolder(account, parent_id, folder_name) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::add_folder(
                     &model.accounts.acct[account_index].subs,
+                    &parent_id,
                     &folder_name,
                 ) {
                     Ok(subscriptions) => {
                         model.accounts.acct[account_index].subs = subscriptions;
-                        render()
+                        model.save_subscriptions(account_index).and(render())
                     }
                     Err(error) => {
                         model.notification = Notification {
@@ -245,27 +511,41 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::DeleteFolder(account, folder_name) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
-                model.accounts.acct[account_index].subs = {
-                    Subscriptions::delete_folder(
-                        &model.accounts.acct[account_index].subs,
-                        &folder_name,
-                    )
+            Event::DeleteFolder(account, folder_id, folder_subs) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
                 };
-                render()
+                match Subscriptions::delete_folder(
+                    &model.accounts.acct[account_index].subs,
+                    &folder_id,
+                    &folder_subs,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model.save_subscriptions(account_index).and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Delete Folder Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
             }
 
-            Event::RenameFolder(account, old_folder_name, new_folder_name) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::RenameFolder(account, folder_id, new_folder_name) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::rename_folder(
                     &model.accounts.acct[account_index].subs,
-                    &old_folder_name,
+                    &folder_id,
                     &new_folder_name,
                 ) {
                     Ok(subscriptions) => {
                         model.accounts.acct[account_index].subs = subscriptions;
-                        render()
+                        model.save_subscriptions(account_index).and(render())
                     }
                     Err(error) => {
                         model.notification = Notification {
@@ -277,17 +557,19 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::AddSubscription(account, folder_name, sub_title, sub_link) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::AddSubscription(account, folder_id, sub_title, sub_link) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::add_subscription(
                     &model.accounts.acct[account_index].subs,
-                    &folder_name,
+                    &folder_id,
                     &sub_title,
                     &sub_link,
                 ) {
                     Ok(subscriptions) => {
                         model.accounts.acct[account_index].subs = subscriptions;
-                        render()
+                        model.save_subscriptions(account_index).and(render())
                     }
                     Err(error) => {
                         model.notification = Notification {
@@ -302,34 +584,29 @@ impl crux_core::App for App {
                 //     .send(move |result| Event::SetFeed(account, result));
             }
 
-            Event::DeleteSubscription(account, folder_name, sub_title) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::DeleteSubscription(account, sub_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 model.accounts.acct[account_index].subs = Subscriptions::delete_subscription(
                     &model.accounts.acct[account_index].subs,
-                    &folder_name,
-                    &sub_title,
+                    &sub_id,
                 );
-                render()
+                model.save_subscriptions(account_index).and(render())
             }
 
-            Event::RenameSubscription(
-                account,
-                folder_name,
-                sub_link,
-                old_sub_name,
-                new_sub_name,
-            ) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::RenameSubscription(account, sub_id, new_sub_name) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::rename_subscription(
                     &model.accounts.acct[account_index].subs,
-                    &folder_name,
-                    &sub_link,
-                    &old_sub_name,
+                    &sub_id,
                     &new_sub_name,
                 ) {
                     Ok(subscriptions) => {
                         model.accounts.acct[account_index].subs = subscriptions;
-                        render()
+                        model.save_subscriptions(account_index).and(render())
                     }
                     Err(error) => {
                         model.notification = Notification {
@@ -341,18 +618,42 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::MoveSubscription(account, subscription, old_folder, new_folder) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
+            Event::MoveSubscription(account, sub_id, new_folder) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
                 match Subscriptions::move_subscription(
                     &model.accounts.acct[account_index].subs,
-                    &subscription,
-                    &old_folder,
+                    &sub_id,
                     &new_folder,
                 ) {
                     Ok(subscriptions) => {
                         model.accounts.acct[account_index].subs = subscriptions;
+                        model.save_subscriptions(account_index).and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Subscription Error".to_string(),
+                            message: error.to_string(),
+                        };
                         render()
                     }
+                }
+            }
+
+            Event::MoveSubscriptions(account, sub_ids, new_folder) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::move_subscriptions(
+                    &model.accounts.acct[account_index].subs,
+                    &sub_ids,
+                    &new_folder,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model.save_subscriptions(account_index).and(render())
+                    }
                     Err(error) => {
                         model.notification = Notification {
                             title: "Subscription Error".to_string(),
@@ -363,18 +664,154 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::GetFeed(account, sub_link) => Http::get(&sub_link)
-                .build()
-                .then_send(move |result| Event::SetFeed(account, result)),
+            Event::GetFeed(account, sub_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                let subs = &model.accounts.acct[account_index].subs;
+                match subs.find_subscription_link(&sub_id) {
+                    Ok(sub_link) => subs
+                        .get_feed(&sub_link)
+                        .build()
+                        .then_send(move |result| Event::SetFeed(account, sub_link, result)),
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Feed Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
 
-            Event::SetFeed(account, Ok(mut response)) => {
-                let account_index = Accounts::find_by_index(&model.accounts, &account);
-                let body = response.take_body().unwrap();
-                match Subscriptions::add_feed(&model.accounts.acct[account_index].subs, body) {
-                    Ok(subs) => {
+            // NOTE the account may be gone by the time the fetch comes back
+            Event::SetFeed(account, sub_link, Ok(response)) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return Command::done();
+                };
+                let not_modified = HttpCache::is_not_modified(&response);
+                match Subscriptions::update_feed(
+                    &model.accounts.acct[account_index].subs,
+                    &sub_link,
+                    response,
+                ) {
+                    Ok((subs, new_entries_count)) => {
                         model.accounts.acct[account_index].subs = subs;
+                        model.new_entries_count = new_entries_count;
+                        model.cache_article();
+                        if not_modified {
+                            render()
+                        } else {
+                            model.save_feed(account_index, &sub_link).and(render())
+                        }
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Feed Error".to_string(),
+                            message: error.to_string(),
+                        };
                         render()
                     }
+                }
+            }
+
+            Event::SetFeed(_, _, Err(error)) => {
+                model.notification = Notification {
+                    title: "Http Error".to_string(),
+                    message: error.to_string(),
+                };
+                render()
+            }
+
+            Event::MarkEntryRead(account, entry_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::mark_entry(
+                    &model.accounts.acct[account_index].subs,
+                    &entry_id,
+                    &ReadStatus::Read,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model
+                            .save_entry_status(account_index, &entry_id)
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Entry Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::MarkEntryUnread(account, entry_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::mark_entry(
+                    &model.accounts.acct[account_index].subs,
+                    &entry_id,
+                    &ReadStatus::Unread,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model
+                            .save_entry_status(account_index, &entry_id)
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Entry Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::ToggleStar(account, entry_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::toggle_star(
+                    &model.accounts.acct[account_index].subs,
+                    &entry_id,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model
+                            .save_entry_status(account_index, &entry_id)
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Entry Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::MarkFeedRead(account, sub_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                let subs = &model.accounts.acct[account_index].subs;
+                match subs.find_subscription_link(&sub_id).and_then(|sub_link| {
+                    subs.mark_feed_read(&sub_link)
+                        .map(|subscriptions| (subscriptions, sub_link))
+                }) {
+                    Ok((subscriptions, sub_link)) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model
+                            .save_feeds_status(account_index, &[sub_link])
+                            .and(render())
+                    }
                     Err(error) => {
                         model.notification = Notification {
                             title: "Feed Error".to_string(),
@@ -385,13 +822,358 @@ impl crux_core::App for App {
                 }
             }
 
-            Event::SetFeed(_, Err(error)) => {
+            Event::MarkFolderRead(account, folder_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::mark_folder_read(
+                    &model.accounts.acct[account_index].subs,
+                    &folder_id,
+                ) {
+                    Ok(subscriptions) => {
+                        let sub_links = subscriptions
+                            .list_folder_subscription_links(&folder_id)
+                            .unwrap_or_default();
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model
+                            .save_feeds_status(account_index, &sub_links)
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Folder Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::MarkAccountRead(account) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                model.accounts.acct[account_index].subs =
+                    Subscriptions::mark_all_read(&model.accounts.acct[account_index].subs);
+                let sub_links = model.accounts.acct[account_index]
+                    .subs
+                    .list_subscription_links();
+                model
+                    .save_feeds_status(account_index, &sub_links)
+                    .and(render())
+            }
+
+            // NOTE a smart feed and a sidebar node are never selected at the same time
+            Event::SelectSmartFeed(smart_feed) => {
+                model.smart_feed = Some(smart_feed);
+                model.sidebar_selection = None;
+                model.entry_page = 0;
+                render()
+            }
+
+            Event::SelectAccount(account) => {
+                if Accounts::find_by_index(&model.accounts, &account).is_none() {
+                    return model.missing_account(&account);
+                }
+                model.smart_feed = None;
+                model.sidebar_selection = Some(SidebarItem::Account(account.id));
+                model.entry_page = 0;
+                render()
+            }
+
+            Event::SelectFolder(account, folder_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                if model.accounts.acct[account_index]
+                    .subs
+                    .find_folder_path(&folder_id)
+                    .is_some()
+                {
+                    model.smart_feed = None;
+                    model.sidebar_selection = Some(SidebarItem::Folder(account.id, folder_id));
+                    model.entry_page = 0;
+                } else {
+                    model.notification = Notification {
+                        title: "Folder Error".to_string(),
+                        message: Error::set_error(
+                            "Cannot select folder",
+                            folder_id.as_ref(),
+                            "It doesn't exist.",
+                        )
+                        .to_string(),
+                    };
+                }
+                render()
+            }
+
+            Event::SelectSubscription(account, sub_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match model.accounts.acct[account_index]
+                    .subs
+                    .find_subscription_link(&sub_id)
+                {
+                    Ok(_) => {
+                        model.smart_feed = None;
+                        model.sidebar_selection =
+                            Some(SidebarItem::Subscription(account.id, sub_id));
+                        model.entry_page = 0;
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Subscription Error".to_string(),
+                            message: error.to_string(),
+                        };
+                    }
+                }
+                render()
+            }
+
+            // NOTE opening an entry marks it read
+            Event::SelectEntry(account, entry_id) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                let feeds = &model.accounts.acct[account_index].subs.feeds;
+                if !feeds.contains_entry(&entry_id) {
+                    model.notification = Notification {
+                        title: "Entry Error".to_string(),
+                        message: Error::set_error(
+                            "Cannot select entry",
+                            entry_id.as_ref(),
+                            "It doesn't exist.",
+                        )
+                        .to_string(),
+                    };
+                    return render();
+                }
+                // NOTE an entry read already has no status to save
+                let was_read = feeds.find_entry_status(&entry_id).read == ReadStatus::Read;
+                match Subscriptions::mark_entry(
+                    &model.accounts.acct[account_index].subs,
+                    &entry_id,
+                    &ReadStatus::Read,
+                ) {
+                    Ok(subscriptions) => {
+                        model.accounts.acct[account_index].subs = subscriptions;
+                        model.selected_entry = Some(SelectedEntry {
+                            account_id: account.id,
+                            entry_id: entry_id.clone(),
+                        });
+                        model.cache_article();
+                        if was_read {
+                            render()
+                        } else {
+                            model
+                                .save_entry_status(account_index, &entry_id)
+                                .and(render())
+                        }
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Entry Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        render()
+                    }
+                }
+            }
+
+            Event::SelectEntryPage(page) => {
+                model.entry_page = page;
+                render()
+            }
+
+            Event::ScheduleRefresh => model.schedule_refresh(),
+
+            Event::CancelRefresh => model.cancel_refresh(),
+
+            Event::SetRefreshInterval(refresh_interval) => {
+                model.refresh_interval = refresh_interval;
+                if model.refresh_timer.is_some() {
+                    model.schedule_refresh().and(render())
+                } else {
+                    render()
+                }
+            }
+
+            Event::SetRefreshConcurrency(refresh_concurrency) => {
+                model.refresh_concurrency = refresh_concurrency;
+                Command::done()
+            }
+
+            Event::RefreshAccount(account) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                model.refresh_account(account_index).and(render())
+            }
+
+            Event::RefreshAll => model.refresh_all().and(render()),
+
+            Event::RefreshTimerFired(TimerOutput::Finished { id })
+                if model.refresh_timer == Some(id) =>
+            {
+                model.refresh_all().and(model.rearm_refresh()).and(render())
+            }
+
+            // NOTE cleared or superseded timers
+            Event::RefreshTimerFired(_) => Command::done(),
+
+            // NOTE the account may be gone by the time the fetch comes back
+            Event::FeedRefreshed(account_id, sub_link, result) => {
+                let account_index = Accounts::find_by_id(&model.accounts, &account_id);
+                let updated = match (account_index, result) {
+                    (Some(account_index), Ok(response)) => {
+                        let not_modified = HttpCache::is_not_modified(&response);
+                        model.accounts.acct[account_index]
+                            .subs
+                            .update_feed(&sub_link, response)
+                            .ok()
+                            .map(|updated| (account_index, not_modified, updated))
+                    }
+                    _ => None,
+                };
+
+                match updated {
+                    Some((account_index, not_modified, (subs, new_entries_count))) => {
+                        model.accounts.acct[account_index].subs = subs;
+                        model.cache_article();
+                        let save = if not_modified {
+                            Command::done()
+                        } else {
+                            model.save_feed(account_index, &sub_link)
+                        };
+                        save.and(model.feed_refreshed(Some(new_entries_count)))
+                            .and(render())
+                    }
+                    None => model.feed_refreshed(None).and(render()),
+                }
+            }
+
+            Event::DiscoverFeeds(site_link) => match model.discover_feeds(&site_link) {
+                Ok(command) => command.and(render()),
+                Err(error) => {
+                    model.notification = Notification {
+                        title: "Discovery Error".to_string(),
+                        message: error.to_string(),
+                    };
+                    render()
+                }
+            },
+
+            // NOTE responses for an earlier discovery are ignored
+            Event::SetDiscoveredSite(site_link, _) | Event::SetDiscoveredFeed(site_link, _, _)
+                if model.discovery.site_link != site_link =>
+            {
+                Command::done()
+            }
+
+            Event::SetDiscoveredSite(site_link, Ok(mut response)) => model
+                .site_fetched(&site_link, response.take_body().unwrap_or_default())
+                .and(render()),
+
+            Event::SetDiscoveredSite(_, Err(error)) => {
+                model.discovery.pending = 0;
                 model.notification = Notification {
                     title: "Http Error".to_string(),
                     message: error.to_string(),
                 };
                 render()
             }
+
+            Event::SetDiscoveredFeed(site_link, link, result) => {
+                let body = result.ok().and_then(|mut response| response.take_body());
+                if let Err(error) = model.candidate_fetched(&site_link, &link, body) {
+                    model.notification = Notification {
+                        title: "Discovery Error".to_string(),
+                        message: error.to_string(),
+                    };
+                }
+                render()
+            }
+
+            Event::AddValidatedSubscription(account, folder_id, sub_link) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return model.missing_account(&account);
+                };
+                match Subscriptions::validate_link(
+                    &model.accounts.acct[account_index].subs,
+                    &sub_link,
+                ) {
+                    Ok(()) => {
+                        model.subscription_error = None;
+                        model.accounts.acct[account_index]
+                            .subs
+                            .get_feed(&sub_link)
+                            .build()
+                            .then_send(move |result| {
+                                Event::SetValidatedSubscription(
+                                    account, folder_id, sub_link, result,
+                                )
+                            })
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Subscription Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        model.subscription_error = Some(error);
+                        render()
+                    }
+                }
+            }
+
+            // NOTE the account may be gone by the time the fetch comes back
+            Event::SetValidatedSubscription(account, folder_id, sub_link, result) => {
+                let Some(account_index) = Accounts::find_by_index(&model.accounts, &account) else {
+                    return Command::done();
+                };
+                match Subscriptions::add_validated_subscription(
+                    &model.accounts.acct[account_index].subs,
+                    &folder_id,
+                    &sub_link,
+                    result,
+                ) {
+                    Ok((subs, new_entries_count)) => {
+                        model.accounts.acct[account_index].subs = subs;
+                        model.new_entries_count = new_entries_count;
+                        model.subscription_error = None;
+                        // NOTE the feed's subscription has to be stored first
+                        model
+                            .save_subscriptions(account_index)
+                            .then(model.save_feed(account_index, &sub_link))
+                            .and(render())
+                    }
+                    Err(error) => {
+                        model.notification = Notification {
+                            title: "Subscription Error".to_string(),
+                            message: error.to_string(),
+                        };
+                        model.subscription_error = Some(error);
+                        render()
+                    }
+                }
+            }
+
+            Event::SetState(Ok(StorageResponse::Loaded(accounts))) => {
+                model.accounts = accounts;
+                render()
+            }
+
+            Event::SetState(Ok(StorageResponse::Saved)) | Event::StateSaved(Ok(_)) => {
+                Command::done()
+            }
+
+            Event::SetState(Err(error)) | Event::StateSaved(Err(error)) => {
+                model.notification = Notification {
+                    title: "Storage Error".to_string(),
+                    message: error.to_string(),
+                };
+                render()
+            }
         }
     }
 
@@ -401,12 +1183,41 @@ impl crux_core::App for App {
             account_name: model.account_name.clone(),
             folder_name: model.folder_name.clone(), // root or folder if None -> nothing? root? phantom?
             subscription_name: model.subscription_name.clone(), // extrapolated from feed
-                                                    // accounts: model.accounts.clone(),
-                                                    // feeds: model.accounts.
-                                                    // subscriptions: model.subscriptions.clone(),
-                                                    // subscription_folder: model.subscription_folder.to_string(),
-                                                    // subscription_title: model.subscription_title.to_string(),
-                                                    // subscription_link: model.subscription_link.to_string(),
+            unread_count: model
+                .accounts
+                .acct
+                .iter()
+                .map(|account| account.subs.feeds.count_unread())
+                .sum(),
+            starred_count: model
+                .accounts
+                .acct
+                .iter()
+                .map(|account| account.subs.feeds.count_starred())
+                .sum(),
+            smart_feeds: SmartFeedsView::new(&model.accounts, &model.smart_feed),
+            sidebar: SidebarView::new(&model.accounts, &model.sidebar_selection),
+            entry_list: EntryListView::new(
+                &model.accounts,
+                &model.sidebar_selection,
+                model.entry_page,
+            ),
+            article: ArticleView::new(
+                &model.accounts,
+                &model.selected_entry,
+                &model.article_content,
+            ),
+            refresh_progress: model.refresh_progress.clone(),
+            new_entries_count: model.new_entries_count,
+            discovery: model.discovery.clone(),
+            subscription_error: model.subscription_error.clone(),
+            import_report: model.import_report.clone(),
+            import_preview: model.import_preview.clone(),
+            // feeds: model.accounts.
+            // subscriptions: model.subscriptions.clone(),
+            // subscription_folder: model.subscription_folder.to_string(),
+            // subscription_title: model.subscription_title.to_string(),
+            // subscription_link: model.subscription_link.to_string(),
         }
     }
 }
@@ -1,10 +1,16 @@
-use super::subscriptions::Subscriptions;
-use super::Error;
+use super::subscriptions::{
+    ImportMode, ImportReport, NestedFolders, OpmlFileContent, OpmlFileName, Subscriptions,
+};
+use super::{Effect, Error, Event, Model, Notification};
 use crate::define_newtype;
 
+use crux_core::{render::render, Command};
+use opml::{Outline, OPML};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-define_newtype!(OldAccountName);
+define_newtype!(AccountId);
+define_newtype!(AccountName);
 define_newtype!(NewAccountName);
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -22,12 +28,26 @@ pub enum AccountType {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[non_exhaustive]
 pub struct Account {
+    pub id: AccountId, // never changes, unlike the name users see
     pub name: String,
+    pub account_type: AccountType,
     pub subs: Subscriptions,
 }
 
+// NOTE each account is a top-level outline of this type when exporting all accounts
+const ACCOUNT_OUTLINE_TYPE: &str = "account";
+
+// NOTE ids are always generated, so a new account never picks up the stored rows of an old one
+impl AccountId {
+    fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+}
+
 trait AccountHelpers {
     fn set_account_name(account_type: &AccountType) -> String;
+    fn account_type_name(account_type: &AccountType) -> String;
+    fn account_type_from_name(type_name: &str) -> Option<AccountType>;
 }
 
 impl AccountHelpers for Account {
@@ -41,6 +61,21 @@ impl AccountHelpers for Account {
         }
         .to_string()
     }
+
+    fn account_type_name(account_type: &AccountType) -> String {
+        format!("{account_type:?}")
+    }
+
+    fn account_type_from_name(type_name: &str) -> Option<AccountType> {
+        match type_name {
+            "Local" => Some(AccountType::Local),
+            "Apple" => Some(AccountType::Apple),
+            "Google" => Some(AccountType::Google),
+            "Microsoft" => Some(AccountType::Microsoft),
+            "Canonical" => Some(AccountType::Canonical),
+            _ => None,
+        }
+    }
 }
 
 trait NewAccount {
@@ -51,7 +86,9 @@ trait NewAccount {
 impl NewAccount for Account {
     fn new(account_type: &AccountType) -> Self {
         Account {
+            id: AccountId::generate(),
             name: Self::set_account_name(account_type),
+            account_type: account_type.clone(),
             subs: Subscriptions::default(),
         }
     }
@@ -68,28 +105,62 @@ pub struct Accounts {
     pub acct: Vec<Account>,
 }
 
+trait AccountsHelpers {
+    fn contains_name(&self, account_name: &str) -> bool;
+}
+
+impl AccountsHelpers for Accounts {
+    fn contains_name(&self, account_name: &str) -> bool {
+        self.acct.iter().any(|a| a.name == account_name)
+    }
+}
+
 trait CreateAccount {
     fn create_account(&self, account_type: &AccountType) -> Result<Self, Error>
     where
         Self: Sized;
+    fn create_named_account(
+        &self,
+        account_type: &AccountType,
+        account_name: &AccountName,
+    ) -> Result<Self, Error>
+    where
+        Self: Sized;
 }
 
+// NOTE names are what users tell accounts apart by, so they stay unique like accounts.alias
 impl CreateAccount for Accounts {
     fn create_account(&self, account_type: &AccountType) -> Result<Self, Error> {
+        self.create_named_account(
+            account_type,
+            &AccountName(Account::set_account_name(account_type)),
+        )
+    }
+
+    fn create_named_account(
+        &self,
+        account_type: &AccountType,
+        account_name: &AccountName,
+    ) -> Result<Self, Error> {
         let mut accounts = self.clone();
-        let account_to_add = Account::new(account_type);
-        let duplicate_err = Error::set_error(
-            "Cannot add account",
-            account_to_add.name.as_str(),
-            "It already exists.",
-        );
+        let account_name = account_name.as_ref().trim();
+        let empty_name_err =
+            Error::set_error("Cannot add account", account_name, "It needs a name.");
+        let duplicate_err =
+            Error::set_error("Cannot add account", account_name, "It already exists.");
 
-        if accounts.acct.contains(&account_to_add) {
-            Err(duplicate_err)
-        } else {
-            accounts.acct.push(account_to_add);
-            Ok(accounts)
+        if account_name.is_empty() {
+            return Err(empty_name_err);
+        }
+        if accounts.contains_name(account_name) {
+            return Err(duplicate_err);
         }
+
+        accounts.acct.push(Account {
+            name: account_name.to_string(),
+            ..Account::new(account_type)
+        });
+        Ok(accounts)
     }
 }
 
@@ -102,7 +173,7 @@ trait DeleteAccount {
 impl DeleteAccount for Accounts {
     fn delete_account(&self, account: &Account) -> Self {
         let mut accounts = self.clone();
-        accounts.acct.retain(|a| a.name != account.name);
+        accounts.acct.retain(|a| a.id != account.id);
         accounts
     }
 }
@@ -110,48 +181,184 @@ impl DeleteAccount for Accounts {
 trait RenameAccount {
     fn rename_account(
         &self,
-        old_account_name: &OldAccountName,
+        account: &Account,
         new_account_name: &NewAccountName,
     ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
+// NOTE accounts are found by id, as the name is what changes
 impl RenameAccount for Accounts {
     fn rename_account(
         &self,
-        old_account_name: &OldAccountName,
+        account: &Account,
         new_account_name: &NewAccountName,
     ) -> Result<Self, Error> {
         let mut accounts = self.clone();
+        let new_account_name = new_account_name.as_ref().trim();
         let does_not_exist_err = Error::set_error(
             "Cannot rename account",
-            old_account_name.0.as_str(),
+            account.name.as_str(),
             "It doesn't exists.",
         );
+        let empty_name_err = Error::set_error(
+            "Cannot rename account",
+            account.name.as_str(),
+            "It needs a name.",
+        );
+        let duplicate_err = Error::set_error(
+            "Cannot rename account",
+            account.name.as_str(),
+            "An account with this name already exists.",
+        );
 
-        for a in accounts.acct.iter_mut() {
-            if a.name == old_account_name.to_string() {
-                a.name = new_account_name.to_string();
-            } else {
-                return Err(does_not_exist_err);
-            }
+        let Some(account_index) = accounts.acct.iter().position(|a| a.id == account.id) else {
+            return Err(does_not_exist_err);
+        };
+        if new_account_name.is_empty() {
+            return Err(empty_name_err);
+        }
+        if accounts
+            .acct
+            .iter()
+            .any(|a| a.id != account.id && a.name == new_account_name)
+        {
+            return Err(duplicate_err);
         }
 
+        accounts.acct[account_index].name = new_account_name.to_string();
         Ok(accounts)
     }
 }
 
 trait FindAccount {
-    fn find_account_index(&self, account: &Account) -> usize;
+    fn find_account_index(&self, account: &Account) -> Option<usize>;
+    fn find_account_id_index(&self, account_id: &AccountId) -> Option<usize>;
 }
 
 impl FindAccount for Accounts {
-    fn find_account_index(&self, account: &Account) -> usize {
-        self.acct
+    fn find_account_index(&self, account: &Account) -> Option<usize> {
+        self.find_account_id_index(&account.id)
+    }
+
+    fn find_account_id_index(&self, account_id: &AccountId) -> Option<usize> {
+        self.acct.iter().position(|a| a.id == *account_id)
+    }
+}
+
+// NOTE shells may still send events for an account deleted in the meantime
+trait MissingAccount {
+    fn missing_account(&mut self, account: &Account) -> Command<Effect, Event>;
+}
+
+impl MissingAccount for Model {
+    fn missing_account(&mut self, account: &Account) -> Command<Effect, Event> {
+        self.notification = Notification {
+            title: "Account Error".to_string(),
+            message: Error::set_error("Cannot find account", &account.name, "It doesn't exist.")
+                .to_string(),
+        };
+        render()
+    }
+}
+
+trait ExportAccounts {
+    fn export_accounts(&self, opml_file_name: &OpmlFileName) -> Result<String, Error>;
+}
+
+// NOTE the account type goes in the category and its id in the url, as OPML has no attributes for them
+impl ExportAccounts for Accounts {
+    fn export_accounts(&self, opml_file_name: &OpmlFileName) -> Result<String, Error> {
+        let outlines = self
+            .acct
             .iter()
-            .position(|a| a.name == account.name)
-            .unwrap()
+            .map(|account| Outline {
+                text: account.name.to_string(),
+                title: Some(account.name.to_string()),
+                r#type: Some(ACCOUNT_OUTLINE_TYPE.to_string()),
+                category: Some(Account::account_type_name(&account.account_type)),
+                url: Some(account.id.to_string()),
+                outlines: account.subs.subs.body.outlines.clone(),
+                ..Outline::default()
+            })
+            .collect::<Vec<_>>();
+
+        Subscriptions::export_outlines(opml_file_name, &outlines)
+    }
+}
+
+trait ImportAccounts {
+    fn import_accounts(
+        &self,
+        opml_file_content: &OpmlFileContent,
+    ) -> Result<(Self, ImportReport), Error>
+    where
+        Self: Sized;
+}
+
+// NOTE accounts are matched by id. existing ones are merged into, missing ones are created
+// NOTE folders keep their nesting, as they were exported from the same tree
+impl ImportAccounts for Accounts {
+    fn import_accounts(
+        &self,
+        opml_file_content: &OpmlFileContent,
+    ) -> Result<(Self, ImportReport), Error> {
+        let mut accounts = self.clone();
+        let mut report = ImportReport::default();
+        let opml = OPML::from_str(opml_file_content)?;
+
+        for outline in &opml.body.outlines {
+            let not_an_account_err = Error::set_error(
+                "Cannot import account",
+                outline.text.as_str(),
+                "It isn't an account.",
+            );
+            let duplicate_err = Error::set_error(
+                "Cannot import account",
+                outline.text.as_str(),
+                "An account with this name already exists.",
+            );
+            if outline.r#type.as_deref() != Some(ACCOUNT_OUTLINE_TYPE) {
+                return Err(not_an_account_err);
+            }
+            let Some(account_type) = outline
+                .category
+                .as_deref()
+                .and_then(Account::account_type_from_name)
+            else {
+                return Err(not_an_account_err);
+            };
+
+            let account_id = outline.url.as_deref().map(AccountId::from);
+            let account_index = match account_id
+                .as_ref()
+                .and_then(|account_id| accounts.acct.iter().position(|a| &a.id == account_id))
+            {
+                Some(account_index) => account_index,
+                None if accounts.contains_name(&outline.text) => return Err(duplicate_err),
+                None => {
+                    accounts.acct.push(Account {
+                        id: account_id.unwrap_or_else(AccountId::generate),
+                        name: outline.text.to_string(),
+                        ..Account::new(&account_type)
+                    });
+                    accounts.acct.len() - 1
+                }
+            };
+            let (subs, account_report) = accounts.acct[account_index].subs.import_outlines(
+                &outline.outlines,
+                &ImportMode::Merge,
+                &NestedFolders::Keep,
+            )?;
+
+            accounts.acct[account_index].subs = subs;
+            report.added.extend(account_report.added);
+            report.skipped.extend(account_report.skipped);
+            report.conflicting.extend(account_report.conflicting);
+        }
+
+        Ok((accounts, report))
     }
 }
 
@@ -160,39 +367,71 @@ impl Accounts {
         <Self as CreateAccount>::create_account(self, account_type)
     }
 
+    pub fn create_named(
+        &self,
+        account_type: &AccountType,
+        account_name: &AccountName,
+    ) -> Result<Self, Error> {
+        <Self as CreateAccount>::create_named_account(self, account_type, account_name)
+    }
+
     pub fn delete(&self, account: &Account) -> Self {
         <Self as DeleteAccount>::delete_account(self, account)
     }
 
     pub fn rename(
         &self,
-        old_account_name: &OldAccountName,
+        account: &Account,
         new_account_name: &NewAccountName,
     ) -> Result<Self, Error> {
-        <Self as RenameAccount>::rename_account(self, old_account_name, new_account_name)
+        <Self as RenameAccount>::rename_account(self, account, new_account_name)
     }
 
-    pub fn find_by_index(&self, account: &Account) -> usize {
+    pub fn find_by_index(&self, account: &Account) -> Option<usize> {
         <Self as FindAccount>::find_account_index(self, account)
     }
+
+    pub fn find_by_id(&self, account_id: &AccountId) -> Option<usize> {
+        <Self as FindAccount>::find_account_id_index(self, account_id)
+    }
+
+    pub fn export(&self, opml_file_name: &OpmlFileName) -> Result<String, Error> {
+        <Self as ExportAccounts>::export_accounts(self, opml_file_name)
+    }
+
+    pub fn import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+    ) -> Result<(Self, ImportReport), Error> {
+        <Self as ImportAccounts>::import_accounts(self, opml_file_content)
+    }
+}
+
+impl Model {
+    pub(crate) fn missing_account(&mut self, account: &Account) -> Command<Effect, Event> {
+        <Self as MissingAccount>::missing_account(self, account)
+    }
 }
 
 #[cfg(test)]
 mod accts {
     use super::*;
-    use crate::{App, Event, Model};
+    use crate::fixtures::{add_subscription, fetched, model_with_account, rss_link, EXAMPLE_RSS};
+    use crate::{App, Event, FolderName, Model};
     use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
 
     #[test]
     fn add_new_local_account() {
         let app = App;
         let mut model = Model::default();
-        let account_to_add = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let does_contain_account = model.accounts.acct.contains(&account_to_add);
+        let added_account = &model.accounts.acct[0];
 
-        assert!(does_contain_account);
+        assert_eq!(model.accounts.acct.len(), 1);
+        assert_eq!(added_account.name, "On Device");
+        assert_eq!(added_account.account_type, AccountType::Local);
     }
 
     #[test]
@@ -217,9 +456,9 @@ mod accts {
     fn delete_local_account() {
         let app = App;
         let mut model = Model::default();
-        let account_to_delete = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account_to_delete = model.accounts.acct[0].clone();
         let _ = app.update(
             Event::DeleteAccount(account_to_delete.clone()),
             &mut model,
@@ -235,12 +474,13 @@ mod accts {
     fn add_new_platform_account() {
         let app = App;
         let mut model = Model::default();
-        let account_to_add = Account::new(&AccountType::Apple);
 
         let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
-        let does_contain_account = model.accounts.acct.contains(&account_to_add);
+        let added_account = &model.accounts.acct[0];
 
-        assert!(does_contain_account);
+        assert_eq!(model.accounts.acct.len(), 1);
+        assert_eq!(added_account.name, "iCloud");
+        assert_eq!(added_account.account_type, AccountType::Apple);
     }
 
     #[test]
@@ -265,9 +505,9 @@ mod accts {
     fn delete_platform_account() {
         let app = App;
         let mut model = Model::default();
-        let account_to_delete = Account::new(&AccountType::Apple);
 
         let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+        let account_to_delete = model.accounts.acct[0].clone();
         let _ = app.update(
             Event::DeleteAccount(account_to_delete.clone()),
             &mut model,
@@ -279,16 +519,33 @@ mod accts {
         assert!(!does_contain_account);
     }
 
+    #[test]
+    fn new_account_gets_new_id() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let deleted_account = model.accounts.acct[0].clone();
+        let _ = app.update(
+            Event::DeleteAccount(deleted_account.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+
+        assert_ne!(model.accounts.acct[0].id, deleted_account.id);
+    }
+
     #[test]
     fn rename_platform_account() {
         let app = App;
         let mut model = Model::default();
-        let old_account_name = OldAccountName("iCloud".to_string());
         let new_account_name = NewAccountName("New Name".to_string());
 
         let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+        let account = model.accounts.acct[0].clone();
         let _ = app.update(
-            Event::RenameAccount(old_account_name, new_account_name.clone()),
+            Event::RenameAccount(account, new_account_name.clone()),
             &mut model,
             &(),
         );
@@ -300,15 +557,15 @@ mod accts {
     }
 
     #[test]
-    fn fail_rename_platform_account_with_empty_name() {
+    fn fail_rename_missing_platform_account() {
         let app = App;
         let mut model = Model::default();
-        let old_account_name = OldAccountName("".to_string());
+        let missing_account = Account::new(&AccountType::Apple);
         let new_account_name = NewAccountName("New Name".to_string());
 
         let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
         let _ = app.update(
-            Event::RenameAccount(old_account_name.clone(), new_account_name),
+            Event::RenameAccount(missing_account.clone(), new_account_name),
             &mut model,
             &(),
         );
@@ -316,32 +573,500 @@ mod accts {
         let actual_error = model.notification.message;
         let expected_error = format!(
             "Cannot rename account \"{}\". It doesn't exists.",
-            old_account_name.0.as_str()
+            missing_account.name
         );
 
         assert_eq!(actual_error, expected_error);
+        assert_eq!(model.accounts.acct[0].name, "iCloud");
     }
 
     #[test]
-    fn fail_rename_platform_account_with_wrong_name() {
+    fn fail_rename_deleted_platform_account() {
         let app = App;
         let mut model = Model::default();
-        let old_account_name = OldAccountName("Dada".to_string());
         let new_account_name = NewAccountName("New Name".to_string());
 
         let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+        let deleted_account = model.accounts.acct[0].clone();
         let _ = app.update(
-            Event::RenameAccount(old_account_name.clone(), new_account_name),
+            Event::DeleteAccount(deleted_account.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::RenameAccount(deleted_account, new_account_name),
             &mut model,
             &(),
         );
 
-        let actual_error = model.notification.message;
-        let expected_error = format!(
-            "Cannot rename account \"{}\". It doesn't exists.",
-            old_account_name.0.as_str()
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(
+            model.notification.message,
+            "Cannot rename account \"iCloud\". It doesn't exists."
         );
+    }
 
-        assert_eq!(actual_error, expected_error);
+    fn model_with_named_accounts(app: &App) -> Model {
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::CreateNamedAccount(AccountType::Local, AccountName::from("Work")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::CreateNamedAccount(AccountType::Local, AccountName::from("Personal")),
+            &mut model,
+            &(),
+        );
+
+        model
+    }
+
+    #[test]
+    fn add_named_accounts_of_same_type() {
+        let app = App;
+        let model = model_with_named_accounts(&app);
+        let work = &model.accounts.acct[0];
+        let personal = &model.accounts.acct[1];
+
+        assert_eq!(model.accounts.acct.len(), 2);
+        assert_eq!(work.name, "Work");
+        assert_eq!(personal.name, "Personal");
+        assert_eq!(personal.account_type, AccountType::Local);
+        assert_ne!(personal.id, work.id);
+    }
+
+    #[test]
+    fn fail_named_account_with_existing_name() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+
+        let _ = app.update(
+            Event::CreateNamedAccount(AccountType::Apple, AccountName::from("Work")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct.len(), 2);
+        assert_eq!(
+            model.notification.message,
+            "Cannot add account \"Work\". It already exists."
+        );
+    }
+
+    #[test]
+    fn fail_named_account_with_empty_name() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::CreateNamedAccount(AccountType::Local, AccountName::from("  ")),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(
+            model.notification.message,
+            "Cannot add account \"\". It needs a name."
+        );
+    }
+
+    #[test]
+    fn delete_one_of_same_type() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(Event::DeleteAccount(personal), &mut model, &());
+
+        assert_eq!(model.accounts.acct.len(), 1);
+        assert_eq!(model.accounts.acct[0].name, "Work");
+    }
+
+    #[test]
+    fn rename_keeps_account_id() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(personal.clone(), NewAccountName::from("Home")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[1].name, "Home");
+        assert_eq!(model.accounts.acct[1].id, personal.id);
+        assert_eq!(model.accounts.acct[0].name, "Work");
+    }
+
+    #[test]
+    fn fail_rename_to_existing_name() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(personal, NewAccountName::from("Work")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[1].name, "Personal");
+        assert_eq!(
+            model.notification.message,
+            "Cannot rename account \"Personal\". An account with this name already exists."
+        );
+    }
+
+    #[test]
+    fn fail_rename_account_with_empty_name() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(personal, NewAccountName::from("  ")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[1].name, "Personal");
+        assert_eq!(
+            model.notification.message,
+            "Cannot rename account \"Personal\". It needs a name."
+        );
+    }
+
+    #[test]
+    fn fail_rename_to_existing_name_with_whitespace() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(personal, NewAccountName::from(" Work ")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[1].name, "Personal");
+        assert_eq!(
+            model.notification.message,
+            "Cannot rename account \"Personal\". An account with this name already exists."
+        );
+    }
+
+    #[test]
+    fn rename_account_trims_name() {
+        let app = App;
+        let mut model = model_with_named_accounts(&app);
+        let personal = model.accounts.acct[1].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(personal, NewAccountName::from(" Home ")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[1].name, "Home");
+    }
+
+    #[test]
+    fn fail_select_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        let _ = app.update(Event::SelectAccount(account.clone()), &mut model, &());
+
+        assert_eq!(model.sidebar_selection, None);
+        assert_eq!(
+            model.notification.message,
+            format!(
+                "Cannot find account \"{}\". It doesn't exist.",
+                account.name
+            )
+        );
+    }
+
+    #[test]
+    fn fail_add_folder_to_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        let _ = app.update(
+            Event::AddNewFolder(account.clone(), None, FolderName::from("Tech")),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(
+            model.notification.message,
+            format!(
+                "Cannot find account \"{}\". It doesn't exist.",
+                account.name
+            )
+        );
+    }
+
+    #[test]
+    fn ignore_feed_for_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        fetched(&app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(model.new_entries_count, 0);
+    }
+
+    #[test]
+    fn ignore_validated_subscription_for_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let response = ResponseBuilder::ok()
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        let _ = app.update(
+            Event::SetValidatedSubscription(account, None, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(model.subscription_error, None);
+    }
+}
+
+#[cfg(test)]
+mod all_accounts {
+    use super::*;
+    use crate::{App, Effect, Event, FileOperation, FolderName, FolderPath, Model};
+    use crate::{SubscriptionLink, SubscriptionTitle};
+    use assert_matches::assert_matches;
+    use crux_core::App as _;
+
+    fn model_with_accounts(app: &App) -> Model {
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+        let local = model.accounts.acct[0].clone();
+        let apple = model.accounts.acct[1].clone();
+        let _ = app.update(
+            Event::AddSubscription(
+                local,
+                None,
+                SubscriptionTitle::from("Feed Name"),
+                SubscriptionLink::from("https://example.com/atom.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::AddNewFolder(apple.clone(), None, FolderName::from("Group Name")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::AddSubscription(
+                apple,
+                model.accounts.acct[1]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Group Name")),
+                SubscriptionTitle::from("Feed Name"),
+                SubscriptionLink::from("https://example.com/rss.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+
+        model
+    }
+
+    fn export_all(app: &App, model: &mut Model) -> String {
+        let mut cmd = app.update(
+            Event::ExportAllSubscriptions("All Subscriptions.opml".to_string()),
+            model,
+            &(),
+        );
+        let request = assert_matches!(
+            cmd.effects().next(),
+            Some(Effect::File(request)) => request
+        );
+        let FileOperation::Save { bytes, .. } = request.operation;
+
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn export_all_accounts() {
+        let app = App;
+        let mut model = model_with_accounts(&app);
+
+        let exported_opml = OPML::from_str(&export_all(&app, &mut model)).unwrap();
+        let groups = exported_opml
+            .body
+            .outlines
+            .iter()
+            .map(|group| {
+                (
+                    group.text.as_str(),
+                    group.r#type.as_deref(),
+                    group.category.as_deref(),
+                    group.outlines.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            groups,
+            vec![
+                ("On Device", Some("account"), Some("Local"), 1),
+                ("iCloud", Some("account"), Some("Apple"), 1),
+            ]
+        );
+        assert_eq!(
+            exported_opml.body.outlines[1].outlines,
+            model.accounts.acct[1].subs.subs.body.outlines
+        );
+        assert_eq!(
+            exported_opml.body.outlines[0].url,
+            Some(model.accounts.acct[0].id.to_string())
+        );
+    }
+
+    #[test]
+    fn import_all_accounts() {
+        let app = App;
+        let mut exported_model = model_with_accounts(&app);
+        let opml_file_content = export_all(&app, &mut exported_model);
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::ImportAllSubscriptions(opml_file_content),
+            &mut model,
+            &(),
+        );
+
+        let imported = model
+            .accounts
+            .acct
+            .iter()
+            .map(|account| {
+                (
+                    account.id.clone(),
+                    account.name.as_str(),
+                    account.account_type.clone(),
+                    account.subs.subs.body.outlines.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let exported = exported_model
+            .accounts
+            .acct
+            .iter()
+            .map(|account| {
+                (
+                    account.id.clone(),
+                    account.name.as_str(),
+                    account.account_type.clone(),
+                    account.subs.subs.body.outlines.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(imported, exported);
+        assert_eq!(model.import_report.added.len(), 2);
+    }
+
+    #[test]
+    fn import_all_accounts_merges_existing() {
+        let app = App;
+        let mut model = model_with_accounts(&app);
+        let opml_file_content = export_all(&app, &mut model);
+        let accounts = model.accounts.clone();
+
+        let _ = app.update(
+            Event::ImportAllSubscriptions(opml_file_content),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts, accounts);
+        assert_eq!(model.import_report.skipped.len(), 2);
+    }
+
+    #[test]
+    fn import_all_accounts_matches_by_id() {
+        let app = App;
+        let mut model = model_with_accounts(&app);
+        let opml_file_content = export_all(&app, &mut model);
+        let local = model.accounts.acct[0].clone();
+
+        let _ = app.update(
+            Event::RenameAccount(local, NewAccountName::from("Personal")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ImportAllSubscriptions(opml_file_content),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct.len(), 2);
+        assert_eq!(model.accounts.acct[0].name, "Personal");
+        assert_eq!(model.import_report.skipped.len(), 2);
+    }
+
+    #[test]
+    fn fail_import_all_for_other_account_with_same_name() {
+        let app = App;
+        let mut exported_model = model_with_accounts(&app);
+        let opml_file_content = export_all(&app, &mut exported_model);
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let accounts = model.accounts.clone();
+        let _ = app.update(
+            Event::ImportAllSubscriptions(opml_file_content),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts, accounts);
+        assert_eq!(
+            model.notification.message,
+            "Cannot import account \"On Device\". An account with this name already exists."
+        );
+    }
+
+    #[test]
+    fn fail_import_all_for_plain_opml() {
+        let app = App;
+        let mut model = Model::default();
+        let plain_opml = r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0"><head><title>Subscriptions.opml</title></head><body><outline text="Feed Name" xmlUrl="https://example.com/atom.xml"/></body></opml>"#;
+
+        let _ = app.update(
+            Event::ImportAllSubscriptions(plain_opml.to_string()),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.accounts.acct.is_empty());
+        assert_eq!(
+            model.notification.message,
+            "Cannot import account \"Feed Name\". It isn't an account."
+        );
     }
 }
@@ -0,0 +1,480 @@
+// NOTE - finds the feeds a website offers, so users don't need to know the exact feed link.
+// NOTE - candidates come from <link rel="alternate"> tags and common paths, and only the ones
+// NOTE - feed_rs can parse are offered. The shell adds the chosen one with AddSubscription.
+use super::{Effect, Error, Event, Model, SubscriptionId, SubscriptionLink};
+
+use crux_core::Command;
+use crux_http::command::Http;
+use feed_rs::model::{Feed, FeedType};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use url::Url;
+
+pub(super) const FEED_SCHEMES: [&str; 2] = ["http", "https"];
+const COMMON_FEED_PATHS: [&str; 4] = ["/feed", "/rss.xml", "/atom.xml", "/index.json"];
+const FEED_MIME_TYPES: [&str; 4] = [
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/json",
+];
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct FeedChoice {
+    pub title: String,
+    pub feed_type: String,
+    pub link: SubscriptionLink,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Discovery {
+    pub site_link: SubscriptionLink,
+    pub candidates: Vec<SubscriptionLink>,
+    pub pending: usize, // candidates still being fetched
+    pub choices: Vec<FeedChoice>,
+}
+
+impl Discovery {
+    pub fn is_discovering(&self) -> bool {
+        self.pending > 0
+    }
+}
+
+trait DiscoveryHelpers {
+    fn feed_type_name(feed_type: &FeedType) -> String;
+    fn feed_choice(link: &SubscriptionLink, feed: &Feed) -> FeedChoice;
+    fn candidate_links(site_url: &Url, html: &str) -> Vec<SubscriptionLink>;
+}
+
+impl DiscoveryHelpers for Discovery {
+    fn feed_type_name(feed_type: &FeedType) -> String {
+        match feed_type {
+            FeedType::Atom => "Atom",
+            FeedType::JSON => "JSON Feed",
+            FeedType::RSS0 => "RSS 0.9",
+            FeedType::RSS1 => "RSS 1.0",
+            FeedType::RSS2 => "RSS 2.0",
+        }
+        .to_string()
+    }
+
+    fn feed_choice(link: &SubscriptionLink, feed: &Feed) -> FeedChoice {
+        FeedChoice {
+            title: feed
+                .title
+                .as_ref()
+                .map(|title| title.content.to_string())
+                .unwrap_or_else(|| link.to_string()),
+            feed_type: Self::feed_type_name(&feed.feed_type),
+            link: link.clone(),
+        }
+    }
+
+    // NOTE advertised feeds come first, then the common paths from the site's root
+    // NOTE only Http links are fetched, and each subscription only once however it's written
+    fn candidate_links(site_url: &Url, html: &str) -> Vec<SubscriptionLink> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(r#"link[rel~="alternate"][href]"#).unwrap();
+
+        let advertised = document.select(&selector).filter_map(|element| {
+            let mime_type = element.value().attr("type").unwrap_or_default();
+            FEED_MIME_TYPES
+                .contains(&mime_type.to_lowercase().as_str())
+                .then(|| element.value().attr("href"))
+                .flatten()
+                .and_then(|href| site_url.join(href).ok())
+        });
+        let common = COMMON_FEED_PATHS
+            .iter()
+            .filter_map(|path| site_url.join(path).ok());
+
+        let mut seen: HashSet<SubscriptionId> = HashSet::new();
+        advertised
+            .chain(common)
+            .filter(|url| FEED_SCHEMES.contains(&url.scheme()))
+            .map(|url| SubscriptionLink::from(url.as_str()))
+            .filter(|link| seen.insert(SubscriptionId::from(link)))
+            .collect()
+    }
+}
+
+trait DiscoverFeeds {
+    fn discover_feeds(
+        &mut self,
+        site_link: &SubscriptionLink,
+    ) -> Result<Command<Effect, Event>, Error>;
+    fn site_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Command<Effect, Event>;
+    fn candidate_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        link: &SubscriptionLink,
+        body: Option<Vec<u8>>,
+    ) -> Result<(), Error>;
+}
+
+impl DiscoverFeeds for Model {
+    fn discover_feeds(
+        &mut self,
+        site_link: &SubscriptionLink,
+    ) -> Result<Command<Effect, Event>, Error> {
+        let invalid_link = Error::set_error(
+            "Cannot discover feeds for",
+            site_link.as_ref(),
+            "It isn't a valid link.",
+        );
+
+        if !Url::parse(site_link.as_ref()).is_ok_and(|url| FEED_SCHEMES.contains(&url.scheme())) {
+            return Err(invalid_link);
+        }
+
+        self.discovery = Discovery {
+            site_link: site_link.clone(),
+            pending: 1,
+            ..Discovery::default()
+        };
+
+        let site_link = site_link.clone();
+        Ok(Http::get(&site_link)
+            .build()
+            .then_send(move |result| Event::SetDiscoveredSite(site_link, result)))
+    }
+
+    // NOTE the link may already be a feed, then there's nothing else to discover
+    fn site_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Command<Effect, Event> {
+        self.discovery.pending = 0;
+
+        if let Ok(feed) = feed_rs::parser::parse(&*body) {
+            self.discovery.candidates = vec![site_link.clone()];
+            self.discovery.choices = vec![Discovery::feed_choice(site_link, &feed)];
+            return Command::done();
+        }
+
+        let Ok(site_url) = Url::parse(site_link.as_ref()) else {
+            return Command::done();
+        };
+        let candidates = Discovery::candidate_links(&site_url, &String::from_utf8_lossy(&body));
+
+        self.discovery.pending = candidates.len();
+        self.discovery.candidates = candidates.clone();
+
+        Command::all(candidates.into_iter().map(|link| {
+            let site_link = site_link.clone();
+            Http::get(&link)
+                .build()
+                .then_send(move |result| Event::SetDiscoveredFeed(site_link, link, result))
+        }))
+    }
+
+    fn candidate_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        link: &SubscriptionLink,
+        body: Option<Vec<u8>>,
+    ) -> Result<(), Error> {
+        let no_feeds_found = Error::set_error(
+            "Cannot find feeds",
+            site_link.as_ref(),
+            "The site has none.",
+        );
+        let discovery = &mut self.discovery;
+
+        discovery.pending = discovery.pending.saturating_sub(1);
+        if let Some(feed) = body.and_then(|body| feed_rs::parser::parse(&*body).ok()) {
+            discovery.choices.push(Discovery::feed_choice(link, &feed));
+            // NOTE responses arrive in any order, choices keep the candidates' one
+            let candidates = &discovery.candidates;
+            discovery.choices.sort_by_key(|choice| {
+                candidates
+                    .iter()
+                    .position(|candidate| *candidate == choice.link)
+            });
+        }
+
+        if !discovery.is_discovering() && discovery.choices.is_empty() {
+            return Err(no_feeds_found);
+        }
+        Ok(())
+    }
+}
+
+impl Model {
+    pub(crate) fn discover_feeds(
+        &mut self,
+        site_link: &SubscriptionLink,
+    ) -> Result<Command<Effect, Event>, Error> {
+        <Self as DiscoverFeeds>::discover_feeds(self, site_link)
+    }
+
+    pub(crate) fn site_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Command<Effect, Event> {
+        <Self as DiscoverFeeds>::site_fetched(self, site_link, body)
+    }
+
+    pub(crate) fn candidate_fetched(
+        &mut self,
+        site_link: &SubscriptionLink,
+        link: &SubscriptionLink,
+        body: Option<Vec<u8>>,
+    ) -> Result<(), Error> {
+        <Self as DiscoverFeeds>::candidate_fetched(self, site_link, link, body)
+    }
+}
+
+#[cfg(test)]
+mod feed_discovery {
+    use super::*;
+    use crate::fixtures::{EXAMPLE_ATOM, EXAMPLE_RSS};
+    use crate::App;
+    use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
+
+    const EXAMPLE_HTML: &str = r#"<!DOCTYPE html><html><head><title>Example</title><link rel="alternate" type="application/rss+xml" title="Example RSS" href="/blog/rss.xml"><link rel="alternate" type="application/atom+xml" href="https://example.com/atom.xml"><link rel="stylesheet" href="/style.css"></head><body></body></html>"#;
+
+    fn site_link() -> SubscriptionLink {
+        SubscriptionLink::from("https://example.com/")
+    }
+
+    fn response(body: &str) -> crux_http::Result<crux_http::Response<Vec<u8>>> {
+        Ok(ResponseBuilder::ok().body(body.as_bytes().to_vec()).build())
+    }
+
+    fn http_urls(cmd: &mut Command<Effect, Event>) -> Vec<String> {
+        cmd.effects()
+            .filter_map(|effect| match effect {
+                Effect::Http(request) => Some(request.operation.url),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn discovered_site(app: &App) -> Model {
+        let mut model = Model::default();
+
+        let _ = app.update(Event::DiscoverFeeds(site_link()), &mut model, &());
+        let _ = app.update(
+            Event::SetDiscoveredSite(site_link(), response(EXAMPLE_HTML)),
+            &mut model,
+            &(),
+        );
+        model
+    }
+
+    #[test]
+    fn fetch_site_page() {
+        let app = App;
+        let mut model = Model::default();
+
+        let mut cmd = app.update(Event::DiscoverFeeds(site_link()), &mut model, &());
+
+        assert_eq!(
+            http_urls(&mut cmd),
+            vec!["https://example.com/".to_string()]
+        );
+        assert!(app.view(&model).discovery.is_discovering());
+    }
+
+    #[test]
+    fn fail_discovery_for_invalid_link() {
+        let app = App;
+        let mut model = Model::default();
+
+        let mut cmd = app.update(
+            Event::DiscoverFeeds(SubscriptionLink::from("not a link")),
+            &mut model,
+            &(),
+        );
+
+        assert!(http_urls(&mut cmd).is_empty());
+        assert_eq!(
+            model.notification.message,
+            "Cannot discover feeds for \"not a link\". It isn't a valid link."
+        );
+    }
+
+    #[test]
+    fn fetch_advertised_and_common_candidates() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::DiscoverFeeds(site_link()), &mut model, &());
+        let mut cmd = app.update(
+            Event::SetDiscoveredSite(site_link(), response(EXAMPLE_HTML)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            http_urls(&mut cmd),
+            vec![
+                "https://example.com/blog/rss.xml".to_string(),
+                "https://example.com/atom.xml".to_string(),
+                "https://example.com/feed".to_string(),
+                "https://example.com/rss.xml".to_string(),
+                "https://example.com/index.json".to_string(),
+            ]
+        );
+        assert_eq!(model.discovery.pending, 5);
+    }
+
+    #[test]
+    fn skip_non_http_and_duplicate_candidates() {
+        let app = App;
+        let mut model = Model::default();
+        let html = r#"<!DOCTYPE html><html><head><link rel="alternate" type="application/rss+xml" href="ftp://example.com/rss.xml"><link rel="alternate" type="application/rss+xml" href="javascript:alert(1)"><link rel="alternate" type="application/atom+xml" href="HTTPS://EXAMPLE.COM/atom.xml"><link rel="alternate" type="application/atom+xml" href="https://example.com:443/atom.xml"></head><body></body></html>"#;
+
+        let _ = app.update(Event::DiscoverFeeds(site_link()), &mut model, &());
+        let mut cmd = app.update(
+            Event::SetDiscoveredSite(site_link(), response(html)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            http_urls(&mut cmd),
+            vec![
+                "https://example.com/atom.xml".to_string(),
+                "https://example.com/feed".to_string(),
+                "https://example.com/rss.xml".to_string(),
+                "https://example.com/index.json".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_discovery_for_non_http_link() {
+        let app = App;
+        let mut model = Model::default();
+
+        let mut cmd = app.update(
+            Event::DiscoverFeeds(SubscriptionLink::from("file:///etc/passwd")),
+            &mut model,
+            &(),
+        );
+
+        assert!(http_urls(&mut cmd).is_empty());
+        assert_eq!(
+            model.notification.message,
+            "Cannot discover feeds for \"file:///etc/passwd\". It isn't a valid link."
+        );
+    }
+
+    #[test]
+    fn offer_only_parsable_candidates_in_order() {
+        let app = App;
+        let mut model = discovered_site(&app);
+        let candidates = model.discovery.candidates.clone();
+
+        for (link, body) in [
+            (&candidates[1], response(EXAMPLE_ATOM)),
+            (&candidates[0], response(EXAMPLE_RSS)),
+            (&candidates[2], response(EXAMPLE_HTML)),
+        ] {
+            let _ = app.update(
+                Event::SetDiscoveredFeed(site_link(), link.clone(), body),
+                &mut model,
+                &(),
+            );
+        }
+        for link in &candidates[3..] {
+            let _ = app.update(
+                Event::SetDiscoveredFeed(
+                    site_link(),
+                    link.clone(),
+                    Err(crux_http::HttpError::Io("not found".to_string())),
+                ),
+                &mut model,
+                &(),
+            );
+        }
+        let discovery = app.view(&model).discovery;
+
+        assert!(!discovery.is_discovering());
+        assert_eq!(
+            discovery.choices,
+            vec![
+                FeedChoice {
+                    title: "Feed Name".to_string(),
+                    feed_type: "RSS 2.0".to_string(),
+                    link: SubscriptionLink::from("https://example.com/blog/rss.xml"),
+                },
+                FeedChoice {
+                    title: "Atom Name".to_string(),
+                    feed_type: "Atom".to_string(),
+                    link: SubscriptionLink::from("https://example.com/atom.xml"),
+                },
+            ]
+        );
+        assert_eq!(model.notification.title, "");
+    }
+
+    #[test]
+    fn site_link_is_already_a_feed() {
+        let app = App;
+        let mut model = Model::default();
+        let feed_link = SubscriptionLink::from("https://example.com/rss.xml");
+
+        let _ = app.update(Event::DiscoverFeeds(feed_link.clone()), &mut model, &());
+        let mut cmd = app.update(
+            Event::SetDiscoveredSite(feed_link.clone(), response(EXAMPLE_RSS)),
+            &mut model,
+            &(),
+        );
+
+        assert!(http_urls(&mut cmd).is_empty());
+        assert_eq!(model.discovery.choices.len(), 1);
+        assert_eq!(model.discovery.choices[0].link, feed_link);
+    }
+
+    #[test]
+    fn fail_discovery_without_feeds() {
+        let app = App;
+        let mut model = discovered_site(&app);
+        let candidates = model.discovery.candidates.clone();
+
+        for link in candidates {
+            let _ = app.update(
+                Event::SetDiscoveredFeed(site_link(), link, response(EXAMPLE_HTML)),
+                &mut model,
+                &(),
+            );
+        }
+
+        assert_eq!(
+            model.notification.message,
+            "Cannot find feeds \"https://example.com/\". The site has none."
+        );
+    }
+
+    #[test]
+    fn ignore_responses_from_earlier_discovery() {
+        let app = App;
+        let mut model = discovered_site(&app);
+        let candidates = model.discovery.candidates.clone();
+
+        let _ = app.update(
+            Event::DiscoverFeeds(SubscriptionLink::from("https://example.org/")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::SetDiscoveredFeed(site_link(), candidates[0].clone(), response(EXAMPLE_RSS)),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.discovery.choices.is_empty());
+        assert_eq!(model.discovery.pending, 1);
+    }
+}
@@ -0,0 +1,715 @@
+// NOTE - the entry list for the sidebar selection and the article for the selected entry.
+// NOTE - dates are formatted here once, so every shell shows them the same way.
+use super::{Account, AccountId, Accounts, EntryId, EntryStatus, Feeds, Model, SidebarItem};
+use super::{SubscriptionId, SubscriptionLink};
+
+use chrono::{DateTime, Local, Utc};
+use feed_rs::model::{Entry, Feed};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+// NOTE in the local time zone, e.g. "5 Oct 2025, 09:30"
+pub const ENTRY_DATE_FORMAT: &str = "%-d %b %Y, %H:%M";
+pub const ENTRY_PAGE_SIZE: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct SelectedEntry {
+    pub account_id: AccountId,
+    pub entry_id: EntryId,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct EntryListItem {
+    pub entry_id: EntryId,
+    pub feed_title: String,
+    pub title: String,
+    pub author: String, // authors joined with ", ", empty when the feed doesn't say
+    pub date: String,   // published, or updated if never published
+    pub summary: String, // as the feed sent it, may hold HTML
+    pub entry_status: EntryStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct EntryListView {
+    pub entries: Vec<EntryListItem>, // newest first, only the current page
+    pub page: usize,                 // zero-based
+    pub page_count: usize,
+    pub total_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ArticleLink {
+    pub href: String,
+    pub rel: Option<String>,
+    pub title: Option<String>,
+    pub media_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ArticleEnclosure {
+    pub url: String,
+    pub media_type: Option<String>,
+    pub length: Option<u64>, // in bytes
+}
+
+// NOTE the content of the selected entry, converted once when it's selected or its feed changes
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ArticleContent {
+    pub entry_id: EntryId,
+    pub html: String, // sanitised, with absolute links
+    pub text: String,
+    pub markdown: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ArticleView {
+    pub entry_id: EntryId,
+    pub feed_title: String,
+    pub title: String,
+    pub author: String,
+    pub published: String,    // empty when the feed doesn't say
+    pub updated: String,      // empty when the feed doesn't say
+    pub content_html: String, // sanitised, with absolute links
+    pub content_text: String, // the content as plain text, links listed at the end
+    pub content_markdown: String,
+    pub links: Vec<ArticleLink>,
+    pub enclosures: Vec<ArticleEnclosure>,
+    pub entry_status: EntryStatus,
+}
+
+trait EntryHelpers {
+    fn format_date(date: &DateTime<Utc>) -> String;
+    fn entry_date(entry: &Entry) -> Option<DateTime<Utc>>;
+    fn feed_title(feed: &Feed) -> String;
+    fn entry_title(entry: &Entry) -> String;
+    fn entry_author(entry: &Entry) -> String;
+    fn entry_base(feed: &Feed, entry: &Entry) -> Option<Url>;
+}
+
+impl EntryHelpers for Account {
+    fn format_date(date: &DateTime<Utc>) -> String {
+        date.with_timezone(&Local)
+            .format(ENTRY_DATE_FORMAT)
+            .to_string()
+    }
+
+    // NOTE feeds without a published date fall back to the updated one
+    fn entry_date(entry: &Entry) -> Option<DateTime<Utc>> {
+        entry.published.or(entry.updated)
+    }
+
+    fn feed_title(feed: &Feed) -> String {
+        feed.title
+            .as_ref()
+            .map(|title| title.content.to_string())
+            .unwrap_or_default()
+    }
+
+    fn entry_title(entry: &Entry) -> String {
+        entry
+            .title
+            .as_ref()
+            .map(|title| title.content.to_string())
+            .unwrap_or_default()
+    }
+
+    fn entry_author(entry: &Entry) -> String {
+        entry
+            .authors
+            .iter()
+            .map(|person| person.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+
+    // NOTE the content's xml:base, then the entry's own link, then the feed's website
+    fn entry_base(feed: &Feed, entry: &Entry) -> Option<Url> {
+        entry
+            .base
+            .iter()
+            .chain(entry.links.iter().map(|link| &link.href))
+            .chain(feed.links.iter().map(|link| &link.href))
+            .find_map(|href| Url::parse(href).ok())
+    }
+}
+
+trait SelectedEntries {
+    fn selected_links(&self, selected: &SidebarItem) -> Vec<SubscriptionLink>;
+    fn selected_entries(&self, selected: &SidebarItem) -> Vec<(&Feed, &Entry)>;
+}
+
+impl SelectedEntries for Account {
+    // NOTE folders list their subfolders too. a missing folder or subscription lists nothing.
+    fn selected_links(&self, selected: &SidebarItem) -> Vec<SubscriptionLink> {
+        match selected {
+            SidebarItem::Account(_) => self.subs.list_subscription_links(),
+            SidebarItem::Folder(_, folder_id) => self
+                .subs
+                .list_folder_subscription_links(folder_id)
+                .unwrap_or_default(),
+            SidebarItem::Subscription(_, sub_id) => self
+                .subs
+                .find_subscription_link(sub_id)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn selected_entries(&self, selected: &SidebarItem) -> Vec<(&Feed, &Entry)> {
+        let feeds = &self.subs.feeds;
+        let mut entries: Vec<(&Feed, &Entry)> = self
+            .selected_links(selected)
+            .iter()
+            .filter_map(|sub_link| feeds.feeds.get(&SubscriptionId::from(sub_link)))
+            .flat_map(|feed| feed.entries.iter().map(move |entry| (feed, entry)))
+            .collect();
+
+        // NOTE newest first, undated entries last
+        entries.sort_by(|(_, a), (_, b)| Self::entry_date(b).cmp(&Self::entry_date(a)));
+        entries
+    }
+}
+
+trait EntryListViewBuilder {
+    fn entry_list_view(
+        accounts: &Accounts,
+        selected: &Option<SidebarItem>,
+        page: usize,
+    ) -> EntryListView;
+}
+
+impl EntryListViewBuilder for EntryListView {
+    fn entry_list_view(
+        accounts: &Accounts,
+        selected: &Option<SidebarItem>,
+        page: usize,
+    ) -> EntryListView {
+        let Some((account, selected)) = selected.as_ref().and_then(|selected| {
+            let account_id = match selected {
+                SidebarItem::Account(account_id)
+                | SidebarItem::Folder(account_id, _)
+                | SidebarItem::Subscription(account_id, _) => account_id,
+            };
+            accounts
+                .acct
+                .iter()
+                .find(|account| &account.id == account_id)
+                .map(|account| (account, selected))
+        }) else {
+            return EntryListView::default();
+        };
+
+        let feeds = &account.subs.feeds;
+        let entries = account.selected_entries(selected);
+        let total_count = entries.len();
+        let page_count = total_count.div_ceil(ENTRY_PAGE_SIZE);
+        // NOTE a page past the end, e.g. after entries were removed, shows the last one
+        let page = page.min(page_count.saturating_sub(1));
+
+        EntryListView {
+            entries: entries
+                .iter()
+                .skip(page * ENTRY_PAGE_SIZE)
+                .take(ENTRY_PAGE_SIZE)
+                .map(|(feed, entry)| {
+                    let entry_id = EntryId::from(entry.id.as_str());
+
+                    EntryListItem {
+                        entry_status: feeds.find_entry_status(&entry_id),
+                        entry_id,
+                        feed_title: Account::feed_title(feed),
+                        title: Account::entry_title(entry),
+                        author: Account::entry_author(entry),
+                        date: Account::entry_date(entry)
+                            .as_ref()
+                            .map(Account::format_date)
+                            .unwrap_or_default(),
+                        summary: entry
+                            .summary
+                            .as_ref()
+                            .map(|summary| summary.content.to_string())
+                            .unwrap_or_default(),
+                    }
+                })
+                .collect(),
+            page,
+            page_count,
+            total_count,
+        }
+    }
+}
+
+trait ArticleViewBuilder {
+    fn find_entry<'a>(
+        accounts: &'a Accounts,
+        selected: &SelectedEntry,
+    ) -> Option<(&'a Feeds, &'a Feed, &'a Entry)>;
+    fn article_content(accounts: &Accounts, selected: &SelectedEntry) -> Option<ArticleContent>;
+    fn article_view(
+        accounts: &Accounts,
+        selected: &SelectedEntry,
+        content: &Option<ArticleContent>,
+    ) -> Option<ArticleView>;
+}
+
+impl ArticleViewBuilder for ArticleView {
+    fn find_entry<'a>(
+        accounts: &'a Accounts,
+        selected: &SelectedEntry,
+    ) -> Option<(&'a Feeds, &'a Feed, &'a Entry)> {
+        let account = accounts
+            .acct
+            .iter()
+            .find(|account| account.id == selected.account_id)?;
+        let feeds = &account.subs.feeds;
+        feeds
+            .feeds
+            .values()
+            .flat_map(|feed| feed.entries.iter().map(move |entry| (feed, entry)))
+            .find(|(_, entry)| entry.id == selected.entry_id.to_string())
+            .map(|(feed, entry)| (feeds, feed, entry))
+    }
+
+    // NOTE feeds that only send a summary show it as the content
+    fn article_content(accounts: &Accounts, selected: &SelectedEntry) -> Option<ArticleContent> {
+        let (_, feed, entry) = Self::find_entry(accounts, selected)?;
+        let html = ArticleView::sanitise_html(
+            &entry
+                .content
+                .as_ref()
+                .and_then(|content| content.body.clone())
+                .or_else(|| {
+                    entry
+                        .summary
+                        .as_ref()
+                        .map(|summary| summary.content.to_string())
+                })
+                .unwrap_or_default(),
+            &Account::entry_base(feed, entry),
+        );
+
+        Some(ArticleContent {
+            entry_id: selected.entry_id.clone(),
+            text: ArticleView::html_to_text(&html),
+            markdown: ArticleView::html_to_markdown(&html),
+            html,
+        })
+    }
+
+    fn article_view(
+        accounts: &Accounts,
+        selected: &SelectedEntry,
+        content: &Option<ArticleContent>,
+    ) -> Option<ArticleView> {
+        let (feeds, feed, entry) = Self::find_entry(accounts, selected)?;
+        let content = content
+            .as_ref()
+            .filter(|content| content.entry_id == selected.entry_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let links = entry
+            .links
+            .iter()
+            .map(|link| ArticleLink {
+                href: link.href.to_string(),
+                rel: link.rel.clone(),
+                title: link.title.clone(),
+                media_type: link.media_type.clone(),
+            })
+            .collect::<Vec<ArticleLink>>();
+
+        // NOTE RSS enclosures arrive as media content, Atom ones as links
+        let mut enclosures: Vec<ArticleEnclosure> = entry
+            .media
+            .iter()
+            .flat_map(|media| media.content.iter())
+            .filter_map(|content| {
+                content.url.as_ref().map(|url| ArticleEnclosure {
+                    url: url.to_string(),
+                    media_type: content.content_type.as_ref().map(|mime| mime.to_string()),
+                    length: content.size,
+                })
+            })
+            .collect();
+        for link in entry
+            .links
+            .iter()
+            .filter(|link| link.rel.as_deref() == Some("enclosure"))
+        {
+            if !enclosures
+                .iter()
+                .any(|enclosure| enclosure.url == link.href)
+            {
+                enclosures.push(ArticleEnclosure {
+                    url: link.href.to_string(),
+                    media_type: link.media_type.clone(),
+                    length: link.length,
+                });
+            }
+        }
+
+        Some(ArticleView {
+            entry_id: selected.entry_id.clone(),
+            feed_title: Account::feed_title(feed),
+            title: Account::entry_title(entry),
+            author: Account::entry_author(entry),
+            published: entry
+                .published
+                .as_ref()
+                .map(Account::format_date)
+                .unwrap_or_default(),
+            updated: entry
+                .updated
+                .as_ref()
+                .map(Account::format_date)
+                .unwrap_or_default(),
+            content_html: content.html,
+            content_text: content.text,
+            content_markdown: content.markdown,
+            links,
+            enclosures,
+            entry_status: feeds.find_entry_status(&selected.entry_id),
+        })
+    }
+}
+
+impl Account {
+    pub(super) fn format_date(date: &DateTime<Utc>) -> String {
+        <Self as EntryHelpers>::format_date(date)
+    }
+
+    pub(super) fn entry_date(entry: &Entry) -> Option<DateTime<Utc>> {
+        <Self as EntryHelpers>::entry_date(entry)
+    }
+
+    pub(super) fn feed_title(feed: &Feed) -> String {
+        <Self as EntryHelpers>::feed_title(feed)
+    }
+
+    pub(super) fn entry_title(entry: &Entry) -> String {
+        <Self as EntryHelpers>::entry_title(entry)
+    }
+}
+
+impl EntryListView {
+    pub fn new(accounts: &Accounts, selected: &Option<SidebarItem>, page: usize) -> Self {
+        <Self as EntryListViewBuilder>::entry_list_view(accounts, selected, page)
+    }
+}
+
+impl ArticleView {
+    pub fn new(
+        accounts: &Accounts,
+        selected: &Option<SelectedEntry>,
+        content: &Option<ArticleContent>,
+    ) -> Option<Self> {
+        selected.as_ref().and_then(|selected| {
+            <Self as ArticleViewBuilder>::article_view(accounts, selected, content)
+        })
+    }
+}
+
+impl Model {
+    pub(crate) fn cache_article(&mut self) {
+        self.article_content = self.selected_entry.as_ref().and_then(|selected| {
+            <ArticleView as ArticleViewBuilder>::article_content(&self.accounts, selected)
+        });
+    }
+}
+
+#[cfg(test)]
+mod entry_views {
+    use super::*;
+    use crate::fixtures::{add_folder, add_subscription, atom_link, fetched, model_with_account};
+    use crate::fixtures::{folder_id, rss_link, EXAMPLE_ATOM, EXAMPLE_RSS};
+    use crate::{App, Event, FolderId, FolderPath, Model};
+    use crate::{ReadStatus, SmartFeed, SubscriptionId};
+    use crux_core::App as _;
+
+    fn tech_id(model: &Model) -> FolderId {
+        folder_id(model, &FolderPath::from("Tech"))
+    }
+
+    fn formatted(date: &str) -> String {
+        DateTime::parse_from_rfc3339(date)
+            .unwrap()
+            .with_timezone(&Local)
+            .format(ENTRY_DATE_FORMAT)
+            .to_string()
+    }
+
+    // NOTE the RSS feed is in the Tech folder, the Atom one at the root
+    fn model_with_entries(app: &App) -> (Model, Account) {
+        let (mut model, account) = model_with_account(app);
+        let folder_id = add_folder(app, &mut model, &account, None, "Tech");
+
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(folder_id),
+            "Feed Name",
+            &rss_link(),
+        );
+        add_subscription(app, &mut model, &account, None, "Atom Name", &atom_link());
+        fetched(app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+        fetched(app, &mut model, &account, &atom_link(), EXAMPLE_ATOM);
+
+        (model, account)
+    }
+
+    #[test]
+    fn list_subscription_entries_newest_first() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(
+            Event::SelectSubscription(account.clone(), SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model).entry_list;
+
+        assert_eq!(
+            model.sidebar_selection,
+            Some(SidebarItem::Subscription(
+                account.id,
+                SubscriptionId::from(&rss_link())
+            ))
+        );
+        assert_eq!(view.total_count, 2);
+        assert_eq!(view.entries[0].title, "Second Entry");
+        assert_eq!(
+            view.entries[1],
+            EntryListItem {
+                entry_id: EntryId::from("https://example.com/one"),
+                feed_title: "Feed Name".to_string(),
+                title: "First Entry".to_string(),
+                author: "Jane Doe".to_string(),
+                date: formatted("2025-10-05T09:30:00Z"),
+                summary: "First summary".to_string(),
+                entry_status: EntryStatus::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn list_folder_and_account_entries() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(
+            Event::SelectFolder(account.clone(), tech_id(&model)),
+            &mut model,
+            &(),
+        );
+        let folder_view = app.view(&model).entry_list;
+        let _ = app.update(Event::SelectAccount(account), &mut model, &());
+        let account_view = app.view(&model).entry_list;
+
+        assert_eq!(folder_view.total_count, 2);
+        assert_eq!(account_view.total_count, 3);
+        assert_eq!(account_view.entries[0].title, "Three");
+        assert_eq!(account_view.entries[0].feed_title, "Atom Name");
+    }
+
+    #[test]
+    fn page_entries() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+        let items: String = (0..ENTRY_PAGE_SIZE + 5)
+            .map(|index| {
+                format!(
+                    "<item><guid>https://example.com/{index}</guid><title>{index}</title></item>"
+                )
+            })
+            .collect();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description>{items}</channel></rss>"#
+        );
+        fetched(&app, &mut model, &account, &rss_link(), &body);
+
+        let _ = app.update(
+            Event::SelectSubscription(account, SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::SelectEntryPage(1), &mut model, &());
+        let view = app.view(&model).entry_list;
+
+        assert_eq!(view.page, 1);
+        assert_eq!(view.page_count, 2);
+        // NOTE the two entries fetched before are kept
+        assert_eq!(view.total_count, ENTRY_PAGE_SIZE + 7);
+        assert_eq!(view.entries.len(), 7);
+    }
+
+    #[test]
+    fn clamp_page_past_the_end() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(Event::SelectAccount(account), &mut model, &());
+        let _ = app.update(Event::SelectEntryPage(3), &mut model, &());
+        let view = app.view(&model).entry_list;
+
+        assert_eq!(view.page, 0);
+        assert_eq!(view.entries.len(), 3);
+    }
+
+    #[test]
+    fn reset_page_on_new_selection() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(Event::SelectEntryPage(2), &mut model, &());
+        let _ = app.update(Event::SelectAccount(account), &mut model, &());
+
+        assert_eq!(model.entry_page, 0);
+    }
+
+    #[test]
+    fn show_selected_article() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+        let entry_id = EntryId::from("urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+
+        let _ = app.update(
+            Event::SelectEntry(account, entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let article = app.view(&model).article.unwrap();
+
+        assert_eq!(article.entry_id, entry_id);
+        assert_eq!(article.feed_title, "Atom Name");
+        assert_eq!(article.title, "Three");
+        assert_eq!(article.author, "John Doe");
+        assert_eq!(article.updated, formatted("2025-10-07T09:00:00Z"));
+        assert_eq!(article.content_html, "<p>Full content</p>");
+        assert_eq!(article.links.len(), 2);
+        assert_eq!(article.links[0].href, "https://example.com/three");
+        assert_eq!(
+            article.enclosures,
+            vec![ArticleEnclosure {
+                url: "https://example.com/three.mp4".to_string(),
+                media_type: Some("video/mp4".to_string()),
+                length: Some(2048),
+            }]
+        );
+        assert_eq!(article.entry_status.read, ReadStatus::Read);
+    }
+
+    #[test]
+    fn show_rss_enclosure_and_summary() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(
+            Event::SelectEntry(account, EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+        let article = app.view(&model).article.unwrap();
+
+        assert_eq!(article.published, formatted("2025-10-05T09:30:00Z"));
+        assert_eq!(article.content_html, "First summary");
+        assert_eq!(
+            article.enclosures,
+            vec![ArticleEnclosure {
+                url: "https://example.com/one.mp3".to_string(),
+                media_type: Some("audio/mpeg".to_string()),
+                length: Some(1024),
+            }]
+        );
+    }
+
+    #[test]
+    fn cache_article_content_until_its_feed_changes() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+        let updated_rss = EXAMPLE_RSS.replace("First summary", "Updated summary");
+
+        let _ = app.update(
+            Event::SelectEntry(account.clone(), EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+        let cached = model.article_content.clone().unwrap();
+
+        assert_eq!(cached.html, "First summary");
+        assert_eq!(cached.text.trim(), "First summary");
+
+        fetched(&app, &mut model, &account, &rss_link(), &updated_rss);
+
+        assert_eq!(
+            model
+                .article_content
+                .as_ref()
+                .map(|content| content.html.as_str()),
+            Some("Updated summary")
+        );
+        assert_eq!(
+            app.view(&model).article.unwrap().content_markdown.trim(),
+            "Updated summary"
+        );
+    }
+
+    #[test]
+    fn fail_select_missing_entry() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(
+            Event::SelectEntry(account, EntryId::from("missing")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.selected_entry, None);
+        assert_eq!(model.notification.title, "Entry Error");
+        assert_eq!(
+            model.notification.message,
+            "Cannot select entry \"missing\". It doesn't exist."
+        );
+    }
+
+    #[test]
+    fn fail_select_missing_folder() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(
+            Event::SelectFolder(account, FolderId::from("missing")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.sidebar_selection, None);
+        assert_eq!(
+            model.notification.message,
+            "Cannot select folder \"missing\". It doesn't exist."
+        );
+    }
+
+    #[test]
+    fn switch_between_smart_feed_and_sidebar() {
+        let app = App;
+        let (mut model, account) = model_with_entries(&app);
+
+        let _ = app.update(Event::SelectAccount(account.clone()), &mut model, &());
+        let _ = app.update(
+            Event::SelectSmartFeed(SmartFeed::AllUnread),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.sidebar_selection, None);
+        assert!(app.view(&model).entry_list.entries.is_empty());
+
+        let _ = app.update(Event::SelectAccount(account), &mut model, &());
+
+        assert_eq!(model.smart_feed, None);
+    }
+}
@@ -0,0 +1,94 @@
+// NOTE - for shells that can't show HTML, e.g. a terminal UI, notifications and widgets.
+// NOTE - converts the sanitised HTML, so links are already absolute and pixels gone.
+use super::ArticleView;
+
+// NOTE lines aren't wrapped, each shell wraps to its own width
+const UNWRAPPED_WIDTH: usize = usize::MAX;
+
+trait ConvertHtml {
+    fn html_to_text(html: &str) -> String;
+    fn html_to_markdown(html: &str) -> String;
+}
+
+impl ConvertHtml for ArticleView {
+    // NOTE links become numbered references listed at the end, e.g. "[post][1]"
+    fn html_to_text(html: &str) -> String {
+        html2text::config::plain_no_decorate()
+            .link_footnotes(true)
+            .string_from_read(html.as_bytes(), UNWRAPPED_WIDTH)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    fn html_to_markdown(html: &str) -> String {
+        html2md::parse_html(html).trim().to_string()
+    }
+}
+
+impl ArticleView {
+    pub fn html_to_text(html: &str) -> String {
+        <Self as ConvertHtml>::html_to_text(html)
+    }
+
+    pub fn html_to_markdown(html: &str) -> String {
+        <Self as ConvertHtml>::html_to_markdown(html)
+    }
+}
+
+#[cfg(test)]
+mod converted_content {
+    use super::*;
+    use crate::fixtures::{add_subscription, fetched, model_with_account, rss_link, UNSAFE_RSS};
+    use crate::{App, EntryId, Event};
+    use crux_core::App as _;
+
+    const EXAMPLE_HTML: &str = r#"<h2>Heading</h2><p>Read the <a href="https://example.com/post">post</a>.</p><ul><li>One</li><li>Two</li></ul><ol><li>First</li></ol><pre><code>fn main() {
+    println!("hi");
+}</code></pre>"#;
+
+    #[test]
+    fn convert_to_plain_text() {
+        assert_eq!(
+            ArticleView::html_to_text(EXAMPLE_HTML),
+            "## Heading\n\nRead the [post][1].\n* One\n* Two\n1. First\n\nfn main() {\n    println!(\"hi\");\n}\n\n[1]: https://example.com/post"
+        );
+    }
+
+    #[test]
+    fn convert_to_markdown() {
+        assert_eq!(
+            ArticleView::html_to_markdown(EXAMPLE_HTML),
+            "Heading\n----------\n\nRead the [post](https://example.com/post).\n\n* One\n* Two\n\n1. First\n\n```\nfn main() {\n    println!(\"hi\");\n}\n```"
+        );
+    }
+
+    #[test]
+    fn convert_empty_content() {
+        assert_eq!(ArticleView::html_to_text(""), "");
+        assert_eq!(ArticleView::html_to_markdown(""), "");
+    }
+
+    #[test]
+    fn convert_sanitised_article_content() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        fetched(&app, &mut model, &account, &rss_link(), UNSAFE_RSS);
+        let _ = app.update(
+            Event::SelectEntry(account, EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+        let article = app.view(&model).article.unwrap();
+
+        assert_eq!(
+            article.content_text,
+            "See [about][1]\n\n[1]: https://example.com/about"
+        );
+        assert_eq!(
+            article.content_markdown,
+            "See [about](https://example.com/about)"
+        );
+    }
+}
@@ -1,48 +1,183 @@
-use super::subscriptions::SubscriptionTitle;
+use super::subscriptions::{SubscriptionId, SubscriptionLink};
 use super::Error;
+use crate::define_newtype;
 
-use feed_rs::model::Feed;
+use feed_rs::model::{Entry, Feed};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ANCHOR: types
+define_newtype!(EntryId);
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub enum ReadStatus {
+    #[default]
+    Unread,
+    Read,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub enum StarStatus {
+    #[default]
+    Unstarred,
+    Starred,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct EntryStatus {
+    pub read: ReadStatus,
+    pub star: StarStatus,
+}
+
+pub type NewEntriesCount = usize;
+// ANCHOR_END: types
+
+// NOTE read and unstarred entries a feed dropped are kept up to this many, the newest first
+const RETAINED_READ_ENTRIES: usize = 100;
 
 #[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Feeds {
-    pub feeds: Vec<Feed>,
+    // NOTE keyed by the subscription each feed was fetched from, as feeds can share their ids
+    pub feeds: HashMap<SubscriptionId, Feed>,
+    // NOTE entries that are unread and unstarred are not tracked
+    pub status: HashMap<EntryId, EntryStatus>,
+}
+
+trait FeedsHelpers {
+    fn entry_ids(feeds: &HashMap<SubscriptionId, Feed>) -> HashSet<&str>;
+    fn contains_entry(&self, entry_id: &EntryId) -> bool;
+    fn set_entry_status(&mut self, entry_id: &EntryId, status: EntryStatus);
+    fn prune_status(&mut self);
+    fn merge_entries(&self, old_entries: &[Entry], new_entries: Vec<Entry>) -> (Vec<Entry>, usize);
+}
+
+impl FeedsHelpers for Feeds {
+    // NOTE subscriptions can share entries, each is counted and marked once
+    fn entry_ids(feeds: &HashMap<SubscriptionId, Feed>) -> HashSet<&str> {
+        feeds
+            .values()
+            .flat_map(|feed| feed.entries.iter())
+            .map(|entry| entry.id.as_str())
+            .collect()
+    }
+
+    fn contains_entry(&self, entry_id: &EntryId) -> bool {
+        self.feeds
+            .values()
+            .flat_map(|feed| feed.entries.iter())
+            .any(|entry| entry.id == entry_id.to_string())
+    }
+
+    fn set_entry_status(&mut self, entry_id: &EntryId, status: EntryStatus) {
+        if status == EntryStatus::default() {
+            self.status.remove(entry_id);
+        } else {
+            self.status.insert(entry_id.clone(), status);
+        }
+    }
+
+    // NOTE entries no feed holds any more have no status to keep
+    fn prune_status(&mut self) {
+        let entry_ids = Self::entry_ids(&self.feeds);
+        self.status
+            .retain(|entry_id, _| entry_ids.contains(entry_id.as_ref()));
+    }
+
+    // NOTE entries keep the order of the new feed. entries the feed dropped follow, so history stays.
+    // NOTE unread and starred ones always stay, read ones up to RETAINED_READ_ENTRIES.
+    fn merge_entries(&self, old_entries: &[Entry], new_entries: Vec<Entry>) -> (Vec<Entry>, usize) {
+        let old_ids: HashSet<&str> = old_entries.iter().map(|old| old.id.as_str()).collect();
+        let new_ids: HashSet<&str> = new_entries.iter().map(|entry| entry.id.as_str()).collect();
+        let new_entries_count = new_entries
+            .iter()
+            .filter(|entry| !old_ids.contains(entry.id.as_str()))
+            .count();
+        let mut retained_read_entries = 0;
+        let dropped_entries: Vec<Entry> = old_entries
+            .iter()
+            .filter(|old| !new_ids.contains(old.id.as_str()))
+            .filter(|old| {
+                let status = self.find_entry_status(&EntryId::from(old.id.as_str()));
+                if status.read == ReadStatus::Unread || status.star == StarStatus::Starred {
+                    return true;
+                }
+                retained_read_entries += 1;
+                retained_read_entries <= RETAINED_READ_ENTRIES
+            })
+            .cloned()
+            .collect();
+
+        let mut entries = new_entries;
+        entries.extend(dropped_entries);
+        (entries, new_entries_count)
+    }
 }
 
 trait AddFeed {
-    fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error>
+    fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error>
     where
         Self: Sized;
 }
 
+// NOTE a feed fetched again replaces the stored one and merges its entries by id.
+// NOTE read and star status is keyed by entry id, so it survives the merge.
 impl AddFeed for Feeds {
-    fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error> {
+    fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
         let mut feeds = self.clone();
-        let feed = feed_rs::parser::parse(&*body)?;
+        let mut feed = feed_rs::parser::parse(&*body)?;
 
-        feeds.feeds.push(feed);
-        Ok(feeds)
+        match feeds.feeds.get_mut(&SubscriptionId::from(sub_link)) {
+            Some(stored) => {
+                let (entries, new_entries_count) =
+                    self.merge_entries(&stored.entries, std::mem::take(&mut feed.entries));
+                *stored = Feed { entries, ..feed };
+                feeds.prune_status();
+                Ok((feeds, new_entries_count))
+            }
+            None => {
+                let new_entries_count = feed.entries.len();
+                feeds.feeds.insert(SubscriptionId::from(sub_link), feed);
+                Ok((feeds, new_entries_count))
+            }
+        }
+    }
+}
+
+trait RetainFeeds {
+    fn retain_feeds(&mut self, sub_links: &[SubscriptionLink]);
+}
+
+// NOTE feeds no subscription links to any more go, and so does the status of their entries
+impl RetainFeeds for Feeds {
+    fn retain_feeds(&mut self, sub_links: &[SubscriptionLink]) {
+        let sub_ids: HashSet<SubscriptionId> = sub_links.iter().map(SubscriptionId::from).collect();
+
+        self.feeds.retain(|sub_id, _| sub_ids.contains(sub_id));
+        self.prune_status();
     }
 }
 
 trait FindFeed {
-    fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error>;
+    fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error>;
 }
 
 impl FindFeed for Feeds {
-    fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error> {
+    fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error> {
         let no_feed_found = Error::set_error(
             "Cannot find feed",
-            feed_title.as_ref(),
+            sub_link.as_ref(),
             "for the specified subscription.",
         );
 
-        let feed = self
-            .feeds
-            .iter()
-            .find(|feed| feed.title.clone().unwrap().content == feed_title.to_string());
-
-        if let Some(feed) = feed {
+        if let Some(feed) = self.feeds.get(&SubscriptionId::from(sub_link)) {
             Ok(feed.clone())
         } else {
             Err(no_feed_found)
@@ -50,15 +185,624 @@ impl FindFeed for Feeds {
     }
 }
 
+trait FindEntryStatus {
+    fn find_entry_status(&self, entry_id: &EntryId) -> EntryStatus;
+}
+
+impl FindEntryStatus for Feeds {
+    fn find_entry_status(&self, entry_id: &EntryId) -> EntryStatus {
+        self.status.get(entry_id).cloned().unwrap_or_default()
+    }
+}
+
+trait MarkEntry {
+    fn mark_entry(&self, entry_id: &EntryId, read_status: &ReadStatus) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+impl MarkEntry for Feeds {
+    fn mark_entry(&self, entry_id: &EntryId, read_status: &ReadStatus) -> Result<Self, Error> {
+        let mut feeds = self.clone();
+        let no_entry_found =
+            Error::set_error("Cannot mark entry", entry_id.as_ref(), "It doesn't exist.");
+
+        if !feeds.contains_entry(entry_id) {
+            return Err(no_entry_found);
+        }
+
+        let status = EntryStatus {
+            read: read_status.clone(),
+            ..feeds.find_entry_status(entry_id)
+        };
+        feeds.set_entry_status(entry_id, status);
+        Ok(feeds)
+    }
+}
+
+trait ToggleStar {
+    fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+impl ToggleStar for Feeds {
+    fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error> {
+        let mut feeds = self.clone();
+        let no_entry_found =
+            Error::set_error("Cannot star entry", entry_id.as_ref(), "It doesn't exist.");
+
+        if !feeds.contains_entry(entry_id) {
+            return Err(no_entry_found);
+        }
+
+        let status = feeds.find_entry_status(entry_id);
+        let star = match status.star {
+            StarStatus::Unstarred => StarStatus::Starred,
+            StarStatus::Starred => StarStatus::Unstarred,
+        };
+        feeds.set_entry_status(entry_id, EntryStatus { star, ..status });
+        Ok(feeds)
+    }
+}
+
+trait MarkFeedRead {
+    fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+impl MarkFeedRead for Feeds {
+    fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error> {
+        let mut feeds = self.clone();
+        let feed = feeds.find_feed(sub_link)?;
+
+        for entry in feed.entries {
+            let entry_id = EntryId(entry.id);
+            let status = EntryStatus {
+                read: ReadStatus::Read,
+                ..feeds.find_entry_status(&entry_id)
+            };
+            feeds.set_entry_status(&entry_id, status);
+        }
+        Ok(feeds)
+    }
+}
+
+trait MarkAllRead {
+    fn mark_all_read(&self) -> Self
+    where
+        Self: Sized;
+}
+
+impl MarkAllRead for Feeds {
+    fn mark_all_read(&self) -> Self {
+        let mut feeds = self.clone();
+        let entry_ids: Vec<EntryId> = Self::entry_ids(&self.feeds)
+            .into_iter()
+            .map(EntryId::from)
+            .collect();
+
+        for entry_id in entry_ids {
+            let status = EntryStatus {
+                read: ReadStatus::Read,
+                ..feeds.find_entry_status(&entry_id)
+            };
+            feeds.set_entry_status(&entry_id, status);
+        }
+        feeds
+    }
+}
+
+trait CountEntries {
+    fn count_unread(&self) -> usize;
+    fn count_feed_unread(&self, sub_link: &SubscriptionLink) -> usize;
+    fn count_starred(&self) -> usize;
+}
+
+impl CountEntries for Feeds {
+    fn count_unread(&self) -> usize {
+        Self::entry_ids(&self.feeds)
+            .into_iter()
+            .filter(|entry_id| {
+                self.find_entry_status(&EntryId::from(*entry_id)).read == ReadStatus::Unread
+            })
+            .count()
+    }
+
+    // NOTE a subscription which hasn't been fetched yet has nothing unread
+    fn count_feed_unread(&self, sub_link: &SubscriptionLink) -> usize {
+        self.feeds
+            .get(&SubscriptionId::from(sub_link))
+            .map(|feed| {
+                feed.entries
+                    .iter()
+                    .filter(|entry| {
+                        self.find_entry_status(&EntryId(entry.id.to_string())).read
+                            == ReadStatus::Unread
+                    })
+                    .count()
+            })
+            .unwrap_or_default()
+    }
+
+    fn count_starred(&self) -> usize {
+        Self::entry_ids(&self.feeds)
+            .into_iter()
+            .filter(|entry_id| {
+                self.find_entry_status(&EntryId::from(*entry_id)).star == StarStatus::Starred
+            })
+            .count()
+    }
+}
+
 impl Feeds {
-    pub(super) fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error> {
-        <Self as AddFeed>::add_feed(self, body)
+    pub(super) fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
+        <Self as AddFeed>::add_feed(self, sub_link, body)
+    }
+
+    pub(super) fn retain_feeds(&mut self, sub_links: &[SubscriptionLink]) {
+        <Self as RetainFeeds>::retain_feeds(self, sub_links)
+    }
+
+    pub(super) fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error> {
+        <Self as FindFeed>::find_feed(self, sub_link)
+    }
+
+    pub(super) fn contains_entry(&self, entry_id: &EntryId) -> bool {
+        <Self as FeedsHelpers>::contains_entry(self, entry_id)
+    }
+
+    pub fn find_entry_status(&self, entry_id: &EntryId) -> EntryStatus {
+        <Self as FindEntryStatus>::find_entry_status(self, entry_id)
+    }
+
+    pub(super) fn mark_entry(
+        &self,
+        entry_id: &EntryId,
+        read_status: &ReadStatus,
+    ) -> Result<Self, Error> {
+        <Self as MarkEntry>::mark_entry(self, entry_id, read_status)
+    }
+
+    pub(super) fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error> {
+        <Self as ToggleStar>::toggle_star(self, entry_id)
+    }
+
+    pub(super) fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error> {
+        <Self as MarkFeedRead>::mark_feed_read(self, sub_link)
+    }
+
+    pub(super) fn mark_all_read(&self) -> Self {
+        <Self as MarkAllRead>::mark_all_read(self)
+    }
+
+    pub fn count_unread(&self) -> usize {
+        <Self as CountEntries>::count_unread(self)
+    }
+
+    pub fn count_feed_unread(&self, sub_link: &SubscriptionLink) -> usize {
+        <Self as CountEntries>::count_feed_unread(self, sub_link)
     }
 
-    pub(super) fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error> {
-        <Self as FindFeed>::find_feed(self, feed_title)
+    pub fn count_starred(&self) -> usize {
+        <Self as CountEntries>::count_starred(self)
     }
 }
 
-// #[cfg(test)]
-// mod feeds { }
+#[cfg(test)]
+mod entry_status {
+    use super::*;
+    use crate::fixtures::{add_folder, add_subscription, fetched, model_with_account};
+    use crate::fixtures::{atom_link, model_with_feed, rss_link, EXAMPLE_RSS};
+    use crate::{App, Event, FolderId, SubscriptionId};
+    use crux_core::App as _;
+
+    const UPDATED_RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><item><guid>https://example.com/three</guid><title>Third Entry</title><link>https://example.com/three</link><description>Third summary</description></item><item><guid>https://example.com/two</guid><title>Second Entry Updated</title><link>https://example.com/two</link><description>Second summary</description></item></channel></rss>"#;
+
+    #[test]
+    fn new_entries_are_unread_and_unstarred() {
+        let app = App;
+        let (model, _) = model_with_feed(&app);
+        let feeds = &model.accounts.acct[0].subs.feeds;
+
+        let actual_status = feeds.find_entry_status(&EntryId("https://example.com/one".into()));
+
+        assert_eq!(actual_status, EntryStatus::default());
+        assert_eq!(feeds.count_unread(), 2);
+    }
+
+    #[test]
+    fn mark_entry_read_and_unread() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId("https://example.com/one".to_string());
+
+        let _ = app.update(
+            Event::MarkEntryRead(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let read_status = model.accounts.acct[0]
+            .subs
+            .feeds
+            .find_entry_status(&entry_id)
+            .read;
+
+        let _ = app.update(
+            Event::MarkEntryUnread(account, entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let unread_status = model.accounts.acct[0]
+            .subs
+            .feeds
+            .find_entry_status(&entry_id)
+            .read;
+
+        assert_eq!(read_status, ReadStatus::Read);
+        assert_eq!(unread_status, ReadStatus::Unread);
+    }
+
+    #[test]
+    fn fail_mark_missing_entry_read() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId("https://example.com/missing".to_string());
+
+        let _ = app.update(
+            Event::MarkEntryRead(account, entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error = format!("Cannot mark entry \"{entry_id}\". It doesn't exist.");
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn toggle_star() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId("https://example.com/two".to_string());
+
+        let _ = app.update(
+            Event::ToggleStar(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let starred = model.accounts.acct[0].subs.feeds.clone();
+
+        let _ = app.update(
+            Event::ToggleStar(account, entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let unstarred = model.accounts.acct[0].subs.feeds.clone();
+
+        assert_eq!(
+            starred.find_entry_status(&entry_id).star,
+            StarStatus::Starred
+        );
+        assert_eq!(starred.count_starred(), 1);
+        assert_eq!(
+            unstarred.find_entry_status(&entry_id).star,
+            StarStatus::Unstarred
+        );
+        assert!(unstarred.status.is_empty());
+    }
+
+    #[test]
+    fn mark_feed_read() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+
+        let _ = app.update(
+            Event::MarkFeedRead(
+                account,
+                SubscriptionId::from_link("https://example.com/rss.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[0].subs.feeds.count_unread(), 0);
+    }
+
+    #[test]
+    fn mark_folder_read() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let folder_id = add_folder(&app, &mut model, &account, None, "Group Name");
+
+        add_subscription(
+            &app,
+            &mut model,
+            &account,
+            Some(folder_id.clone()),
+            "Feed Name",
+            &rss_link(),
+        );
+        fetched(&app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+        let _ = app.update(Event::MarkFolderRead(account, folder_id), &mut model, &());
+
+        assert_eq!(model.accounts.acct[0].subs.feeds.count_unread(), 0);
+    }
+
+    #[test]
+    fn fail_mark_missing_folder_read() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let folder_id = FolderId::from("missing-folder");
+
+        let _ = app.update(
+            Event::MarkFolderRead(account, folder_id.clone()),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error =
+            format!("Cannot mark as read folder \"{folder_id}\". It doesn't exist.");
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn mark_account_read_keeps_stars() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId("https://example.com/one".to_string());
+
+        let _ = app.update(
+            Event::ToggleStar(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::MarkAccountRead(account), &mut model, &());
+        let feeds = &model.accounts.acct[0].subs.feeds;
+
+        assert_eq!(feeds.count_unread(), 0);
+        assert_eq!(
+            feeds.find_entry_status(&entry_id),
+            EntryStatus {
+                read: ReadStatus::Read,
+                star: StarStatus::Starred,
+            }
+        );
+    }
+
+    #[test]
+    fn view_counts_unread_and_starred() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+
+        let _ = app.update(
+            Event::MarkEntryRead(account.clone(), EntryId("https://example.com/one".into())),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ToggleStar(account, EntryId("https://example.com/two".into())),
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model);
+
+        assert_eq!(view.unread_count, 1);
+        assert_eq!(view.starred_count, 1);
+    }
+
+    #[test]
+    fn refetched_feed_is_not_duplicated() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+
+        fetched(&app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+        let feeds = &model.accounts.acct[0].subs.feeds;
+
+        assert_eq!(feeds.feeds.len(), 1);
+        assert_eq!(
+            feeds.feeds[&SubscriptionId::from(&rss_link())]
+                .entries
+                .len(),
+            2
+        );
+        assert_eq!(app.view(&model).new_entries_count, 0);
+    }
+
+    #[test]
+    fn refetched_feed_merges_entries() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+
+        fetched(&app, &mut model, &account, &rss_link(), UPDATED_RSS);
+        let feed = &model.accounts.acct[0].subs.feeds.feeds[&SubscriptionId::from(&rss_link())];
+        let entry_titles: Vec<String> = feed
+            .entries
+            .iter()
+            .map(|entry| entry.title.clone().unwrap().content)
+            .collect();
+
+        assert_eq!(
+            entry_titles,
+            vec!["Third Entry", "Second Entry Updated", "First Entry"]
+        );
+        assert_eq!(app.view(&model).new_entries_count, 1);
+    }
+
+    #[test]
+    fn refetched_feed_keeps_entry_status() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId::from("https://example.com/two");
+
+        let _ = app.update(
+            Event::MarkEntryRead(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ToggleStar(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        fetched(&app, &mut model, &account, &rss_link(), UPDATED_RSS);
+        let feeds = &model.accounts.acct[0].subs.feeds;
+
+        assert_eq!(
+            feeds.find_entry_status(&entry_id),
+            EntryStatus {
+                read: ReadStatus::Read,
+                star: StarStatus::Starred,
+            }
+        );
+        assert_eq!(feeds.count_unread(), 2);
+    }
+
+    #[test]
+    fn feeds_sharing_an_id_stay_apart() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let mirror_link = SubscriptionLink::from("https://mirror.example.com/rss.xml");
+
+        add_subscription(&app, &mut model, &account, None, "Mirror", &mirror_link);
+        fetched(&app, &mut model, &account, &mirror_link, UPDATED_RSS);
+        let subs = &model.accounts.acct[0].subs;
+        let feed = subs.find_feed(&rss_link()).unwrap();
+        let mirror_feed = subs.find_feed(&mirror_link).unwrap();
+        let entry_titles = |feed: &Feed| -> Vec<String> {
+            feed.entries
+                .iter()
+                .map(|entry| entry.title.clone().unwrap().content)
+                .collect()
+        };
+
+        assert_eq!(feed.id, mirror_feed.id);
+        assert_eq!(entry_titles(&feed), vec!["First Entry", "Second Entry"]);
+        assert_eq!(
+            entry_titles(&mirror_feed),
+            vec!["Third Entry", "Second Entry Updated"]
+        );
+        assert_eq!(subs.feeds.count_feed_unread(&rss_link()), 2);
+        assert_eq!(subs.feeds.count_feed_unread(&mirror_link), 2);
+        assert_eq!(subs.feeds.count_unread(), 3);
+    }
+
+    fn rss_with_items(ids: std::ops::Range<usize>) -> String {
+        let items: String = ids
+            .map(|n| {
+                format!("<item><guid>https://example.com/{n}</guid><title>Entry {n}</title></item>")
+            })
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description>{items}</channel></rss>"#
+        )
+    }
+
+    #[test]
+    fn refetched_feed_caps_read_entries_it_dropped() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let oldest = EntryId::from(format!("https://example.com/{}", RETAINED_READ_ENTRIES + 1));
+        let unread = EntryId::from(format!("https://example.com/{}", RETAINED_READ_ENTRIES));
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        fetched(
+            &app,
+            &mut model,
+            &account,
+            &rss_link(),
+            &rss_with_items(0..RETAINED_READ_ENTRIES + 3),
+        );
+        let _ = app.update(
+            Event::MarkFeedRead(account.clone(), SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ToggleStar(account.clone(), oldest.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::MarkEntryUnread(account.clone(), unread.clone()),
+            &mut model,
+            &(),
+        );
+        fetched(
+            &app,
+            &mut model,
+            &account,
+            &rss_link(),
+            &rss_with_items(1000..1001),
+        );
+        let feeds = &model.accounts.acct[0].subs.feeds;
+        let entry_ids: Vec<&str> = feeds.feeds[&SubscriptionId::from(&rss_link())]
+            .entries
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect();
+        let last = EntryId::from(format!("https://example.com/{}", RETAINED_READ_ENTRIES + 2));
+
+        // NOTE the new one, the newest read ones, then the unread and the starred ones
+        assert_eq!(entry_ids.len(), 1 + RETAINED_READ_ENTRIES + 2);
+        assert!(entry_ids.contains(&unread.as_ref()));
+        assert!(entry_ids.contains(&oldest.as_ref()));
+        assert!(!entry_ids.contains(&last.as_ref()));
+        assert_eq!(feeds.find_entry_status(&last), EntryStatus::default());
+        assert!(!feeds.status.contains_key(&last));
+        assert_eq!(feeds.status.len(), RETAINED_READ_ENTRIES + 1);
+    }
+
+    #[test]
+    fn find_feed_by_subscription_link() {
+        let app = App;
+        let (model, _) = model_with_feed(&app);
+        let subs = &model.accounts.acct[0].subs;
+
+        let feed = subs.find_feed(&SubscriptionLink::from("https://example.com/rss.xml"));
+        let no_feed = subs.find_feed(&SubscriptionLink::from("https://example.com/other.xml"));
+
+        assert_eq!(feed.unwrap().title.unwrap().content, "Feed Name");
+        assert_eq!(
+            no_feed.unwrap_err().to_string(),
+            "Cannot find feed \"https://example.com/other.xml\". for the specified subscription."
+        );
+    }
+
+    #[test]
+    fn mark_untitled_feed_read() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let untitled_atom = r#"<?xml version="1.0" encoding="utf-8"?><feed xmlns="http://www.w3.org/2005/Atom"><id>urn:example:untitled</id><updated>2025-10-19T09:00:00Z</updated><entry><id>urn:example:untitled:one</id><title>Untitled Feed Entry</title><updated>2025-10-19T09:00:00Z</updated></entry></feed>"#;
+
+        add_subscription(
+            &app,
+            &mut model,
+            &account,
+            None,
+            "Untitled Feed",
+            &atom_link(),
+        );
+        fetched(&app, &mut model, &account, &atom_link(), untitled_atom);
+        let _ = app.update(
+            Event::MarkFeedRead(account, SubscriptionId::from(&atom_link())),
+            &mut model,
+            &(),
+        );
+        let feeds = &model.accounts.acct[0].subs.feeds;
+
+        assert_eq!(
+            feeds
+                .find_entry_status(&EntryId::from("urn:example:untitled:one"))
+                .read,
+            ReadStatus::Read
+        );
+        assert_eq!(feeds.count_unread(), 2);
+    }
+}
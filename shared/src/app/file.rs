@@ -0,0 +1,44 @@
+// NOTE - port to the shell's file system. The shell fulfils it, e.g. with a save dialog.
+// NOTE - the core only hands over the file name and its bytes, it never touches std::fs.
+use super::{Effect, Event};
+
+use crux_core::{capability::Operation, command::RequestBuilder, Command};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum FileOperation {
+    Save { file_name: String, bytes: Vec<u8> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum FileResponse {
+    Saved { file_name: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum FileError {
+    #[error("{message}")]
+    Io { message: String },
+}
+
+pub type FileResult = Result<FileResponse, FileError>;
+
+impl Operation for FileOperation {
+    type Output = FileResult;
+}
+
+pub struct File;
+
+impl File {
+    pub fn save(
+        file_name: &str,
+        bytes: Vec<u8>,
+    ) -> RequestBuilder<Effect, Event, impl Future<Output = FileResult>> {
+        Command::request_from_shell(FileOperation::Save {
+            file_name: file_name.to_string(),
+            bytes,
+        })
+    }
+}
@@ -0,0 +1,153 @@
+// NOTE - feeds and model setup shared by the tests of every module and of the storage adapters.
+// NOTE - each helper goes through App::update, as shells would.
+use super::{Account, AccountType, App, Event, FolderId, FolderName, FolderPath, Model};
+use super::{SubscriptionLink, SubscriptionTitle, Subscriptions};
+
+use crux_core::App as _;
+use crux_http::testing::ResponseBuilder;
+
+pub const EXAMPLE_RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><image><url>https://example.com/logo.png</url><title>Feed Name</title><link>https://example.com/</link></image><item><guid>https://example.com/one</guid><title>First Entry</title><link>https://example.com/one</link><dc:creator>Jane Doe</dc:creator><description>First summary</description><pubDate>Sun, 05 Oct 2025 09:30:00 GMT</pubDate><enclosure url="https://example.com/one.mp3" type="audio/mpeg" length="1024"/></item><item><guid>https://example.com/two</guid><title>Second Entry</title><link>https://example.com/two</link><description>Second summary</description><pubDate>Mon, 06 Oct 2025 09:30:00 GMT</pubDate></item></channel></rss>"#;
+
+pub const EXAMPLE_ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?><feed xmlns="http://www.w3.org/2005/Atom"><title>Atom Name</title><subtitle>An atom feed</subtitle><link rel="self" href="https://example.com/atom.xml"/><link rel="alternate" href="https://example.com/blog/"/><id>urn:uuid:60a76c80-d399-11d9-b93c-0003939e0af6</id><updated>2025-10-07T09:00:00Z</updated><entry><id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id><title>Three</title><author><name>John Doe</name></author><updated>2025-10-07T09:00:00Z</updated><link rel="alternate" href="https://example.com/three"/><link rel="enclosure" type="video/mp4" length="2048" href="https://example.com/three.mp4"/><content type="html">&lt;p&gt;Full content&lt;/p&gt;</content></entry></feed>"#;
+
+// NOTE an entry with scripts, handlers, a tracking pixel and a link relative to the site
+pub const UNSAFE_RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><item><guid>https://example.com/one</guid><title>First Entry</title><description>&lt;p onclick="steal()"&gt;See &lt;a href="/about"&gt;about&lt;/a&gt;&lt;/p&gt;&lt;script&gt;steal()&lt;/script&gt;&lt;img src="/pixel.gif" width="1" height="1"&gt;</description></item></channel></rss>"#;
+
+pub fn rss_link() -> SubscriptionLink {
+    SubscriptionLink::from("https://example.com/rss.xml")
+}
+
+pub fn atom_link() -> SubscriptionLink {
+    SubscriptionLink::from("https://example.com/atom.xml")
+}
+
+fn account_subs<'a>(model: &'a Model, account: &Account) -> &'a Subscriptions {
+    &model
+        .accounts
+        .acct
+        .iter()
+        .find(|acct| acct.id == account.id)
+        .unwrap()
+        .subs
+}
+
+pub fn folder_id(model: &Model, folder_path: &FolderPath) -> FolderId {
+    model.accounts.acct[0]
+        .subs
+        .find_folder_id(folder_path)
+        .unwrap()
+}
+
+// NOTE the account is read back from the model, as its id is generated when it's created
+pub fn model_with_account(app: &App) -> (Model, Account) {
+    let mut model = Model::default();
+
+    let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+    let account = model.accounts.acct[0].clone();
+
+    (model, account)
+}
+
+pub fn add_folder(
+    app: &App,
+    model: &mut Model,
+    account: &Account,
+    parent_id: Option<FolderId>,
+    folder_name: &str,
+) -> FolderId {
+    let folder_path = match &parent_id {
+        Some(parent_id) => account_subs(model, account)
+            .find_folder_path(parent_id)
+            .unwrap()
+            .join(&FolderName::from(folder_name)),
+        None => FolderPath::from(folder_name),
+    };
+
+    let _ = app.update(
+        Event::AddNewFolder(account.clone(), parent_id, FolderName::from(folder_name)),
+        model,
+        &(),
+    );
+    account_subs(model, account)
+        .find_folder_id(&folder_path)
+        .unwrap()
+}
+
+pub fn add_subscription(
+    app: &App,
+    model: &mut Model,
+    account: &Account,
+    folder_id: Option<FolderId>,
+    sub_title: &str,
+    sub_link: &SubscriptionLink,
+) {
+    let _ = app.update(
+        Event::AddSubscription(
+            account.clone(),
+            folder_id,
+            SubscriptionTitle::from(sub_title),
+            sub_link.clone(),
+        ),
+        model,
+        &(),
+    );
+}
+
+pub fn fetched(
+    app: &App,
+    model: &mut Model,
+    account: &Account,
+    sub_link: &SubscriptionLink,
+    body: &str,
+) {
+    let response = ResponseBuilder::ok().body(body.as_bytes().to_vec()).build();
+    let _ = app.update(
+        Event::SetFeed(account.clone(), sub_link.clone(), Ok(response)),
+        model,
+        &(),
+    );
+}
+
+// NOTE one account with the RSS feed fetched at the root
+pub fn model_with_feed(app: &App) -> (Model, Account) {
+    let (mut model, account) = model_with_account(app);
+
+    add_subscription(app, &mut model, &account, None, "Feed Name", &rss_link());
+    fetched(app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+
+    (model, account)
+}
+
+// NOTE one account with a subscription that hasn't been fetched yet, at the root
+pub fn model_with_subscription(app: &App, sub_link: &SubscriptionLink) -> (Model, Account) {
+    let (mut model, account) = model_with_account(app);
+
+    add_subscription(app, &mut model, &account, None, "Feed Name", sub_link);
+
+    (model, account)
+}
+
+// NOTE one account with empty folders at the root, their ids in the same order
+pub fn model_with_folders(app: &App, folder_names: &[&str]) -> (Model, Account, Vec<FolderId>) {
+    let (mut model, account) = model_with_account(app);
+    let folder_ids = folder_names
+        .iter()
+        .map(|folder_name| add_folder(app, &mut model, &account, None, folder_name))
+        .collect();
+
+    (model, account, folder_ids)
+}
+
+// NOTE one account with the empty Rust folder in the Engineering one. the id is Rust's
+pub fn model_with_nested_folder(app: &App) -> (Model, Account, FolderId) {
+    let (mut model, account, folder_ids) = model_with_folders(app, &["Engineering"]);
+    let folder_id = add_folder(
+        app,
+        &mut model,
+        &account,
+        Some(folder_ids[0].clone()),
+        "Rust",
+    );
+
+    (model, account, folder_id)
+}
@@ -0,0 +1,250 @@
+// NOTE - validators from the last successful fetch of a subscription, sent back on the next one.
+// NOTE - the server answers 304 Not Modified with an empty body when nothing changed.
+use super::{Effect, Event, SubscriptionLink, Subscriptions};
+
+use crux_http::{
+    command::{Http, RequestBuilder},
+    http::{
+        headers::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+        StatusCode,
+    },
+    Response,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct HttpCache {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+trait HttpCacheHelpers {
+    fn header_value(response: &Response<Vec<u8>>, name: HeaderName) -> Option<String>;
+}
+
+impl HttpCacheHelpers for HttpCache {
+    fn header_value(response: &Response<Vec<u8>>, name: HeaderName) -> Option<String> {
+        response
+            .header(name)
+            .map(|values| values.last().as_str().to_string())
+    }
+}
+
+impl HttpCache {
+    pub fn from_response(response: &Response<Vec<u8>>) -> Self {
+        Self {
+            etag: Self::header_value(response, ETAG),
+            last_modified: Self::header_value(response, LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    pub fn is_not_modified(response: &Response<Vec<u8>>) -> bool {
+        response.status() == StatusCode::NotModified
+    }
+}
+
+trait ConditionalGet {
+    fn get_feed(&self, sub_link: &SubscriptionLink) -> RequestBuilder<Effect, Event>;
+}
+
+impl ConditionalGet for Subscriptions {
+    fn get_feed(&self, sub_link: &SubscriptionLink) -> RequestBuilder<Effect, Event> {
+        let mut request = Http::get(sub_link);
+
+        if let Some(cache) = self.http_cache.get(sub_link) {
+            if let Some(etag) = &cache.etag {
+                request = request.header(IF_NONE_MATCH, etag.as_str());
+            }
+            if let Some(last_modified) = &cache.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+            }
+        }
+
+        request
+    }
+}
+
+impl Subscriptions {
+    pub fn get_feed(&self, sub_link: &SubscriptionLink) -> RequestBuilder<Effect, Event> {
+        <Self as ConditionalGet>::get_feed(self, sub_link)
+    }
+}
+
+#[cfg(test)]
+mod conditional_get {
+    use super::*;
+    use crate::fixtures::{model_with_subscription, rss_link, EXAMPLE_RSS};
+    use crate::{Account, App, Model, SubscriptionId};
+    use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
+    use std::collections::HashMap;
+
+    const ETAG_VALUE: &str = r#""abc123""#;
+    const LAST_MODIFIED_VALUE: &str = "Sun, 19 Oct 2025 09:00:00 GMT";
+
+    fn fetched_feed(app: &App, model: &mut Model, account: &Account) {
+        let response = ResponseBuilder::ok()
+            .header(ETAG, ETAG_VALUE)
+            .header(LAST_MODIFIED, LAST_MODIFIED_VALUE)
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+        let _ = app.update(
+            Event::SetFeed(account.clone(), rss_link(), Ok(response)),
+            model,
+            &(),
+        );
+    }
+
+    fn request_headers(cmd: &mut crux_core::Command<Effect, Event>) -> HashMap<String, String> {
+        let request = cmd.effects().next().unwrap().expect_http();
+        request
+            .operation
+            .headers
+            .into_iter()
+            .map(|header| (header.name.to_lowercase(), header.value))
+            .collect()
+    }
+
+    #[test]
+    fn first_fetch_is_unconditional() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        let mut cmd = app.update(
+            Event::GetFeed(account, SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+
+        let headers = request_headers(&mut cmd);
+
+        assert_eq!(headers.get("if-none-match"), None);
+        assert_eq!(headers.get("if-modified-since"), None);
+    }
+
+    #[test]
+    fn store_validators_from_response() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        fetched_feed(&app, &mut model, &account);
+
+        assert_eq!(
+            model.accounts.acct[0].subs.http_cache.get(&rss_link()),
+            Some(&HttpCache {
+                etag: Some(ETAG_VALUE.to_string()),
+                last_modified: Some(LAST_MODIFIED_VALUE.to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn send_validators_on_next_fetch() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        fetched_feed(&app, &mut model, &account);
+        let mut cmd = app.update(
+            Event::GetFeed(account, SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+        let headers = request_headers(&mut cmd);
+
+        assert_eq!(
+            headers.get("if-none-match").map(String::as_str),
+            Some(ETAG_VALUE)
+        );
+        assert_eq!(
+            headers.get("if-modified-since").map(String::as_str),
+            Some(LAST_MODIFIED_VALUE)
+        );
+    }
+
+    #[test]
+    fn not_modified_means_no_new_entries() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        fetched_feed(&app, &mut model, &account);
+        let subs = model.accounts.acct[0].subs.clone();
+        let response = ResponseBuilder::with_status(StatusCode::NotModified)
+            .body(Vec::new())
+            .build();
+        let _ = app.update(
+            Event::SetFeed(account, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(model.notification.title, "");
+    }
+
+    #[test]
+    fn error_status_keeps_feed_and_validators() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        fetched_feed(&app, &mut model, &account);
+        let subs = model.accounts.acct[0].subs.clone();
+        let response = ResponseBuilder::with_status(StatusCode::InternalServerError)
+            .header(ETAG, r#""error""#)
+            .body(b"<html>Server Error</html>".to_vec())
+            .build();
+        let _ = app.update(
+            Event::SetFeed(account, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(
+            model.notification.message,
+            "Cannot update feed \"https://example.com/rss.xml\". The server answered 500."
+        );
+    }
+
+    #[test]
+    fn error_status_counts_as_failed() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        let _ = app.update(Event::RefreshAll, &mut model, &());
+        let response = ResponseBuilder::with_status(StatusCode::NotFound)
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+        let _ = app.update(
+            Event::FeedRefreshed(account.id, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.refresh_progress.failed, 1);
+        assert!(model.accounts.acct[0].subs.feeds.feeds.is_empty());
+        assert!(model.accounts.acct[0].subs.http_cache.is_empty());
+    }
+
+    #[test]
+    fn not_modified_counts_as_refreshed() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &rss_link());
+
+        let _ = app.update(Event::RefreshAll, &mut model, &());
+        let response = ResponseBuilder::with_status(StatusCode::NotModified)
+            .body(Vec::new())
+            .build();
+        let _ = app.update(
+            Event::FeedRefreshed(account.id, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.refresh_progress.done, 1);
+        assert_eq!(model.refresh_progress.failed, 0);
+    }
+}
@@ -0,0 +1,601 @@
+use super::{Account, AccountId, Accounts, Effect, Event, Model, NewEntriesCount};
+use super::{SubscriptionLink, Timer, TimerId};
+
+use crux_core::Command;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// NOTE counts fetches of the refresh in flight, reset when a new refresh starts
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct RefreshProgress {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub new_entries_count: NewEntriesCount,
+}
+
+impl RefreshProgress {
+    pub fn is_refreshing(&self) -> bool {
+        self.done + self.failed < self.total
+    }
+}
+
+// NOTE maximum number of Http requests in flight during a refresh
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RefreshConcurrency(pub usize);
+
+impl Default for RefreshConcurrency {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
+// NOTE accounts are looked up when their turn comes, so a deleted one is skipped
+pub type RefreshQueue = VecDeque<(AccountId, SubscriptionLink)>;
+
+trait RefreshHelpers {
+    fn refresh_links(account: &Account) -> Vec<(AccountId, SubscriptionLink)>;
+}
+
+impl RefreshHelpers for Model {
+    fn refresh_links(account: &Account) -> Vec<(AccountId, SubscriptionLink)> {
+        account
+            .subs
+            .list_subscription_links()
+            .into_iter()
+            .map(|link| (account.id.clone(), link))
+            .collect()
+    }
+}
+
+trait ScheduleRefresh {
+    fn schedule_refresh(&mut self) -> Command<Effect, Event>;
+    fn rearm_refresh(&mut self) -> Command<Effect, Event>;
+    fn cancel_refresh(&mut self) -> Command<Effect, Event>;
+}
+
+impl ScheduleRefresh for Model {
+    fn schedule_refresh(&mut self) -> Command<Effect, Event> {
+        let cancel = self.cancel_refresh();
+        cancel.and(self.rearm_refresh())
+    }
+
+    // NOTE every timer gets a fresh id, so a late Finished from a cleared one is ignored
+    // NOTE a timer that just fired has nothing left to clear
+    fn rearm_refresh(&mut self) -> Command<Effect, Event> {
+        let id = TimerId(self.refresh_timers_started + 1);
+
+        self.refresh_timers_started = id.0;
+        self.refresh_timer = Some(id);
+
+        Timer::start(id, self.refresh_interval.as_millis()).then_send(Event::RefreshTimerFired)
+    }
+
+    fn cancel_refresh(&mut self) -> Command<Effect, Event> {
+        match self.refresh_timer.take() {
+            Some(id) => Timer::clear(id),
+            None => Command::done(),
+        }
+    }
+}
+
+trait RefreshFeeds {
+    fn refresh(&mut self, links: Vec<(AccountId, SubscriptionLink)>) -> Command<Effect, Event>;
+    fn refresh_next(&mut self) -> Command<Effect, Event>;
+    fn feed_refreshed(
+        &mut self,
+        new_entries_count: Option<NewEntriesCount>,
+    ) -> Command<Effect, Event>;
+}
+
+impl RefreshFeeds for Model {
+    // NOTE links already waiting in the queue are not queued twice
+    fn refresh(&mut self, links: Vec<(AccountId, SubscriptionLink)>) -> Command<Effect, Event> {
+        if !self.refresh_progress.is_refreshing() {
+            self.refresh_progress = RefreshProgress::default();
+        }
+
+        for (account_id, link) in links {
+            let is_queued = self
+                .refresh_queue
+                .iter()
+                .any(|(a, l)| *a == account_id && *l == link);
+            if !is_queued {
+                self.refresh_queue.push_back((account_id, link));
+                self.refresh_progress.total += 1;
+            }
+        }
+
+        self.refresh_next()
+    }
+
+    // NOTE links of accounts deleted while they waited count as failed
+    fn refresh_next(&mut self) -> Command<Effect, Event> {
+        let mut fetches = Vec::new();
+
+        while self.refresh_in_flight < self.refresh_concurrency.0.max(1) {
+            let Some((account_id, link)) = self.refresh_queue.pop_front() else {
+                break;
+            };
+            let Some(account_index) = Accounts::find_by_id(&self.accounts, &account_id) else {
+                self.refresh_progress.failed += 1;
+                continue;
+            };
+
+            self.refresh_in_flight += 1;
+            fetches.push(
+                self.accounts.acct[account_index]
+                    .subs
+                    .get_feed(&link)
+                    .build()
+                    .then_send(move |result| Event::FeedRefreshed(account_id, link, result)),
+            );
+        }
+
+        Command::all(fetches)
+    }
+
+    // NOTE None when the fetch or the parsing failed
+    fn feed_refreshed(
+        &mut self,
+        new_entries_count: Option<NewEntriesCount>,
+    ) -> Command<Effect, Event> {
+        self.refresh_in_flight = self.refresh_in_flight.saturating_sub(1);
+
+        match new_entries_count {
+            Some(new_entries_count) => {
+                self.refresh_progress.done += 1;
+                self.refresh_progress.new_entries_count += new_entries_count;
+            }
+            None => self.refresh_progress.failed += 1,
+        }
+
+        self.refresh_next()
+    }
+}
+
+impl Model {
+    pub(crate) fn schedule_refresh(&mut self) -> Command<Effect, Event> {
+        <Self as ScheduleRefresh>::schedule_refresh(self)
+    }
+
+    pub(crate) fn rearm_refresh(&mut self) -> Command<Effect, Event> {
+        <Self as ScheduleRefresh>::rearm_refresh(self)
+    }
+
+    pub(crate) fn cancel_refresh(&mut self) -> Command<Effect, Event> {
+        <Self as ScheduleRefresh>::cancel_refresh(self)
+    }
+
+    pub(crate) fn refresh_all(&mut self) -> Command<Effect, Event> {
+        let links = self
+            .accounts
+            .acct
+            .iter()
+            .flat_map(Self::refresh_links)
+            .collect();
+        <Self as RefreshFeeds>::refresh(self, links)
+    }
+
+    pub(crate) fn refresh_account(&mut self, account_index: usize) -> Command<Effect, Event> {
+        let links = Self::refresh_links(&self.accounts.acct[account_index]);
+        <Self as RefreshFeeds>::refresh(self, links)
+    }
+
+    pub(crate) fn feed_refreshed(
+        &mut self,
+        new_entries_count: Option<NewEntriesCount>,
+    ) -> Command<Effect, Event> {
+        <Self as RefreshFeeds>::feed_refreshed(self, new_entries_count)
+    }
+}
+
+#[cfg(test)]
+mod scheduled_refresh {
+    use super::*;
+    use crate::fixtures::{add_folder, add_subscription, model_with_account, EXAMPLE_RSS};
+    use crate::{AccountType, App, RefreshInterval, TimerOperation, TimerOutput};
+    use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
+
+    fn root_link() -> SubscriptionLink {
+        SubscriptionLink::from("https://example.com/root.xml")
+    }
+
+    fn model_with_subscriptions(app: &App) -> (Model, Account) {
+        let (mut model, account) = model_with_account(app);
+        let folder_id = add_folder(app, &mut model, &account, None, "Group Name");
+
+        add_subscription(app, &mut model, &account, None, "Root Feed", &root_link());
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(folder_id),
+            "Folder Feed",
+            &SubscriptionLink::from("https://example.com/folder.xml"),
+        );
+
+        (model, account)
+    }
+
+    #[test]
+    fn schedule_refresh_with_interval() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::SetRefreshInterval(RefreshInterval::HoursOne),
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(Event::ScheduleRefresh, &mut model, &());
+        let timer = cmd.effects().next().unwrap().expect_timer();
+
+        assert_eq!(
+            timer.operation,
+            TimerOperation::Start {
+                id: TimerId(1),
+                millis: 60 * 60 * 1000
+            }
+        );
+        assert_eq!(model.refresh_timer, Some(TimerId(1)));
+    }
+
+    #[test]
+    fn reschedule_on_interval_change() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let mut cmd = app.update(
+            Event::SetRefreshInterval(RefreshInterval::HoursTwo),
+            &mut model,
+            &(),
+        );
+        let operations: Vec<TimerOperation> = cmd
+            .effects()
+            .filter_map(|effect| match effect {
+                Effect::Timer(request) => Some(request.operation),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            operations,
+            vec![
+                TimerOperation::Clear { id: TimerId(1) },
+                TimerOperation::Start {
+                    id: TimerId(2),
+                    millis: 2 * 60 * 60 * 1000
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cancel_refresh() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let mut cmd = app.update(Event::CancelRefresh, &mut model, &());
+        let timer = cmd.effects().next().unwrap().expect_timer();
+
+        assert_eq!(timer.operation, TimerOperation::Clear { id: TimerId(1) });
+        assert_eq!(model.refresh_timer, None);
+    }
+
+    #[test]
+    fn timer_fetches_every_subscription() {
+        let app = App;
+        let (mut model, _) = model_with_subscriptions(&app);
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let mut cmd = app.update(
+            Event::RefreshTimerFired(TimerOutput::Finished { id: TimerId(1) }),
+            &mut model,
+            &(),
+        );
+        let mut urls = Vec::new();
+        let mut timers = Vec::new();
+        for effect in cmd.effects() {
+            match effect {
+                Effect::Http(request) => urls.push(request.operation.url),
+                Effect::Timer(request) => timers.push(request.operation),
+                _ => {}
+            }
+        }
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/folder.xml".to_string(),
+                "https://example.com/root.xml".to_string(),
+            ]
+        );
+        assert_eq!(
+            timers,
+            vec![TimerOperation::Start {
+                id: TimerId(2),
+                millis: RefreshInterval::default().as_millis()
+            }]
+        );
+        assert_eq!(model.refresh_timer, Some(TimerId(2)));
+        assert_eq!(
+            app.view(&model).refresh_progress,
+            RefreshProgress {
+                total: 2,
+                done: 0,
+                failed: 0,
+                new_entries_count: 0
+            }
+        );
+    }
+
+    #[test]
+    fn ignore_stale_timer() {
+        let app = App;
+        let (mut model, _) = model_with_subscriptions(&app);
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let mut cmd = app.update(
+            Event::RefreshTimerFired(TimerOutput::Finished { id: TimerId(1) }),
+            &mut model,
+            &(),
+        );
+
+        assert!(cmd.effects().next().is_none());
+        assert_eq!(model.refresh_progress, RefreshProgress::default());
+    }
+
+    #[test]
+    fn report_refresh_progress() {
+        let app = App;
+        let (mut model, account) = model_with_subscriptions(&app);
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let _ = app.update(
+            Event::RefreshTimerFired(TimerOutput::Finished { id: TimerId(1) }),
+            &mut model,
+            &(),
+        );
+        let response = ResponseBuilder::ok()
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+        let _ = app.update(
+            Event::FeedRefreshed(account.id.clone(), root_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+        let response = ResponseBuilder::ok().body(b"not a feed".to_vec()).build();
+        let _ = app.update(
+            Event::FeedRefreshed(account.id, root_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        let progress = app.view(&model).refresh_progress;
+
+        assert_eq!(
+            progress,
+            RefreshProgress {
+                total: 2,
+                done: 1,
+                failed: 1,
+                new_entries_count: 2
+            }
+        );
+        assert!(!progress.is_refreshing());
+        assert_eq!(model.accounts.acct[0].subs.feeds.feeds.len(), 1);
+    }
+
+    #[test]
+    fn refreshed_feed_for_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_subscriptions(&app);
+
+        let _ = app.update(Event::ScheduleRefresh, &mut model, &());
+        let _ = app.update(
+            Event::RefreshTimerFired(TimerOutput::Finished { id: TimerId(1) }),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        let response = ResponseBuilder::ok()
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+        let _ = app.update(
+            Event::FeedRefreshed(account.id, root_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.refresh_progress.failed, 1);
+    }
+
+    fn http_urls(cmd: &mut Command<Effect, Event>) -> Vec<String> {
+        cmd.effects()
+            .filter_map(|effect| match effect {
+                Effect::Http(request) => Some(request.operation.url),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn add_root_subscriptions(app: &App, model: &mut Model, account: &Account, count: usize) {
+        for n in 0..count {
+            add_subscription(
+                app,
+                model,
+                account,
+                None,
+                &format!("Feed {n}"),
+                &SubscriptionLink::from(format!("https://example.com/{n}.xml").as_str()),
+            );
+        }
+    }
+
+    #[test]
+    fn refresh_account_with_bounded_concurrency() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_root_subscriptions(&app, &mut model, &account, 5);
+        let _ = app.update(
+            Event::SetRefreshConcurrency(RefreshConcurrency(2)),
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(Event::RefreshAccount(account.clone()), &mut model, &());
+
+        assert_eq!(http_urls(&mut cmd).len(), 2);
+        assert_eq!(model.refresh_queue.len(), 3);
+        assert_eq!(model.refresh_progress.total, 5);
+
+        let mut cmd = app.update(
+            Event::FeedRefreshed(
+                account.id.clone(),
+                root_link(),
+                Ok(ResponseBuilder::ok()
+                    .body(EXAMPLE_RSS.as_bytes().to_vec())
+                    .build()),
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(http_urls(&mut cmd).len(), 1);
+        assert_eq!(model.refresh_queue.len(), 2);
+    }
+
+    #[test]
+    fn refresh_queue_drains() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_root_subscriptions(&app, &mut model, &account, 3);
+        let _ = app.update(
+            Event::SetRefreshConcurrency(RefreshConcurrency(1)),
+            &mut model,
+            &(),
+        );
+        let mut cmd = app.update(Event::RefreshAll, &mut model, &());
+        let mut fetched = http_urls(&mut cmd);
+
+        for _ in 0..3 {
+            let response = ResponseBuilder::ok().body(b"not a feed".to_vec()).build();
+            let mut cmd = app.update(
+                Event::FeedRefreshed(account.id.clone(), root_link(), Ok(response)),
+                &mut model,
+                &(),
+            );
+            fetched.extend(http_urls(&mut cmd));
+        }
+
+        assert_eq!(
+            fetched,
+            vec![
+                "https://example.com/0.xml".to_string(),
+                "https://example.com/1.xml".to_string(),
+                "https://example.com/2.xml".to_string(),
+            ]
+        );
+        assert_eq!(
+            app.view(&model).refresh_progress,
+            RefreshProgress {
+                total: 3,
+                done: 0,
+                failed: 3,
+                new_entries_count: 0
+            }
+        );
+        assert!(model.refresh_queue.is_empty());
+    }
+
+    #[test]
+    fn refresh_all_accounts() {
+        let app = App;
+        let (mut model, _) = model_with_subscriptions(&app);
+
+        let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+        let apple = model.accounts.acct[1].clone();
+        add_root_subscriptions(&app, &mut model, &apple, 1);
+        let mut cmd = app.update(Event::RefreshAll, &mut model, &());
+
+        assert_eq!(http_urls(&mut cmd).len(), 3);
+        assert_eq!(model.refresh_progress.total, 3);
+    }
+
+    #[test]
+    fn refresh_while_refreshing_skips_queued_links() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_root_subscriptions(&app, &mut model, &account, 3);
+        let _ = app.update(
+            Event::SetRefreshConcurrency(RefreshConcurrency(1)),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::RefreshAll, &mut model, &());
+        let mut cmd = app.update(Event::RefreshAccount(account), &mut model, &());
+
+        assert!(http_urls(&mut cmd).is_empty());
+        // NOTE the link in flight is queued again, the two waiting ones are not
+        assert_eq!(model.refresh_queue.len(), 3);
+        assert_eq!(model.refresh_progress.total, 4);
+    }
+
+    #[test]
+    fn skip_queued_links_of_deleted_account() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_root_subscriptions(&app, &mut model, &account, 3);
+        let _ = app.update(
+            Event::SetRefreshConcurrency(RefreshConcurrency(1)),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::RefreshAll, &mut model, &());
+        let _ = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+        let response = ResponseBuilder::ok()
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+        let mut cmd = app.update(
+            Event::FeedRefreshed(account.id, root_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert!(http_urls(&mut cmd).is_empty());
+        assert!(model.refresh_queue.is_empty());
+        assert_eq!(model.refresh_in_flight, 0);
+        assert_eq!(
+            model.refresh_progress,
+            RefreshProgress {
+                total: 3,
+                done: 0,
+                failed: 3,
+                new_entries_count: 0
+            }
+        );
+    }
+
+    #[test]
+    fn late_response_keeps_in_flight_at_zero() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let response = ResponseBuilder::ok().body(b"not a feed".to_vec()).build();
+
+        let _ = app.update(
+            Event::FeedRefreshed(account.id, root_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.refresh_in_flight, 0);
+    }
+}
@@ -0,0 +1,169 @@
+// NOTE - feed HTML is untrusted, so it's cleaned here before any shell puts it in a web view.
+// NOTE - only allow-listed tags and attributes are kept, and relative links are made absolute.
+use super::ArticleView;
+
+use ammonia::{Builder, UrlRelative};
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use url::Url;
+
+// NOTE dropped together with their content. every other unknown tag only loses its markup.
+const REMOVED_CONTENT_TAGS: [&str; 7] = [
+    "script", "style", "iframe", "object", "embed", "noscript", "template",
+];
+// NOTE on top of ammonia's defaults, so podcasts and videos can still play
+const MEDIA_TAGS: [&str; 3] = ["audio", "video", "source"];
+const MEDIA_ATTRIBUTES: [&str; 5] = ["src", "controls", "poster", "type", "preload"];
+const TRACKING_PIXEL_SIZE: u32 = 1;
+
+trait SanitiseHelpers {
+    fn is_tracking_pixel(width: Option<&str>, height: Option<&str>) -> bool;
+    fn remove_tracking_pixels(html: &str) -> String;
+}
+
+impl SanitiseHelpers for ArticleView {
+    // NOTE an image is a pixel if either side is at most 1px, e.g. width="1" or height="0"
+    fn is_tracking_pixel(width: Option<&str>, height: Option<&str>) -> bool {
+        [width, height].iter().flatten().any(|size| {
+            size.trim()
+                .trim_end_matches("px")
+                .parse::<u32>()
+                .is_ok_and(|size| size <= TRACKING_PIXEL_SIZE)
+        })
+    }
+
+    fn remove_tracking_pixels(html: &str) -> String {
+        let mut fragment = Html::parse_fragment(html);
+        let selector = Selector::parse("img").unwrap();
+
+        let pixels: Vec<_> = fragment
+            .select(&selector)
+            .filter(|img| {
+                Self::is_tracking_pixel(img.value().attr("width"), img.value().attr("height"))
+            })
+            .map(|img| img.id())
+            .collect();
+        for pixel in pixels {
+            if let Some(mut node) = fragment.tree.get_mut(pixel) {
+                node.detach();
+            }
+        }
+
+        fragment.root_element().inner_html()
+    }
+}
+
+trait SanitiseHtml {
+    fn sanitise_html(html: &str, base: &Option<Url>) -> String;
+}
+
+impl SanitiseHtml for ArticleView {
+    // NOTE without a base, relative links can't be resolved, so they are dropped
+    fn sanitise_html(html: &str, base: &Option<Url>) -> String {
+        let url_relative = match base {
+            Some(base) => UrlRelative::RewriteWithBase(base.clone()),
+            None => UrlRelative::Deny,
+        };
+
+        Builder::default()
+            .clean_content_tags(HashSet::from(REMOVED_CONTENT_TAGS))
+            .add_tags(MEDIA_TAGS)
+            .add_tag_attributes("audio", MEDIA_ATTRIBUTES)
+            .add_tag_attributes("video", MEDIA_ATTRIBUTES)
+            .add_tag_attributes("source", MEDIA_ATTRIBUTES)
+            .url_relative(url_relative)
+            .clean(&Self::remove_tracking_pixels(html))
+            .to_string()
+    }
+}
+
+impl ArticleView {
+    pub fn sanitise_html(html: &str, base: &Option<Url>) -> String {
+        <Self as SanitiseHtml>::sanitise_html(html, base)
+    }
+}
+
+#[cfg(test)]
+mod sanitised_html {
+    use super::*;
+    use crate::fixtures::{add_subscription, fetched, model_with_account, rss_link, UNSAFE_RSS};
+    use crate::{App, EntryId, Event};
+    use crux_core::App as _;
+
+    fn base() -> Option<Url> {
+        Url::parse("https://example.com/blog/").ok()
+    }
+
+    #[test]
+    fn strip_scripts_and_event_handlers() {
+        let html = r#"<p onclick="steal()" style="color: red">Hello</p><script>steal()</script>"#;
+
+        assert_eq!(ArticleView::sanitise_html(html, &base()), "<p>Hello</p>");
+    }
+
+    #[test]
+    fn strip_iframes_with_their_content() {
+        let html = r#"<p>Video</p><iframe src="https://tracker.example.com/">Fallback</iframe>"#;
+
+        assert_eq!(ArticleView::sanitise_html(html, &base()), "<p>Video</p>");
+    }
+
+    #[test]
+    fn strip_tracking_pixels() {
+        let html = r#"<img src="https://example.com/photo.jpg" width="640"><img src="https://tracker.example.com/p.gif" width="1" height="1"><img src="https://tracker.example.com/q.gif" height="0px">"#;
+
+        assert_eq!(
+            ArticleView::sanitise_html(html, &base()),
+            r#"<img src="https://example.com/photo.jpg" width="640">"#
+        );
+    }
+
+    #[test]
+    fn resolve_relative_links() {
+        let html = r#"<a href="post.html">Post</a><img src="/images/photo.jpg">"#;
+
+        assert_eq!(
+            ArticleView::sanitise_html(html, &base()),
+            r#"<a href="https://example.com/blog/post.html" rel="noopener noreferrer">Post</a><img src="https://example.com/images/photo.jpg">"#
+        );
+    }
+
+    #[test]
+    fn drop_relative_links_without_base() {
+        let html = r#"<a href="post.html">Post</a>"#;
+
+        assert_eq!(
+            ArticleView::sanitise_html(html, &None),
+            r#"<a rel="noopener noreferrer">Post</a>"#
+        );
+    }
+
+    #[test]
+    fn keep_media_players() {
+        let html = r#"<audio controls src="https://example.com/one.mp3" autoplay></audio>"#;
+
+        assert_eq!(
+            ArticleView::sanitise_html(html, &base()),
+            r#"<audio controls="" src="https://example.com/one.mp3"></audio>"#
+        );
+    }
+
+    #[test]
+    fn sanitise_article_content() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        fetched(&app, &mut model, &account, &rss_link(), UNSAFE_RSS);
+        let _ = app.update(
+            Event::SelectEntry(account, EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            app.view(&model).article.unwrap().content_html,
+            r#"<p>See <a href="https://example.com/about" rel="noopener noreferrer">about</a></p>"#
+        );
+    }
+}
@@ -60,6 +60,21 @@ pub enum RefreshInterval {
     HoursFour,
 }
 
+impl RefreshInterval {
+    pub fn as_millis(&self) -> u64 {
+        let minutes = match self {
+            RefreshInterval::MinutesFifteen => 15,
+            RefreshInterval::MinutesThirty => 30,
+            RefreshInterval::HoursOne => 60,
+            RefreshInterval::HoursTwo => 120,
+            RefreshInterval::HoursThree => 180,
+            RefreshInterval::HoursFour => 240,
+        };
+
+        minutes * 60 * 1000
+    }
+}
+
 // pub fn read_config() -> Result<HashMap<String, String>, ConfigError> {
 //     let settings = Config::builder()
 //         .add_source(config::File::from_str("wrong", config::FileFormat::Toml))
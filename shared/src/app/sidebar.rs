@@ -0,0 +1,262 @@
+// NOTE - the sidebar every shell renders: accounts, then their folders and subscriptions.
+// NOTE - built from Model.accounts on every view, so the tree can't drift from the subscriptions.
+use super::{Account, AccountId, AccountType, Accounts, Feeds, FolderId, FolderName, FolderPath};
+use super::{SubscriptionId, SubscriptionLink};
+
+use opml::Outline;
+use serde::{Deserialize, Serialize};
+
+// NOTE what a node stands for. shells send it back to select the node
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SidebarItem {
+    Account(AccountId),
+    Folder(AccountId, FolderId),
+    Subscription(AccountId, SubscriptionId),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SidebarIcon {
+    Account(AccountType),
+    Folder,
+    Subscription(Option<String>), // the feed's icon or logo, None until fetched or if it has none
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SidebarNode {
+    pub item: SidebarItem,
+    pub title: String,
+    pub icon: SidebarIcon,
+    pub unread_count: usize, // folders and accounts count their whole subtree
+    pub is_selected: bool,
+    pub children: Vec<SidebarNode>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct SidebarView {
+    pub nodes: Vec<SidebarNode>,
+}
+
+trait SidebarHelpers {
+    fn feed_icon(feeds: &Feeds, sub_link: &SubscriptionLink) -> Option<String>;
+    fn outline_nodes(
+        account: &Account,
+        parent: &FolderPath,
+        outlines: &[Outline],
+        selected: &Option<SidebarItem>,
+    ) -> Vec<SidebarNode>;
+}
+
+impl SidebarHelpers for SidebarView {
+    fn feed_icon(feeds: &Feeds, sub_link: &SubscriptionLink) -> Option<String> {
+        feeds
+            .feeds
+            .get(&SubscriptionId::from(sub_link))
+            .and_then(|feed| feed.icon.as_ref().or(feed.logo.as_ref()))
+            .map(|image| image.uri.to_string())
+    }
+
+    // NOTE nodes keep the order of the outlines, as the shells show them
+    fn outline_nodes(
+        account: &Account,
+        parent: &FolderPath,
+        outlines: &[Outline],
+        selected: &Option<SidebarItem>,
+    ) -> Vec<SidebarNode> {
+        let subs = &account.subs;
+
+        outlines
+            .iter()
+            .filter_map(|outline| match outline.xml_url.as_deref() {
+                Some(sub_link) => {
+                    let sub_link = SubscriptionLink::from(sub_link);
+                    let item = SidebarItem::Subscription(
+                        account.id.clone(),
+                        SubscriptionId::from(&sub_link),
+                    );
+
+                    Some(SidebarNode {
+                        is_selected: selected.as_ref() == Some(&item),
+                        item,
+                        title: outline.text.to_string(),
+                        icon: SidebarIcon::Subscription(Self::feed_icon(&subs.feeds, &sub_link)),
+                        unread_count: subs.feeds.count_feed_unread(&sub_link),
+                        children: vec![],
+                    })
+                }
+                None => {
+                    let folder_path = parent.join(&FolderName::from(outline.text.as_str()));
+                    // NOTE a folder without an id can't be addressed, so it isn't shown
+                    let folder_id = subs.find_folder_id(&folder_path)?;
+                    let item = SidebarItem::Folder(account.id.clone(), folder_id);
+                    let children =
+                        Self::outline_nodes(account, &folder_path, &outline.outlines, selected);
+
+                    Some(SidebarNode {
+                        is_selected: selected.as_ref() == Some(&item),
+                        item,
+                        title: outline.text.to_string(),
+                        icon: SidebarIcon::Folder,
+                        unread_count: children.iter().map(|child| child.unread_count).sum(),
+                        children,
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+trait SidebarViewBuilder {
+    fn sidebar_view(accounts: &Accounts, selected: &Option<SidebarItem>) -> SidebarView;
+}
+
+impl SidebarViewBuilder for SidebarView {
+    fn sidebar_view(accounts: &Accounts, selected: &Option<SidebarItem>) -> SidebarView {
+        let nodes = accounts
+            .acct
+            .iter()
+            .map(|account| {
+                let item = SidebarItem::Account(account.id.clone());
+                let children = Self::outline_nodes(
+                    account,
+                    &FolderPath::default(),
+                    &account.subs.subs.body.outlines,
+                    selected,
+                );
+
+                SidebarNode {
+                    is_selected: selected.as_ref() == Some(&item),
+                    item,
+                    title: account.name.to_string(),
+                    icon: SidebarIcon::Account(account.account_type.clone()),
+                    unread_count: children.iter().map(|child| child.unread_count).sum(),
+                    children,
+                }
+            })
+            .collect();
+
+        SidebarView { nodes }
+    }
+}
+
+impl SidebarView {
+    pub fn new(accounts: &Accounts, selected: &Option<SidebarItem>) -> Self {
+        <Self as SidebarViewBuilder>::sidebar_view(accounts, selected)
+    }
+}
+
+#[cfg(test)]
+mod sidebar_tree {
+    use super::*;
+    use crate::fixtures::{add_subscription, fetched, folder_id, model_with_nested_folder};
+    use crate::fixtures::{atom_link, rss_link, EXAMPLE_RSS};
+    use crate::{App, Model};
+    use crux_core::App as _;
+
+    fn engineering() -> FolderPath {
+        FolderPath::from("Engineering")
+    }
+
+    fn rust() -> FolderPath {
+        engineering().join(&FolderName::from("Rust"))
+    }
+
+    fn model_with_tree(app: &App) -> (Model, Account) {
+        let (mut model, account, rust_id) = model_with_nested_folder(app);
+
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(rust_id),
+            "Feed Name",
+            &rss_link(),
+        );
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            None,
+            "Unfetched Feed",
+            &atom_link(),
+        );
+        fetched(app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+
+        (model, account)
+    }
+
+    #[test]
+    fn build_tree_from_accounts() {
+        let app = App;
+        let (model, account) = model_with_tree(&app);
+
+        let view = app.view(&model).sidebar;
+        let account_node = &view.nodes[0];
+        let engineering_node = &account_node.children[0];
+        let rust_node = &engineering_node.children[0];
+
+        assert_eq!(view.nodes.len(), 1);
+        assert_eq!(account_node.item, SidebarItem::Account(account.id.clone()));
+        assert_eq!(account_node.icon, SidebarIcon::Account(AccountType::Local));
+        assert_eq!(engineering_node.title, "Engineering");
+        assert_eq!(engineering_node.icon, SidebarIcon::Folder);
+        assert_eq!(
+            rust_node.item,
+            SidebarItem::Folder(account.id.clone(), folder_id(&model, &rust()))
+        );
+        assert_eq!(
+            rust_node.children[0].item,
+            SidebarItem::Subscription(account.id, SubscriptionId::from(&rss_link()))
+        );
+        assert_eq!(rust_node.children[0].title, "Feed Name");
+        assert_eq!(account_node.children[1].title, "Unfetched Feed");
+    }
+
+    #[test]
+    fn roll_up_unread_counts() {
+        let app = App;
+        let (model, _) = model_with_tree(&app);
+
+        let view = app.view(&model).sidebar;
+        let account_node = &view.nodes[0];
+
+        assert_eq!(account_node.unread_count, 2);
+        assert_eq!(account_node.children[0].unread_count, 2);
+        assert_eq!(account_node.children[0].children[0].unread_count, 2);
+        assert_eq!(account_node.children[1].unread_count, 0);
+    }
+
+    #[test]
+    fn show_feed_icon_once_fetched() {
+        let app = App;
+        let (model, _) = model_with_tree(&app);
+
+        let view = app.view(&model).sidebar;
+        let account_node = &view.nodes[0];
+
+        assert_eq!(
+            account_node.children[0].children[0].children[0].icon,
+            SidebarIcon::Subscription(Some("https://example.com/logo.png".to_string()))
+        );
+        assert_eq!(
+            account_node.children[1].icon,
+            SidebarIcon::Subscription(None)
+        );
+    }
+
+    #[test]
+    fn mark_selected_node() {
+        let app = App;
+        let (mut model, account) = model_with_tree(&app);
+
+        model.sidebar_selection = Some(SidebarItem::Folder(
+            account.id,
+            folder_id(&model, &engineering()),
+        ));
+        let view = app.view(&model).sidebar;
+        let account_node = &view.nodes[0];
+
+        assert!(!account_node.is_selected);
+        assert!(account_node.children[0].is_selected);
+        assert!(!account_node.children[0].children[0].is_selected);
+    }
+}
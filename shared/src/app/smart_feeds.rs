@@ -0,0 +1,316 @@
+use super::{Account, AccountId, Accounts, EntryId, EntryStatus, ReadStatus, StarStatus};
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use feed_rs::model::Entry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// NOTE these mirror today_view, unread_view and starred_view in the migrations,
+// NOTE but are computed from Feeds so they work without the storage adapter.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub enum SmartFeed {
+    #[default]
+    Today,
+    AllUnread,
+    Starred,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct SmartFeedEntry {
+    pub account_id: AccountId, // to address the entry in events
+    pub account_name: String,
+    pub feed_title: String,
+    pub entry_id: EntryId,
+    pub entry_title: String,
+    pub entry_published: String, // as ENTRY_DATE_FORMAT, empty when the feed doesn't say
+    pub entry_status: EntryStatus,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct SmartFeedsView {
+    pub selected: Option<SmartFeed>,
+    pub today_count: usize, // entries published today, read or not, like the Today list
+    pub all_unread_count: usize,
+    pub starred_count: usize,
+    pub entries: Vec<SmartFeedEntry>,
+}
+
+trait SmartFeedHelpers {
+    fn published_on(entry: &Entry) -> Option<NaiveDate>;
+}
+
+impl SmartFeedHelpers for SmartFeed {
+    // NOTE feeds without a published date fall back to the updated one
+    fn published_on(entry: &Entry) -> Option<NaiveDate> {
+        Account::entry_date(entry).map(|date| date.with_timezone(&Local).date_naive())
+    }
+}
+
+trait MatchEntry {
+    fn match_entry(&self, entry: &Entry, status: &EntryStatus, today: &NaiveDate) -> bool;
+}
+
+impl MatchEntry for SmartFeed {
+    fn match_entry(&self, entry: &Entry, status: &EntryStatus, today: &NaiveDate) -> bool {
+        match self {
+            SmartFeed::Today => Self::published_on(entry).as_ref() == Some(today),
+            SmartFeed::AllUnread => status.read == ReadStatus::Unread,
+            SmartFeed::Starred => status.star == StarStatus::Starred,
+        }
+    }
+}
+
+// NOTE entries come with their date, to sort them by
+type DatedSmartFeedEntry = (Option<DateTime<Utc>>, SmartFeedEntry);
+
+trait SmartFeedEntries {
+    fn smart_feed_entries(
+        &self,
+        smart_feed: &SmartFeed,
+        today: &NaiveDate,
+    ) -> Vec<DatedSmartFeedEntry>;
+}
+
+impl SmartFeedEntries for Account {
+    fn smart_feed_entries(
+        &self,
+        smart_feed: &SmartFeed,
+        today: &NaiveDate,
+    ) -> Vec<DatedSmartFeedEntry> {
+        let feeds = &self.subs.feeds;
+        let mut listed = HashSet::new();
+
+        // NOTE an entry two subscriptions share is listed once
+        feeds
+            .feeds
+            .values()
+            .flat_map(|feed| feed.entries.iter().map(move |entry| (feed, entry)))
+            .filter(|(_, entry)| listed.insert(entry.id.as_str()))
+            .filter_map(|(feed, entry)| {
+                let entry_id = EntryId::from(entry.id.as_str());
+                let entry_status = feeds.find_entry_status(&entry_id);
+                let entry_date = Account::entry_date(entry);
+
+                smart_feed
+                    .match_entry(entry, &entry_status, today)
+                    .then(|| {
+                        (
+                            entry_date,
+                            SmartFeedEntry {
+                                account_id: self.id.clone(),
+                                account_name: self.name.to_string(),
+                                feed_title: Account::feed_title(feed),
+                                entry_id,
+                                entry_title: Account::entry_title(entry),
+                                entry_published: entry_date
+                                    .as_ref()
+                                    .map(Account::format_date)
+                                    .unwrap_or_default(),
+                                entry_status,
+                            },
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+trait SmartFeedsViewBuilder {
+    fn smart_feeds_view(
+        accounts: &Accounts,
+        selected: &Option<SmartFeed>,
+        today: &NaiveDate,
+    ) -> SmartFeedsView;
+}
+
+impl SmartFeedsViewBuilder for SmartFeedsView {
+    fn smart_feeds_view(
+        accounts: &Accounts,
+        selected: &Option<SmartFeed>,
+        today: &NaiveDate,
+    ) -> SmartFeedsView {
+        let collect = |smart_feed: &SmartFeed| -> Vec<SmartFeedEntry> {
+            let mut entries: Vec<DatedSmartFeedEntry> = accounts
+                .acct
+                .iter()
+                .flat_map(|account| account.smart_feed_entries(smart_feed, today))
+                .collect();
+            // NOTE newest first, undated entries last
+            entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+            entries.into_iter().map(|(_, entry)| entry).collect()
+        };
+
+        SmartFeedsView {
+            selected: selected.clone(),
+            today_count: collect(&SmartFeed::Today).len(),
+            all_unread_count: collect(&SmartFeed::AllUnread).len(),
+            starred_count: collect(&SmartFeed::Starred).len(),
+            entries: selected.as_ref().map(collect).unwrap_or_default(),
+        }
+    }
+}
+
+impl SmartFeedsView {
+    pub fn new(accounts: &Accounts, selected: &Option<SmartFeed>) -> Self {
+        <Self as SmartFeedsViewBuilder>::smart_feeds_view(
+            accounts,
+            selected,
+            &Local::now().date_naive(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod smart_feed {
+    use super::*;
+    use crate::fixtures::{add_subscription, fetched, model_with_account, rss_link, EXAMPLE_RSS};
+    use crate::{App, Event, Model, ENTRY_DATE_FORMAT};
+    use chrono::{Duration, Utc};
+    use crux_core::App as _;
+
+    fn example_rss() -> String {
+        let today = Utc::now().to_rfc2822();
+        let last_week = (Utc::now() - Duration::days(7)).to_rfc2822();
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><item><guid>https://example.com/today</guid><title>Today Entry</title><pubDate>{today}</pubDate></item><item><guid>https://example.com/last-week</guid><title>Last Week Entry</title><pubDate>{last_week}</pubDate></item></channel></rss>"#
+        )
+    }
+
+    fn model_with_dated_feed(app: &App) -> (Model, Account) {
+        let (mut model, account) = model_with_account(app);
+
+        add_subscription(app, &mut model, &account, None, "Feed Name", &rss_link());
+        fetched(app, &mut model, &account, &rss_link(), &example_rss());
+
+        (model, account)
+    }
+
+    #[test]
+    fn no_smart_feed_selected() {
+        let app = App;
+        let (model, _) = model_with_dated_feed(&app);
+
+        let view = app.view(&model).smart_feeds;
+
+        assert_eq!(view.selected, None);
+        assert!(view.entries.is_empty());
+        assert_eq!(view.today_count, 1);
+        assert_eq!(view.all_unread_count, 2);
+        assert_eq!(view.starred_count, 0);
+    }
+
+    #[test]
+    fn select_today() {
+        let app = App;
+        let (mut model, account) = model_with_dated_feed(&app);
+
+        let _ = app.update(Event::SelectSmartFeed(SmartFeed::Today), &mut model, &());
+        let view = app.view(&model).smart_feeds;
+        let entries: Vec<(AccountId, EntryId)> = view
+            .entries
+            .into_iter()
+            .map(|e| (e.account_id, e.entry_id))
+            .collect();
+
+        assert_eq!(view.selected, Some(SmartFeed::Today));
+        assert_eq!(
+            entries,
+            vec![(account.id, EntryId::from("https://example.com/today"))]
+        );
+    }
+
+    #[test]
+    fn select_all_unread() {
+        let app = App;
+        let (mut model, account) = model_with_dated_feed(&app);
+
+        let _ = app.update(
+            Event::MarkEntryRead(account, EntryId::from("https://example.com/today")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::SelectSmartFeed(SmartFeed::AllUnread),
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model).smart_feeds;
+        let entry_ids: Vec<EntryId> = view.entries.into_iter().map(|e| e.entry_id).collect();
+
+        assert_eq!(view.today_count, 1);
+        assert_eq!(view.all_unread_count, 1);
+        assert_eq!(
+            entry_ids,
+            vec![EntryId::from("https://example.com/last-week")]
+        );
+    }
+
+    #[test]
+    fn select_starred() {
+        let app = App;
+        let (mut model, account) = model_with_dated_feed(&app);
+
+        let _ = app.update(
+            Event::ToggleStar(account, EntryId::from("https://example.com/last-week")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(Event::SelectSmartFeed(SmartFeed::Starred), &mut model, &());
+        let view = app.view(&model).smart_feeds;
+
+        assert_eq!(view.starred_count, 1);
+        assert_eq!(view.entries.len(), 1);
+        assert_eq!(view.entries[0].entry_title, "Last Week Entry");
+        assert_eq!(view.entries[0].feed_title, "Feed Name");
+        assert_eq!(view.entries[0].entry_status.star, StarStatus::Starred);
+    }
+
+    #[test]
+    fn smart_feed_entries_are_newest_first() {
+        let app = App;
+        let (mut model, _) = model_with_dated_feed(&app);
+
+        let _ = app.update(
+            Event::SelectSmartFeed(SmartFeed::AllUnread),
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model).smart_feeds;
+        let entry_titles: Vec<String> = view.entries.into_iter().map(|e| e.entry_title).collect();
+
+        assert_eq!(entry_titles, vec!["Today Entry", "Last Week Entry"]);
+    }
+
+    #[test]
+    fn smart_feed_dates_are_formatted_like_entry_lists() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        fetched(&app, &mut model, &account, &rss_link(), EXAMPLE_RSS);
+        let _ = app.update(
+            Event::SelectSmartFeed(SmartFeed::AllUnread),
+            &mut model,
+            &(),
+        );
+        let view = app.view(&model).smart_feeds;
+        let published: Vec<String> = view
+            .entries
+            .into_iter()
+            .map(|e| e.entry_published)
+            .collect();
+        let expected: Vec<String> = ["2025-10-06T09:30:00Z", "2025-10-05T09:30:00Z"]
+            .iter()
+            .map(|date| {
+                DateTime::parse_from_rfc3339(date)
+                    .unwrap()
+                    .with_timezone(&Local)
+                    .format(ENTRY_DATE_FORMAT)
+                    .to_string()
+            })
+            .collect();
+
+        assert_eq!(published, expected);
+    }
+}
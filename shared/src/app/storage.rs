@@ -0,0 +1,406 @@
+// NOTE - this is the port to the driven adapter/repository. The shell fulfils it.
+// NOTE - see sqlite_adapter for the Rust adapter against the migrations schema.
+// NOTE - every change is saved on its own, so only what changed is written.
+use super::{AccountId, AccountType, Accounts, Effect, EntryId, EntryStatus, Event, Model};
+use super::{FolderId, FolderPath, HttpCache, Subscription, SubscriptionId, SubscriptionLink};
+
+use crux_core::{capability::Operation, command::RequestBuilder, Command};
+use feed_rs::model::Feed;
+use opml::OPML;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum StorageOperation {
+    Load,
+    // NOTE the account itself. what it holds is saved by the operations below.
+    SaveAccount(AccountId, AccountType, String),
+    // NOTE everything the account holds goes with it
+    DeleteAccount(AccountId),
+    // NOTE replaces the account's folders and subscriptions. feeds no subscription links to go.
+    SaveSubscriptions(AccountId, Box<OPML>, HashMap<FolderId, FolderPath>),
+    // NOTE replaces the feed and its entries, and the subscription it was fetched from
+    SaveFeed(AccountId, Box<Subscription>, Box<Feed>, HttpCache),
+    // NOTE entries back to unread and unstarred have their status removed
+    SaveEntryStatus(AccountId, HashMap<EntryId, EntryStatus>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum StorageResponse {
+    Loaded(Accounts),
+    Saved,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum StorageError {
+    #[error("{message}")]
+    Io { message: String },
+}
+
+pub type StorageResult = Result<StorageResponse, StorageError>;
+
+impl Operation for StorageOperation {
+    type Output = StorageResult;
+}
+
+pub struct Storage;
+
+impl Storage {
+    pub fn load() -> RequestBuilder<Effect, Event, impl Future<Output = StorageResult>> {
+        Command::request_from_shell(StorageOperation::Load)
+    }
+
+    pub fn save(
+        operation: StorageOperation,
+    ) -> RequestBuilder<Effect, Event, impl Future<Output = StorageResult>> {
+        Command::request_from_shell(operation)
+    }
+}
+
+trait SaveChanges {
+    fn save_account(&self, account_index: usize) -> Command<Effect, Event>;
+    fn delete_stored_account(account_id: &AccountId) -> Command<Effect, Event>;
+    fn save_subscriptions(&self, account_index: usize) -> Command<Effect, Event>;
+    fn save_feed(
+        &self,
+        account_index: usize,
+        sub_link: &SubscriptionLink,
+    ) -> Command<Effect, Event>;
+    fn save_entry_status(
+        &self,
+        account_index: usize,
+        entry_ids: Vec<EntryId>,
+    ) -> Command<Effect, Event>;
+}
+
+impl SaveChanges for Model {
+    fn save_account(&self, account_index: usize) -> Command<Effect, Event> {
+        let account = &self.accounts.acct[account_index];
+
+        Storage::save(StorageOperation::SaveAccount(
+            account.id.clone(),
+            account.account_type.clone(),
+            account.name.clone(),
+        ))
+        .then_send(Event::StateSaved)
+    }
+
+    fn delete_stored_account(account_id: &AccountId) -> Command<Effect, Event> {
+        Storage::save(StorageOperation::DeleteAccount(account_id.clone()))
+            .then_send(Event::StateSaved)
+    }
+
+    fn save_subscriptions(&self, account_index: usize) -> Command<Effect, Event> {
+        let account = &self.accounts.acct[account_index];
+
+        Storage::save(StorageOperation::SaveSubscriptions(
+            account.id.clone(),
+            Box::new(account.subs.subs.clone()),
+            account.subs.folder_ids.clone(),
+        ))
+        .then_send(Event::StateSaved)
+    }
+
+    // NOTE the subscription goes along, as its metadata is filled from the feed
+    fn save_feed(
+        &self,
+        account_index: usize,
+        sub_link: &SubscriptionLink,
+    ) -> Command<Effect, Event> {
+        let account = &self.accounts.acct[account_index];
+        let sub = account
+            .subs
+            .find_subscription_by_id(&SubscriptionId::from(sub_link));
+
+        match (sub, account.subs.find_feed(sub_link)) {
+            (Some((_, sub)), Ok(feed)) => Storage::save(StorageOperation::SaveFeed(
+                account.id.clone(),
+                Box::new(sub),
+                Box::new(feed),
+                account
+                    .subs
+                    .http_cache
+                    .get(sub_link)
+                    .cloned()
+                    .unwrap_or_default(),
+            ))
+            .then_send(Event::StateSaved),
+            _ => Command::done(),
+        }
+    }
+
+    fn save_entry_status(
+        &self,
+        account_index: usize,
+        entry_ids: Vec<EntryId>,
+    ) -> Command<Effect, Event> {
+        let account = &self.accounts.acct[account_index];
+        let status = entry_ids
+            .into_iter()
+            .map(|entry_id| {
+                let entry_status = account.subs.feeds.find_entry_status(&entry_id);
+                (entry_id, entry_status)
+            })
+            .collect();
+
+        Storage::save(StorageOperation::SaveEntryStatus(
+            account.id.clone(),
+            status,
+        ))
+        .then_send(Event::StateSaved)
+    }
+}
+
+impl Model {
+    pub(crate) fn save_account(&self, account_index: usize) -> Command<Effect, Event> {
+        <Self as SaveChanges>::save_account(self, account_index)
+    }
+
+    pub(crate) fn delete_stored_account(account_id: &AccountId) -> Command<Effect, Event> {
+        <Self as SaveChanges>::delete_stored_account(account_id)
+    }
+
+    pub(crate) fn save_subscriptions(&self, account_index: usize) -> Command<Effect, Event> {
+        <Self as SaveChanges>::save_subscriptions(self, account_index)
+    }
+
+    pub(crate) fn save_feed(
+        &self,
+        account_index: usize,
+        sub_link: &SubscriptionLink,
+    ) -> Command<Effect, Event> {
+        <Self as SaveChanges>::save_feed(self, account_index, sub_link)
+    }
+
+    pub(crate) fn save_entry_status(
+        &self,
+        account_index: usize,
+        entry_id: &EntryId,
+    ) -> Command<Effect, Event> {
+        <Self as SaveChanges>::save_entry_status(self, account_index, vec![entry_id.clone()])
+    }
+
+    // NOTE subscriptions which haven't been fetched yet have no entries to save
+    pub(crate) fn save_feeds_status(
+        &self,
+        account_index: usize,
+        sub_links: &[SubscriptionLink],
+    ) -> Command<Effect, Event> {
+        let subs = &self.accounts.acct[account_index].subs;
+        let entry_ids = sub_links
+            .iter()
+            .filter_map(|sub_link| subs.find_feed(sub_link).ok())
+            .flat_map(|feed| feed.entries)
+            .map(|entry| EntryId::from(entry.id))
+            .collect();
+
+        <Self as SaveChanges>::save_entry_status(self, account_index, entry_ids)
+    }
+}
+
+#[cfg(test)]
+mod persistence {
+    use super::*;
+    use crate::fixtures::{model_with_feed, rss_link, EXAMPLE_RSS};
+    use crate::{Account, App, Model, ReadStatus, StarStatus};
+    use assert_matches::assert_matches;
+    use crux_core::App as _;
+    use crux_http::{http::StatusCode, testing::ResponseBuilder};
+
+    fn storage_operations(mut cmd: Command<Effect, Event>) -> Vec<StorageOperation> {
+        cmd.effects()
+            .filter_map(|effect| match effect {
+                Effect::Storage(request) => Some(request.operation.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn load_state_from_storage() {
+        let app = App;
+        let mut model = Model::default();
+        let mut stored_accounts = Accounts::default();
+        stored_accounts.acct.push(Account::new(&AccountType::Local));
+
+        let mut cmd = app.update(Event::LoadState, &mut model, &());
+        let mut request = assert_matches!(
+            cmd.effects().next(),
+            Some(Effect::Storage(request)) => request
+        );
+        assert_eq!(request.operation, StorageOperation::Load);
+
+        request
+            .resolve(Ok(StorageResponse::Loaded(stored_accounts.clone())))
+            .unwrap();
+        let event = cmd.events().next().unwrap();
+        let mut cmd = app.update(event, &mut model, &());
+
+        assert_eq!(model.accounts, stored_accounts);
+        assert!(!cmd.effects().any(|e| matches!(e, Effect::Storage(_))));
+    }
+
+    #[test]
+    fn save_created_account() {
+        let app = App;
+        let mut model = Model::default();
+
+        let cmd = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = &model.accounts.acct[0];
+
+        assert_eq!(
+            storage_operations(cmd),
+            vec![StorageOperation::SaveAccount(
+                account.id.clone(),
+                AccountType::Local,
+                account.name.clone(),
+            )]
+        );
+    }
+
+    #[test]
+    fn no_save_state_without_change() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let mut cmd = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+
+        assert!(!cmd.effects().any(|e| matches!(e, Effect::Storage(_))));
+    }
+
+    #[test]
+    fn delete_stored_account() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+
+        let cmd = app.update(Event::DeleteAccount(account.clone()), &mut model, &());
+
+        assert_eq!(
+            storage_operations(cmd),
+            vec![StorageOperation::DeleteAccount(account.id)]
+        );
+    }
+
+    #[test]
+    fn save_only_the_marked_entry_status() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId::from("https://example.com/one");
+
+        let cmd = app.update(
+            Event::MarkEntryRead(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let entry_status = EntryStatus {
+            read: ReadStatus::Read,
+            star: StarStatus::Unstarred,
+        };
+
+        assert_eq!(
+            storage_operations(cmd),
+            vec![StorageOperation::SaveEntryStatus(
+                account.id,
+                HashMap::from([(entry_id, entry_status)]),
+            )]
+        );
+    }
+
+    #[test]
+    fn no_save_when_selecting_a_read_entry() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let entry_id = EntryId::from("https://example.com/one");
+
+        let first_cmd = app.update(
+            Event::SelectEntry(account.clone(), entry_id.clone()),
+            &mut model,
+            &(),
+        );
+        let again_cmd = app.update(Event::SelectEntry(account, entry_id), &mut model, &());
+
+        assert_eq!(storage_operations(first_cmd).len(), 1);
+        assert!(storage_operations(again_cmd).is_empty());
+    }
+
+    #[test]
+    fn save_only_the_refetched_feed() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let response = ResponseBuilder::ok()
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+
+        let cmd = app.update(
+            Event::SetFeed(account.clone(), rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+        let operations = storage_operations(cmd);
+
+        assert_eq!(operations.len(), 1);
+        assert_matches!(
+            &operations[0],
+            StorageOperation::SaveFeed(account_id, sub, feed, _)
+                if *account_id == account.id
+                    && sub.xml_url.as_deref() == Some(rss_link().as_ref())
+                    && feed.entries.len() == 2
+        );
+    }
+
+    #[test]
+    fn no_save_for_not_modified_feed() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let response = ResponseBuilder::with_status(StatusCode::NotModified)
+            .body(Vec::new())
+            .build();
+
+        let cmd = app.update(
+            Event::SetFeed(account, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert!(storage_operations(cmd).is_empty());
+    }
+
+    #[test]
+    fn save_subscriptions_of_their_account_only() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let _ = app.update(Event::CreateAccount(AccountType::Apple), &mut model, &());
+
+        let cmd = app.update(
+            Event::DeleteSubscription(account.clone(), SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+
+        assert_matches!(
+            storage_operations(cmd).as_slice(),
+            [StorageOperation::SaveSubscriptions(account_id, opml, _)]
+                if *account_id == account.id && opml.body.outlines.is_empty()
+        );
+    }
+
+    #[test]
+    fn fail_save_state() {
+        let app = App;
+        let mut model = Model::default();
+        let storage_error = StorageError::Io {
+            message: "database is locked".to_string(),
+        };
+
+        let _ = app.update(
+            Event::StateSaved(Err(storage_error.clone())),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.notification.message, storage_error.to_string());
+    }
+}
@@ -1,28 +1,115 @@
 use super::Error;
-use super::Feeds;
+use super::{EntryId, Feeds, HttpCache, NewEntriesCount, ReadStatus};
 use crate::define_newtype;
 
 use chrono::Local;
-use feed_rs::model::Feed;
-use opml::{Head, Outline, OPML};
+use crux_http::Response;
+use feed_rs::model::{Feed, FeedType};
+use opml::{Body, Head, Outline, OPML};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use url::Url;
+use uuid::Uuid;
 
 // ANCHOR: types
 // Generate new types using my custom macro
 define_newtype!(FolderName);
+define_newtype!(FolderId);
+define_newtype!(SubscriptionId);
 define_newtype!(OldFolderName);
 define_newtype!(NewFolderName);
 define_newtype!(SubscriptionTitle);
 define_newtype!(SubscriptionLink);
-define_newtype!(OldSubscriptionName);
 define_newtype!(NewSubscriptionName);
 
 // Optional types and type aliases remain the same
 pub type Subscription = Outline;
 pub type OpmlFileContent = String;
 pub type OpmlFileName = String;
-pub type OldFolder = Option<FolderName>;
-pub type NewFolder = Option<FolderName>;
+pub type NewFolder = Option<FolderId>;
+
+// NOTE a folder is addressed by its name and the names of its parents, from the root down
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct FolderPath(pub Vec<FolderName>);
+
+impl FolderPath {
+    pub fn name(&self) -> Option<&FolderName> {
+        self.0.last()
+    }
+
+    // NOTE None is the account root
+    pub fn parent(&self) -> Option<FolderPath> {
+        match self.0.split_last() {
+            Some((_, parents)) if !parents.is_empty() => Some(FolderPath(parents.to_vec())),
+            _ => None,
+        }
+    }
+
+    pub fn join(&self, folder_name: &FolderName) -> FolderPath {
+        let mut folder_path = self.clone();
+        folder_path.0.push(folder_name.clone());
+        folder_path
+    }
+
+    pub fn starts_with(&self, folder_path: &FolderPath) -> bool {
+        self.0.starts_with(&folder_path.0)
+    }
+}
+
+impl std::fmt::Display for FolderPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.0.iter().map(FolderName::as_ref).collect::<Vec<_>>();
+        write!(f, "{}", names.join(" / "))
+    }
+}
+
+impl From<FolderName> for FolderPath {
+    fn from(value: FolderName) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl From<Vec<FolderName>> for FolderPath {
+    fn from(value: Vec<FolderName>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<String> for FolderPath {
+    fn from(value: String) -> Self {
+        Self(vec![FolderName::from(value)])
+    }
+}
+
+impl From<&str> for FolderPath {
+    fn from(value: &str) -> Self {
+        Self(vec![FolderName::from(value)])
+    }
+}
+
+// NOTE generated once, so a folder keeps its id through renames and moves
+impl FolderId {
+    fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+}
+
+// NOTE an account can't subscribe twice to a link, so the normalised link is the id
+impl SubscriptionId {
+    pub fn from_link(sub_link: &str) -> Self {
+        Self(
+            Url::parse(sub_link)
+                .map(String::from)
+                .unwrap_or_else(|_| sub_link.trim().to_string()),
+        )
+    }
+}
+
+impl From<&SubscriptionLink> for SubscriptionId {
+    fn from(sub_link: &SubscriptionLink) -> Self {
+        Self::from_link(sub_link.as_ref())
+    }
+}
 // ANCHOR_END: types
 
 // NOTE - crate: https://crates.io/crates/opml to deal with subscriptions and outlines.
@@ -31,11 +118,33 @@ pub type NewFolder = Option<FolderName>;
 pub struct Subscriptions {
     pub feeds: Feeds,
     pub subs: OPML,
+    pub http_cache: HashMap<SubscriptionLink, HttpCache>,
+    // NOTE events address folders by id. display names are only data
+    pub folder_ids: HashMap<FolderId, FolderPath>,
 }
 
 trait SubscriptionHelpers {
     fn set_test_folder(title: &str) -> Outline;
     fn set_test_sub(title: &str, sub_link: &str) -> Outline;
+    fn is_folder(outline: &Outline, folder_name: &FolderName) -> bool;
+    fn find_folder<'a>(outlines: &'a [Outline], folder_names: &[FolderName])
+        -> Option<&'a Outline>;
+    fn find_folder_mut<'a>(
+        outlines: &'a mut [Outline],
+        folder_names: &[FolderName],
+    ) -> Option<&'a mut Outline>;
+    fn folder_outlines_mut(
+        &mut self,
+        folder_path: &Option<FolderPath>,
+    ) -> Option<&mut Vec<Outline>>;
+    fn list_folder_paths(
+        parent: &FolderPath,
+        outlines: &[Outline],
+        folder_paths: &mut Vec<FolderPath>,
+    );
+    fn sync_folder_ids(&mut self);
+    fn rehome_folder_ids(&mut self, old_folder_path: &FolderPath, new_folder_path: &FolderPath);
+    fn prune_feeds(&mut self);
 }
 
 impl SubscriptionHelpers for Subscriptions {
@@ -54,41 +163,677 @@ impl SubscriptionHelpers for Subscriptions {
             ..Outline::default()
         }
     }
+
+    // NOTE only folders are matched, never a subscription with the same name
+    fn is_folder(outline: &Outline, folder_name: &FolderName) -> bool {
+        outline.text == folder_name.as_ref() && outline.xml_url.is_none()
+    }
+
+    fn find_folder<'a>(
+        outlines: &'a [Outline],
+        folder_names: &[FolderName],
+    ) -> Option<&'a Outline> {
+        let (folder_name, children) = folder_names.split_first()?;
+        let folder = outlines
+            .iter()
+            .find(|outline| Self::is_folder(outline, folder_name))?;
+
+        match children {
+            [] => Some(folder),
+            _ => Self::find_folder(&folder.outlines, children),
+        }
+    }
+
+    fn find_folder_mut<'a>(
+        outlines: &'a mut [Outline],
+        folder_names: &[FolderName],
+    ) -> Option<&'a mut Outline> {
+        let (folder_name, children) = folder_names.split_first()?;
+        let folder = outlines
+            .iter_mut()
+            .find(|outline| Self::is_folder(outline, folder_name))?;
+
+        match children {
+            [] => Some(folder),
+            _ => Self::find_folder_mut(&mut folder.outlines, children),
+        }
+    }
+
+    // NOTE None is the account root. a folder that doesn't exist has no outlines
+    fn folder_outlines_mut(
+        &mut self,
+        folder_path: &Option<FolderPath>,
+    ) -> Option<&mut Vec<Outline>> {
+        match folder_path {
+            Some(folder_path) => {
+                Self::find_folder_mut(&mut self.subs.body.outlines, &folder_path.0)
+                    .map(|folder| &mut folder.outlines)
+            }
+            None => Some(&mut self.subs.body.outlines),
+        }
+    }
+
+    fn list_folder_paths(
+        parent: &FolderPath,
+        outlines: &[Outline],
+        folder_paths: &mut Vec<FolderPath>,
+    ) {
+        for folder in outlines.iter().filter(|outline| outline.xml_url.is_none()) {
+            let folder_path = parent.join(&FolderName::from(folder.text.as_str()));
+            Self::list_folder_paths(&folder_path, &folder.outlines, folder_paths);
+            folder_paths.push(folder_path);
+        }
+    }
+
+    // NOTE ids of folders that are gone are dropped. new folders get a new id
+    fn sync_folder_ids(&mut self) {
+        let mut folder_paths = Vec::new();
+        Self::list_folder_paths(
+            &FolderPath::default(),
+            &self.subs.body.outlines,
+            &mut folder_paths,
+        );
+
+        self.folder_ids
+            .retain(|_, folder_path| folder_paths.contains(folder_path));
+        for folder_path in folder_paths {
+            if !self.folder_ids.values().any(|path| *path == folder_path) {
+                self.folder_ids.insert(FolderId::generate(), folder_path);
+            }
+        }
+    }
+
+    // NOTE subfolders move with their parent, so their ids follow too
+    fn rehome_folder_ids(&mut self, old_folder_path: &FolderPath, new_folder_path: &FolderPath) {
+        for folder_path in self.folder_ids.values_mut() {
+            if folder_path.starts_with(old_folder_path) {
+                let children = folder_path.0[old_folder_path.0.len()..].to_vec();
+                *folder_path = FolderPath([new_folder_path.0.clone(), children].concat());
+            }
+        }
+    }
+
+    // NOTE feeds, validators and entry status of links no longer subscribed are dropped
+    fn prune_feeds(&mut self) {
+        let sub_links = self.list_subscription_links();
+
+        self.http_cache
+            .retain(|sub_link, _| sub_links.contains(sub_link));
+        self.feeds.retain_feeds(&sub_links);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ImportMode {
+    #[default]
+    Replace,
+    Merge,
+    // NOTE the folder has to exist. shells add it first with AddNewFolder
+    MergeIntoFolder(FolderId),
+}
+
+// NOTE how folders nested in the imported OPML are normalised
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NestedFolders {
+    #[default]
+    Flatten, // "Parent / Child" root folders
+    Reject,
+    Keep,
+}
+
+// NOTE only subscriptions are reported. folders are reused or created as needed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: Vec<Subscription>,
+    pub skipped: Vec<Subscription>, // already subscribed, same name in the same folder
+    pub conflicting: Vec<Subscription>, // already subscribed, another name or folder
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ImportStatus {
+    #[default]
+    New,
+    Duplicate,
+    Conflicting,
+    Invalid, // neither a folder nor a subscription with a valid link
+    Folder,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportPreviewNode {
+    pub text: String,
+    pub xml_url: Option<String>,
+    pub status: ImportStatus,
+    pub children: Vec<ImportPreviewNode>,
+}
+
+// NOTE what an import would do, without changing any subscriptions
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ImportPreview {
+    pub nodes: Vec<ImportPreviewNode>,
+    pub report: ImportReport,
+}
+
+trait ImportHelpers {
+    fn flatten_folder(
+        folder: &Outline,
+        folder_name: &str,
+        nested_folders: &NestedFolders,
+    ) -> Result<Vec<Outline>, Error>;
+    fn normalize_outlines(
+        outlines: &[Outline],
+        nested_folders: &NestedFolders,
+    ) -> Result<Vec<Outline>, Error>;
+    fn parse_opml(
+        opml_file_content: &OpmlFileContent,
+        nested_folders: &NestedFolders,
+    ) -> Result<OPML, Error>;
+    fn is_valid_outline(outline: &Outline) -> bool;
+    fn retain_outlines(outlines: &[Outline], keep: &dyn Fn(&Outline) -> bool) -> Vec<Outline>;
+    fn preview_nodes(
+        outlines: &[Outline],
+        statuses: &mut [(VecDeque<Subscription>, ImportStatus); 3],
+    ) -> Vec<ImportPreviewNode>;
+    fn import_opml(
+        &self,
+        opml: OPML,
+        import_mode: &ImportMode,
+    ) -> Result<(Self, ImportReport), Error>
+    where
+        Self: Sized;
+    fn list_subscriptions(outlines: &[Outline]) -> Vec<Subscription>;
+    fn find_or_add_folder(
+        &mut self,
+        parent: &Option<FolderPath>,
+        folder: &Outline,
+    ) -> Result<FolderPath, Error>;
+    fn merge_outlines(
+        &mut self,
+        folder_path: &Option<FolderPath>,
+        outlines: &[Outline],
+        report: &mut ImportReport,
+    ) -> Result<(), Error>;
+}
+
+impl ImportHelpers for Subscriptions {
+    // NOTE a folder holding only folders is dropped once they are flattened
+    // NOTE empty folders are kept, so validation reports and drops them like at the top level
+    fn flatten_folder(
+        folder: &Outline,
+        folder_name: &str,
+        nested_folders: &NestedFolders,
+    ) -> Result<Vec<Outline>, Error> {
+        let (nested, subs): (Vec<&Outline>, Vec<&Outline>) = folder
+            .outlines
+            .iter()
+            .partition(|outline| outline.xml_url.is_none());
+        let mut folders = vec![];
+
+        if !subs.is_empty() || folder.outlines.is_empty() {
+            let mut flat_folder = Outline {
+                outlines: subs.into_iter().cloned().collect(),
+                ..folder.clone()
+            };
+            if flat_folder.text != folder_name {
+                flat_folder.text = folder_name.to_string();
+                flat_folder.title = Some(folder_name.to_string());
+            }
+            folders.push(flat_folder);
+        }
+
+        for child in nested {
+            let child_name = format!("{folder_name} / {}", child.text);
+            if *nested_folders == NestedFolders::Reject && !child.outlines.is_empty() {
+                return Err(Error::set_error(
+                    "Cannot import folder",
+                    &child_name,
+                    "Folders can't be nested.",
+                ));
+            }
+            folders.extend(Self::flatten_folder(child, &child_name, nested_folders)?);
+        }
+
+        Ok(folders)
+    }
+
+    fn normalize_outlines(
+        outlines: &[Outline],
+        nested_folders: &NestedFolders,
+    ) -> Result<Vec<Outline>, Error> {
+        let mut normalized = vec![];
+
+        if *nested_folders == NestedFolders::Keep {
+            return Ok(outlines.to_vec());
+        }
+        for outline in outlines {
+            match outline.xml_url {
+                Some(_) => normalized.push(outline.clone()),
+                None => normalized.extend(Self::flatten_folder(
+                    outline,
+                    &outline.text,
+                    nested_folders,
+                )?),
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    fn parse_opml(
+        opml_file_content: &OpmlFileContent,
+        nested_folders: &NestedFolders,
+    ) -> Result<OPML, Error> {
+        let mut opml = OPML::from_str(opml_file_content)?;
+
+        opml.body.outlines = Self::normalize_outlines(&opml.body.outlines, nested_folders)?;
+        Ok(opml)
+    }
+
+    fn is_valid_outline(outline: &Outline) -> bool {
+        match outline.xml_url.as_deref() {
+            Some(sub_link) => Url::parse(sub_link).is_ok(),
+            None => !outline.outlines.is_empty(),
+        }
+    }
+
+    // NOTE folders left without subscriptions are dropped
+    fn retain_outlines(outlines: &[Outline], keep: &dyn Fn(&Outline) -> bool) -> Vec<Outline> {
+        outlines
+            .iter()
+            .filter(|outline| Self::is_valid_outline(outline))
+            .filter_map(|outline| match outline.xml_url {
+                Some(_) => keep(outline).then(|| outline.clone()),
+                None => {
+                    let children = Self::retain_outlines(&outline.outlines, keep);
+                    (!children.is_empty()).then(|| Outline {
+                        outlines: children,
+                        ..outline.clone()
+                    })
+                }
+            })
+            .collect()
+    }
+
+    // NOTE the report lists subscriptions in the same order the outlines are walked
+    fn preview_nodes(
+        outlines: &[Outline],
+        statuses: &mut [(VecDeque<Subscription>, ImportStatus); 3],
+    ) -> Vec<ImportPreviewNode> {
+        outlines
+            .iter()
+            .map(|outline| {
+                let (status, children) = if !Self::is_valid_outline(outline) {
+                    (ImportStatus::Invalid, vec![])
+                } else if outline.xml_url.is_none() {
+                    (
+                        ImportStatus::Folder,
+                        Self::preview_nodes(&outline.outlines, statuses),
+                    )
+                } else {
+                    let status = statuses
+                        .iter_mut()
+                        .find(|(subs, _)| subs.front() == Some(outline))
+                        .map(|(subs, status)| {
+                            subs.pop_front();
+                            status.clone()
+                        })
+                        .unwrap_or_default();
+                    (status, vec![])
+                };
+
+                ImportPreviewNode {
+                    text: outline.text.to_string(),
+                    xml_url: outline.xml_url.clone(),
+                    status,
+                    children,
+                }
+            })
+            .collect()
+    }
+
+    // NOTE every import goes through here, so invalid outlines are dropped the same way for all
+    fn import_opml(
+        &self,
+        opml: OPML,
+        import_mode: &ImportMode,
+    ) -> Result<(Self, ImportReport), Error> {
+        let mut subs = self.clone();
+        let mut report = ImportReport::default();
+        let opml = OPML {
+            body: Body {
+                outlines: Self::retain_outlines(&opml.body.outlines, &|_| true),
+            },
+            ..opml
+        };
+
+        match import_mode {
+            // NOTE built as a merge into an empty tree, so duplicates in the file are reported too
+            ImportMode::Replace => {
+                subs.subs = OPML {
+                    body: Body::default(),
+                    ..opml.clone()
+                };
+                subs.merge_outlines(&None, &opml.body.outlines, &mut report)?;
+                subs.prune_feeds();
+            }
+            ImportMode::Merge => {
+                subs.merge_outlines(&None, &opml.body.outlines, &mut report)?;
+            }
+            ImportMode::MergeIntoFolder(folder_id) => {
+                let Some(folder_path) = subs.find_folder_path(folder_id) else {
+                    return Err(Error::set_error(
+                        "Cannot import into folder",
+                        folder_id.as_ref(),
+                        "It doesn't exist.",
+                    ));
+                };
+                // NOTE imported folders are dropped. their subscriptions all go in this folder
+                let outlines = Self::list_subscriptions(&opml.body.outlines);
+                subs.merge_outlines(&Some(folder_path), &outlines, &mut report)?;
+            }
+        }
+        subs.sync_folder_ids();
+
+        Ok((subs, report))
+    }
+
+    fn list_subscriptions(outlines: &[Outline]) -> Vec<Subscription> {
+        outlines
+            .iter()
+            .flat_map(|outline| match outline.xml_url {
+                Some(_) => vec![outline.clone()],
+                None => Self::list_subscriptions(&outline.outlines),
+            })
+            .collect()
+    }
+
+    fn find_or_add_folder(
+        &mut self,
+        parent: &Option<FolderPath>,
+        folder: &Outline,
+    ) -> Result<FolderPath, Error> {
+        let folder_name = FolderName::from(folder.text.as_str());
+        let Some(outlines) = self.folder_outlines_mut(parent) else {
+            return Err(Error::set_error(
+                "Cannot import folder",
+                &folder.text,
+                "Its parent folder doesn't exist.",
+            ));
+        };
+
+        if !outlines
+            .iter()
+            .any(|outline| Self::is_folder(outline, &folder_name))
+        {
+            outlines.push(Outline {
+                outlines: vec![],
+                ..folder.clone()
+            });
+        }
+        match parent {
+            Some(parent) => Ok(parent.join(&folder_name)),
+            None => Ok(FolderPath::from(folder_name)),
+        }
+    }
+
+    // NOTE imported folders are merged into the folder with the same path, if any
+    fn merge_outlines(
+        &mut self,
+        folder_path: &Option<FolderPath>,
+        outlines: &[Outline],
+        report: &mut ImportReport,
+    ) -> Result<(), Error> {
+        for outline in outlines {
+            let Some(sub_link) = outline.xml_url.as_deref() else {
+                let child_path = self.find_or_add_folder(folder_path, outline)?;
+                self.merge_outlines(&Some(child_path), &outline.outlines, report)?;
+                continue;
+            };
+
+            // NOTE links that only differ in how they're written are the same subscription
+            match self.find_subscription_by_id(&SubscriptionId::from_link(sub_link)) {
+                None => {
+                    let Some(outlines) = self.folder_outlines_mut(folder_path) else {
+                        return Err(Error::set_error(
+                            "Cannot import subscription",
+                            &outline.text,
+                            "Its folder doesn't exist.",
+                        ));
+                    };
+                    outlines.push(outline.clone());
+                    report.added.push(outline.clone());
+                }
+                Some((existing_folder, existing))
+                    if existing_folder == *folder_path && existing.text == outline.text =>
+                {
+                    report.skipped.push(outline.clone())
+                }
+                Some(_) => report.conflicting.push(outline.clone()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+trait OutlineMetadata {
+    fn feed_outline(sub_link: &SubscriptionLink, feed: &Feed) -> Outline;
+    fn fill_outline_metadata(outlines: &mut [Outline], feed_outline: &Outline);
+    fn contains_sub(outlines: &[Outline], test_subscription: &Outline) -> bool;
+}
+
+impl OutlineMetadata for Subscriptions {
+    fn feed_outline(sub_link: &SubscriptionLink, feed: &Feed) -> Outline {
+        let title = feed
+            .title
+            .as_ref()
+            .map(|title| title.content.to_string())
+            .unwrap_or_else(|| sub_link.to_string());
+        let version = match feed.feed_type {
+            FeedType::Atom => "Atom",
+            FeedType::JSON => "JSON",
+            FeedType::RSS0 => "RSS",
+            FeedType::RSS1 => "RSS1",
+            FeedType::RSS2 => "RSS2",
+        };
+        // NOTE atom feeds list their own link too, the website is the alternate one
+        let html_url = feed
+            .links
+            .iter()
+            .find(|link| link.rel.as_deref() == Some("alternate"))
+            .or_else(|| {
+                feed.links
+                    .iter()
+                    .find(|link| link.href != sub_link.to_string())
+            })
+            .map(|link| link.href.to_string());
+        let categories = feed
+            .categories
+            .iter()
+            .map(|category| category.term.as_str())
+            .collect::<Vec<_>>();
+
+        Outline {
+            text: title.clone(),
+            title: Some(title),
+            r#type: Some("rss".to_string()),
+            version: Some(version.to_string()),
+            xml_url: Some(sub_link.to_string()),
+            html_url,
+            description: feed
+                .description
+                .as_ref()
+                .map(|description| description.content.to_string()),
+            language: feed.language.clone(),
+            category: (!categories.is_empty()).then(|| categories.join(",")),
+            ..Outline::default()
+        }
+    }
+
+    // NOTE only missing fields are filled, text is the user's name for the subscription
+    fn fill_outline_metadata(outlines: &mut [Outline], feed_outline: &Outline) {
+        for outline in outlines {
+            if outline.xml_url == feed_outline.xml_url {
+                outline.title = outline.title.take().or(feed_outline.title.clone());
+                outline.r#type = outline.r#type.take().or(feed_outline.r#type.clone());
+                outline.version = outline.version.take().or(feed_outline.version.clone());
+                outline.html_url = outline.html_url.take().or(feed_outline.html_url.clone());
+                outline.description = outline
+                    .description
+                    .take()
+                    .or(feed_outline.description.clone());
+                outline.language = outline.language.take().or(feed_outline.language.clone());
+                outline.category = outline.category.take().or(feed_outline.category.clone());
+            }
+            Self::fill_outline_metadata(&mut outline.outlines, feed_outline);
+        }
+    }
+
+    // NOTE stored subscriptions carry metadata, so only name and link are compared
+    fn contains_sub(outlines: &[Outline], test_subscription: &Outline) -> bool {
+        outlines.iter().any(|sub| {
+            sub.text == test_subscription.text && sub.xml_url == test_subscription.xml_url
+        })
+    }
 }
 
 trait ImportSubscriptions {
-    fn import_subscriptions(&self, opml_file_content: &OpmlFileContent) -> Result<Self, Error>
+    fn import_subscriptions(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error>
+    where
+        Self: Sized;
+    fn import_outlines(
+        &self,
+        outlines: &[Outline],
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error>
     where
         Self: Sized;
 }
 
-// TODO on duplicates, prompt user for merge or replace
+// NOTE merging skips duplicates by xml_url no matter where they exist
 // FIXME refactor this to drive the adapter to store data in the database
 impl ImportSubscriptions for Subscriptions {
-    fn import_subscriptions(&self, opml_file_content: &OpmlFileContent) -> Result<Self, Error> {
-        let subs = self.clone();
+    fn import_subscriptions(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error> {
+        let opml = Self::parse_opml(opml_file_content, nested_folders)?;
 
-        Ok(Self {
-            subs: OPML::from_str(opml_file_content)?,
-            feeds: subs.feeds,
-        })
+        self.import_opml(opml, import_mode)
+    }
+
+    // NOTE for outlines taken from a larger document, e.g. one account of an export
+    fn import_outlines(
+        &self,
+        outlines: &[Outline],
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error> {
+        let opml = OPML {
+            body: Body {
+                outlines: Self::normalize_outlines(outlines, nested_folders)?,
+            },
+            ..self.subs.clone()
+        };
+
+        self.import_opml(opml, import_mode)
     }
 }
 
-trait ExportSubscriptions {
-    fn export_subscriptions(&self, opml_file_name: &OpmlFileName) -> Result<Self, Error>
+trait PreviewImport {
+    fn preview_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<ImportPreview, Error>;
+    fn confirm_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+        selection: &[SubscriptionLink],
+    ) -> Result<(Self, ImportReport), Error>
     where
         Self: Sized;
 }
 
-// TODO once shell is implemented, check failures
-// TODO use XML tag in shells when exporting to opml
-// let xml_tag = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string();
-// FIXME refactor this to drive the adapter to read data and pass it on to the shell
-impl ExportSubscriptions for Subscriptions {
-    fn export_subscriptions(&self, opml_file_name: &OpmlFileName) -> Result<Self, Error> {
-        let subs = self.clone();
+// NOTE the preview is a dry run of the import, without the invalid outlines
+// NOTE confirming parses the file again, so the shell only keeps the selected links
+impl PreviewImport for Subscriptions {
+    fn preview_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<ImportPreview, Error> {
+        let opml = Self::parse_opml(opml_file_content, nested_folders)?;
+        let (_, report) = self.import_opml(opml.clone(), import_mode)?;
+        let mut statuses = [
+            (VecDeque::from(report.added.clone()), ImportStatus::New),
+            (
+                VecDeque::from(report.skipped.clone()),
+                ImportStatus::Duplicate,
+            ),
+            (
+                VecDeque::from(report.conflicting.clone()),
+                ImportStatus::Conflicting,
+            ),
+        ];
+
+        Ok(ImportPreview {
+            nodes: Self::preview_nodes(&opml.body.outlines, &mut statuses),
+            report,
+        })
+    }
+
+    fn confirm_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+        selection: &[SubscriptionLink],
+    ) -> Result<(Self, ImportReport), Error> {
+        let opml = Self::parse_opml(opml_file_content, nested_folders)?;
+        let selected_opml = OPML {
+            body: Body {
+                outlines: Self::retain_outlines(&opml.body.outlines, &|outline| {
+                    selection
+                        .iter()
+                        .any(|sub_link| outline.xml_url.as_deref() == Some(sub_link.as_ref()))
+                }),
+            },
+            ..opml
+        };
+
+        self.import_opml(selected_opml, import_mode)
+    }
+}
+
+const XML_PROLOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+trait ExportSubscriptions {
+    fn export_outlines(
+        opml_file_name: &OpmlFileName,
+        outlines: &[Outline],
+    ) -> Result<String, Error>;
+    fn export_subscriptions(&self, opml_file_name: &OpmlFileName) -> Result<String, Error>;
+}
 
+// NOTE the shell saves the file, see File
+impl ExportSubscriptions for Subscriptions {
+    fn export_outlines(
+        opml_file_name: &OpmlFileName,
+        outlines: &[Outline],
+    ) -> Result<String, Error> {
         let custom_head = Head {
             title: Some(opml_file_name.to_string()),
             date_created: Some(Local::now().format("%Y - %a %b %e %T").to_string()),
@@ -99,107 +844,325 @@ impl ExportSubscriptions for Subscriptions {
         let custom_opml = OPML {
             version: "2.0".to_string(),
             head: Some(custom_head),
-            body: subs.subs.body,
+            body: Body {
+                outlines: outlines.to_vec(),
+            },
         };
 
-        Ok(Self {
-            subs: custom_opml,
-            feeds: subs.feeds,
-        })
+        Ok(format!("{XML_PROLOG}{}", custom_opml.to_string()?))
+    }
+
+    fn export_subscriptions(&self, opml_file_name: &OpmlFileName) -> Result<String, Error> {
+        Self::export_outlines(opml_file_name, &self.subs.body.outlines)
+    }
+}
+
+trait FindNodes {
+    fn find_folder_id(&self, folder_path: &FolderPath) -> Option<FolderId>;
+    fn find_folder_path(&self, folder_id: &FolderId) -> Option<FolderPath>;
+    fn find_subscription_by_id(
+        &self,
+        sub_id: &SubscriptionId,
+    ) -> Option<(Option<FolderPath>, Subscription)>;
+    fn find_subscription_link(&self, sub_id: &SubscriptionId) -> Result<SubscriptionLink, Error>;
+}
+
+impl FindNodes for Subscriptions {
+    fn find_folder_id(&self, folder_path: &FolderPath) -> Option<FolderId> {
+        self.folder_ids
+            .iter()
+            .find(|(_, path)| *path == folder_path)
+            .map(|(folder_id, _)| folder_id.clone())
+    }
+
+    fn find_folder_path(&self, folder_id: &FolderId) -> Option<FolderPath> {
+        self.folder_ids.get(folder_id).cloned()
+    }
+
+    // NOTE returns the folder holding the subscription, if any, and the subscription
+    fn find_subscription_by_id(
+        &self,
+        sub_id: &SubscriptionId,
+    ) -> Option<(Option<FolderPath>, Subscription)> {
+        fn find(
+            parent: &FolderPath,
+            outlines: &[Outline],
+            sub_id: &SubscriptionId,
+        ) -> Option<(Option<FolderPath>, Subscription)> {
+            outlines
+                .iter()
+                .find_map(|outline| match outline.xml_url.as_deref() {
+                    Some(sub_link) => (SubscriptionId::from_link(sub_link) == *sub_id).then(|| {
+                        let folder_path = (!parent.0.is_empty()).then(|| parent.clone());
+                        (folder_path, outline.clone())
+                    }),
+                    None => find(
+                        &parent.join(&FolderName::from(outline.text.as_str())),
+                        &outline.outlines,
+                        sub_id,
+                    ),
+                })
+        }
+
+        find(&FolderPath::default(), &self.subs.body.outlines, sub_id)
+    }
+
+    fn find_subscription_link(&self, sub_id: &SubscriptionId) -> Result<SubscriptionLink, Error> {
+        self.find_subscription_by_id(sub_id)
+            .and_then(|(_, sub)| sub.xml_url)
+            .map(SubscriptionLink::from)
+            .ok_or_else(|| {
+                Error::set_error(
+                    "Cannot find subscription",
+                    sub_id.as_ref(),
+                    "It doesn't exist.",
+                )
+            })
     }
 }
 
 trait AddFolder {
-    fn add_folder(&self, folder_name: &FolderName) -> Result<Self, Error>
+    fn add_folder(
+        &self,
+        parent_id: &Option<FolderId>,
+        folder_name: &FolderName,
+    ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
-// NOTE folders are only allowed at root level. no nesting.
+// NOTE folder names are unique among their siblings only
 impl AddFolder for Subscriptions {
-    fn add_folder(&self, folder_name: &FolderName) -> Result<Self, Error> {
+    fn add_folder(
+        &self,
+        parent_id: &Option<FolderId>,
+        folder_name: &FolderName,
+    ) -> Result<Self, Error> {
         let mut subs = self.clone();
-        let test_folder = Self::set_test_folder(folder_name.as_ref());
-        let duplicate_err = Error::set_error(
-            "Cannot add new folder",
-            folder_name.0.as_str(),
-            "It already exists.",
-        );
+        let does_not_exist_err = |parent_id: &FolderId| {
+            Error::set_error(
+                "Cannot add new folder to",
+                parent_id.as_ref(),
+                "The folder doesn't exist.",
+            )
+        };
+        let parent = parent_id
+            .as_ref()
+            .map(|parent_id| {
+                self.find_folder_path(parent_id)
+                    .ok_or_else(|| does_not_exist_err(parent_id))
+            })
+            .transpose()?;
+        let folder_path = parent.clone().unwrap_or_default().join(folder_name);
+        let Some(outlines) = subs.folder_outlines_mut(&parent) else {
+            return Err(does_not_exist_err(
+                parent_id.as_ref().unwrap_or(&FolderId::default()),
+            ));
+        };
 
-        if subs.subs.body.outlines.contains(&test_folder) {
-            Err(duplicate_err)
+        if outlines
+            .iter()
+            .any(|outline| Self::is_folder(outline, folder_name))
+        {
+            Err(Error::set_error(
+                "Cannot add new folder",
+                &folder_path.to_string(),
+                "It already exists.",
+            ))
         } else {
-            subs.subs.body.outlines.push(test_folder);
+            outlines.push(Self::set_test_folder(folder_name.as_ref()));
+            subs.sync_folder_ids();
             Ok(subs)
         }
     }
 }
 
+// NOTE what happens to the subscriptions of a deleted folder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum FolderSubscriptions {
+    Delete,
+    MoveTo(NewFolder), // None is the account root
+}
+
 trait DeleteFolder {
-    fn delete_folder(&self, folder_name: &FolderName) -> Self
+    fn delete_folder(
+        &self,
+        folder_id: &FolderId,
+        folder_subs: &FolderSubscriptions,
+    ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
-// NOTE folders are only allowed at root level. no nesting.
+// NOTE deleting a folder deletes its subfolders too, unless they are moved with its subscriptions
 impl DeleteFolder for Subscriptions {
-    fn delete_folder(&self, folder_name: &FolderName) -> Self {
-        let mut subs = self.clone();
+    fn delete_folder(
+        &self,
+        folder_id: &FolderId,
+        folder_subs: &FolderSubscriptions,
+    ) -> Result<Self, Error> {
+        let does_not_exist_err = Error::set_error(
+            "Cannot delete folder",
+            folder_id.as_ref(),
+            "It doesn't exist.",
+        );
+        let Some(folder_path) = self.find_folder_path(folder_id) else {
+            return Err(does_not_exist_err);
+        };
+        let Some(folder) = Self::find_folder(&self.subs.body.outlines, &folder_path.0) else {
+            return Err(does_not_exist_err);
+        };
 
-        subs.subs
-            .body
-            .outlines
-            .retain(|name| name.text != folder_name.to_string());
-        subs
+        let mut subs = match folder_subs {
+            FolderSubscriptions::Delete => self.clone(),
+            FolderSubscriptions::MoveTo(new_folder_id) => {
+                let new_folder = new_folder_id
+                    .as_ref()
+                    .map(|new_folder_id| {
+                        self.find_folder_path(new_folder_id).ok_or_else(|| {
+                            Error::set_error(
+                                "Cannot move subscriptions to",
+                                new_folder_id.as_ref(),
+                                "The folder doesn't exist.",
+                            )
+                        })
+                    })
+                    .transpose()?;
+                if let Some(new_folder) = new_folder
+                    .as_ref()
+                    .filter(|new_folder| new_folder.starts_with(&folder_path))
+                {
+                    return Err(Error::set_error(
+                        "Cannot move subscriptions to",
+                        &new_folder.to_string(),
+                        "The folder is being deleted.",
+                    ));
+                }
+
+                let (child_folders, folder_subscriptions): (Vec<_>, Vec<_>) = folder
+                    .outlines
+                    .iter()
+                    .partition(|outline| outline.xml_url.is_none());
+                let mut subs = folder_subscriptions
+                    .into_iter()
+                    .try_fold(self.clone(), |subs, sub| {
+                        subs.move_outline(sub, &Some(folder_path.clone()), &new_folder)
+                    })?;
+                let Some(new_outlines) = subs.folder_outlines_mut(&new_folder) else {
+                    return Err(Error::set_error(
+                        "Cannot move folder to",
+                        &new_folder.unwrap_or_default().to_string(),
+                        "The folder doesn't exist.",
+                    ));
+                };
+
+                let mut moved_folders = vec![];
+                for child_folder in child_folders {
+                    let child_folder_name = FolderName::from(child_folder.text.as_str());
+                    if new_outlines
+                        .iter()
+                        .any(|outline| Self::is_folder(outline, &child_folder_name))
+                    {
+                        return Err(Error::set_error(
+                            "Cannot move folder to",
+                            &child_folder.text,
+                            "It already exists.",
+                        ));
+                    }
+                    new_outlines.push(child_folder.clone());
+                    moved_folders.push(child_folder_name);
+                }
+                for child_folder_name in moved_folders {
+                    subs.rehome_folder_ids(
+                        &folder_path.join(&child_folder_name),
+                        &new_folder
+                            .clone()
+                            .unwrap_or_default()
+                            .join(&child_folder_name),
+                    );
+                }
+                subs
+            }
+        };
+
+        let (folder_name, parent) = (folder_path.name(), folder_path.parent());
+        if let (Some(folder_name), Some(outlines)) =
+            (folder_name, subs.folder_outlines_mut(&parent))
+        {
+            outlines.retain(|outline| !Self::is_folder(outline, folder_name));
+        }
+        subs.sync_folder_ids();
+        subs.prune_feeds();
+        Ok(subs)
     }
 }
 
 trait RenameFolder {
     fn rename_folder(
         &self,
-        old_folder_name: &OldFolderName,
+        folder_id: &FolderId,
         new_folder_name: &NewFolderName,
     ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
-// NOTE folders are only allowed at root level. no nesting.
+// NOTE the folder keeps its place in the tree and its id. only its name changes
 impl RenameFolder for Subscriptions {
     fn rename_folder(
         &self,
-        old_folder_name: &OldFolderName,
+        folder_id: &FolderId,
         new_folder_name: &NewFolderName,
     ) -> Result<Self, Error> {
         let mut subs = self.clone();
-        let test_folder = Self::set_test_folder(new_folder_name.0.as_str());
         let duplicate_err = Error::set_error(
             "Cannot rename folder to",
             new_folder_name.0.as_str(),
             "It already exists.",
         );
+        let does_not_exist_err = Error::set_error(
+            "Cannot rename folder",
+            folder_id.as_ref(),
+            "It doesn't exist.",
+        );
+        let Some(folder_path) = self.find_folder_path(folder_id) else {
+            return Err(does_not_exist_err);
+        };
+        let new_folder_name = FolderName::from(new_folder_name.as_ref());
+        let parent = folder_path.parent();
+        let (Some(folder_name), Some(outlines)) =
+            (folder_path.name(), subs.folder_outlines_mut(&parent))
+        else {
+            return Err(does_not_exist_err);
+        };
 
-        if subs.subs.body.outlines.contains(&test_folder) {
-            Err(duplicate_err)
-        } else {
-            subs.subs
-                .body
-                .outlines
-                .iter_mut()
-                .filter(|folder| folder.text == old_folder_name.to_string())
-                .for_each(|folder| {
-                    folder.text = new_folder_name.to_string();
-                    folder.title = Some(new_folder_name.to_string());
-                });
-            Ok(subs)
+        if outlines
+            .iter()
+            .any(|outline| Self::is_folder(outline, &new_folder_name))
+        {
+            return Err(duplicate_err);
         }
+
+        let Some(folder) = outlines
+            .iter_mut()
+            .find(|outline| Self::is_folder(outline, folder_name))
+        else {
+            return Err(does_not_exist_err);
+        };
+        folder.text = new_folder_name.to_string();
+        folder.title = Some(new_folder_name.to_string());
+        subs.rehome_folder_ids(
+            &folder_path,
+            &parent.unwrap_or_default().join(&new_folder_name),
+        );
+        Ok(subs)
     }
 }
 
 trait AddSubscription {
     fn add_subscription(
         &self,
-        folder_name: &Option<FolderName>,
+        folder_id: &Option<FolderId>,
         sub_title: &SubscriptionTitle,
         sub_link: &SubscriptionLink,
     ) -> Result<Self, Error>
@@ -211,81 +1174,68 @@ trait AddSubscription {
 impl AddSubscription for Subscriptions {
     fn add_subscription(
         &self,
-        folder_name: &Option<FolderName>,
+        folder_id: &Option<FolderId>,
         sub_title: &SubscriptionTitle,
         sub_link: &SubscriptionLink,
     ) -> Result<Self, Error> {
         let mut subs = self.clone();
-        let test_subscription = Self::set_test_sub(sub_title.0.as_str(), sub_link.0.as_str());
         let duplicate_err = Error::set_error(
             "Cannot add new subscription",
             sub_title.0.as_str(),
             "You are already subscribed.",
         );
-
-        if let Some(folder_text) = &folder_name {
-            for folder in subs
-                .subs
-                .body
-                .outlines
-                .iter_mut()
-                .filter(|folder| folder.text == *folder_text.to_string())
-            {
-                if folder.outlines.contains(&test_subscription) {
-                    return Err(duplicate_err);
-                } else {
-                    folder.add_feed(sub_title.0.as_str(), sub_link.0.as_str());
-                }
-            }
-
-            return Ok(subs);
-        }
-
-        if subs.subs.body.outlines.contains(&test_subscription) {
-            Err(duplicate_err)
-        } else {
-            subs.subs
-                .add_feed(sub_title.0.as_str(), sub_link.0.as_str());
-            Ok(subs)
+        let does_not_exist_err = |folder_id: &FolderId| {
+            Error::set_error(
+                "Cannot add new subscription to",
+                folder_id.as_ref(),
+                "The folder doesn't exist.",
+            )
+        };
+        let folder_path = folder_id
+            .as_ref()
+            .map(|folder_id| {
+                self.find_folder_path(folder_id)
+                    .ok_or_else(|| does_not_exist_err(folder_id))
+            })
+            .transpose()?;
+
+        if self
+            .find_subscription_by_id(&SubscriptionId::from(sub_link))
+            .is_some()
+        {
+            return Err(duplicate_err);
         }
+        let Some(outlines) = subs.folder_outlines_mut(&folder_path) else {
+            return Err(does_not_exist_err(
+                folder_id.as_ref().unwrap_or(&FolderId::default()),
+            ));
+        };
+        outlines.push(Self::set_test_sub(
+            sub_title.0.as_str(),
+            sub_link.0.as_str(),
+        ));
+        Ok(subs)
     }
 }
 
 trait DeleteSubscription {
-    fn delete_subscription(
-        &self,
-        folder_name: &Option<FolderName>,
-        sub_title: &SubscriptionTitle,
-    ) -> Self
+    fn delete_subscription(&self, sub_id: &SubscriptionId) -> Self
     where
         Self: Sized;
 }
 
 impl DeleteSubscription for Subscriptions {
-    fn delete_subscription(
-        &self,
-        folder_name: &Option<FolderName>,
-        sub_title: &SubscriptionTitle,
-    ) -> Self {
+    fn delete_subscription(&self, sub_id: &SubscriptionId) -> Self {
         let mut subs = self.clone();
 
-        if let Some(folder_text) = folder_name {
-            subs.subs
-                .body
-                .outlines
-                .iter_mut()
-                .filter(|folder| folder.text == folder_text.to_string())
-                .for_each(|folder| {
-                    folder
-                        .outlines
-                        .retain(|name| name.text != sub_title.to_string())
+        if let Some((folder_path, _)) = self.find_subscription_by_id(sub_id) {
+            if let Some(outlines) = subs.folder_outlines_mut(&folder_path) {
+                outlines.retain(|sub| {
+                    sub.xml_url.as_deref().map(SubscriptionId::from_link) != Some(sub_id.clone())
                 });
-        } else {
-            subs.subs
-                .body
-                .outlines
-                .retain(|name| name.text != sub_title.to_string());
+            }
         }
+        subs.prune_feeds();
         subs
     }
 }
@@ -293,87 +1243,73 @@ impl DeleteSubscription for Subscriptions {
 trait RenameSubscription {
     fn rename_subscription(
         &self,
-        folder_name: &Option<FolderName>,
-        sub_link: &SubscriptionLink,
-        old_sub_name: &OldSubscriptionName,
+        sub_id: &SubscriptionId,
         new_sub_name: &NewSubscriptionName,
     ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
-// NOTE rename to an existing sub should always fail no matter where it exists
+// NOTE only the display name changes. the link, and so the id, stay the same
 impl RenameSubscription for Subscriptions {
     fn rename_subscription(
         &self,
-        folder_name: &Option<FolderName>,
-        sub_link: &SubscriptionLink,
-        old_sub_name: &OldSubscriptionName,
+        sub_id: &SubscriptionId,
         new_sub_name: &NewSubscriptionName,
     ) -> Result<Self, Error> {
         let mut subs = self.clone();
-        let test_subscription = Self::set_test_sub(new_sub_name.0.as_str(), sub_link.0.as_str());
         let duplicate_err = Error::set_error(
             "Cannot rename subscription to",
             new_sub_name.0.as_str(),
             "It already exists.",
         );
+        let does_not_exist_err = Error::set_error(
+            "Cannot rename subscription",
+            sub_id.as_ref(),
+            "It doesn't exist.",
+        );
+        let Some((folder_path, subscription)) = self.find_subscription_by_id(sub_id) else {
+            return Err(does_not_exist_err);
+        };
 
-        if let Some(folder_text) = &folder_name {
-            for folder in subs
-                .subs
-                .body
-                .outlines
-                .iter_mut()
-                .filter(|folder| folder.text == *folder_text.to_string())
-            {
-                if folder.outlines.contains(&test_subscription) {
-                    return Err(duplicate_err);
-                } else {
-                    folder
-                        .outlines
-                        .iter_mut()
-                        .filter(|sub| sub.text == old_sub_name.to_string())
-                        .for_each(|sub| {
-                            sub.text = new_sub_name.to_string();
-                        });
-                }
-            }
-
-            return Ok(subs);
-        }
-
-        if subs.subs.body.outlines.contains(&test_subscription) {
-            Err(duplicate_err)
-        } else {
-            subs.subs
-                .body
-                .outlines
-                .iter_mut()
-                .filter(|sub| sub.text == old_sub_name.to_string())
-                .for_each(|sub| sub.text = new_sub_name.to_string());
-            Ok(subs)
+        if subscription.text == new_sub_name.as_ref() {
+            return Err(duplicate_err);
         }
+        subs.folder_outlines_mut(&folder_path)
+            .into_iter()
+            .flatten()
+            .filter(|sub| **sub == subscription)
+            .for_each(|sub| sub.text = new_sub_name.to_string());
+        Ok(subs)
     }
 }
 
 trait MoveSubscription {
-    fn move_subscription(
+    fn move_outline(
         &self,
         subscription: &Subscription,
-        old_folder: &OldFolder,
+        old_folder: &Option<FolderPath>,
+        new_folder: &Option<FolderPath>,
+    ) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn move_subscription(
+        &self,
+        sub_id: &SubscriptionId,
         new_folder: &NewFolder,
     ) -> Result<Self, Error>
     where
         Self: Sized;
 }
 
+// NOTE the stored outline is moved, so its metadata goes with it
+// NOTE feeds are keyed by link, so fetched entries are untouched by a move
 impl MoveSubscription for Subscriptions {
-    fn move_subscription(
+    fn move_outline(
         &self,
         subscription: &Subscription,
-        old_folder: &OldFolder,
-        new_folder: &NewFolder,
+        old_folder: &Option<FolderPath>,
+        new_folder: &Option<FolderPath>,
     ) -> Result<Self, Error> {
         let mut subs = self.clone();
         let duplicate_err = Error::set_error(
@@ -381,353 +1317,1467 @@ impl MoveSubscription for Subscriptions {
             subscription.text.as_str(),
             "It already exists.",
         );
+        let does_not_exist_err = Error::set_error(
+            "Cannot move subscription",
+            subscription.text.as_str(),
+            "It doesn't exist.",
+        );
 
-        match (old_folder, new_folder) {
-            (None, Some(folder_new)) => {
-                subs = Self::delete_subscription(
-                    &subs,
-                    &None,
-                    &SubscriptionTitle(subscription.text.to_string()),
-                );
-                match Self::add_subscription(
-                    &subs,
-                    &Some(FolderName(folder_new.to_string())),
-                    &SubscriptionTitle(subscription.text.to_string()),
-                    &SubscriptionLink(subscription.xml_url.clone().unwrap()),
-                ) {
-                    Ok(s) => Ok(s),
-                    Err(_) => Err(duplicate_err),
-                }
-            }
-            (Some(folder_old), None) => {
-                subs = Self::delete_subscription(
-                    &subs,
-                    &Some(FolderName(folder_old.to_string())),
-                    &SubscriptionTitle(subscription.text.to_string()),
-                );
-                match Self::add_subscription(
-                    &subs,
-                    &None,
-                    &SubscriptionTitle(subscription.text.to_string()),
-                    &SubscriptionLink(subscription.xml_url.clone().unwrap()),
-                ) {
-                    Ok(s) => Ok(s),
-                    Err(_) => Err(duplicate_err),
-                }
-            }
-            (Some(folder_old), Some(folder_new)) => {
-                subs = Self::delete_subscription(
-                    &subs,
-                    &Some(FolderName(folder_old.to_string())),
-                    &SubscriptionTitle(subscription.text.to_string()),
-                );
-                match Self::add_subscription(
-                    &subs,
-                    &Some(FolderName(folder_new.to_string())),
-                    &SubscriptionTitle(subscription.text.to_string()),
-                    &SubscriptionLink(subscription.xml_url.clone().unwrap()),
-                ) {
-                    Ok(s) => Ok(s),
-                    Err(_) => Err(duplicate_err),
-                }
-            }
-            (None, None) => Err(duplicate_err),
+        if old_folder == new_folder {
+            return Err(duplicate_err);
+        }
+
+        let is_moved =
+            |sub: &Outline| sub.text == subscription.text && sub.xml_url == subscription.xml_url;
+        let Some(old_outlines) = subs.folder_outlines_mut(old_folder) else {
+            return Err(does_not_exist_err);
+        };
+        let Some(position) = old_outlines.iter().position(is_moved) else {
+            return Err(does_not_exist_err);
+        };
+        let moved_subscription = old_outlines.remove(position);
+
+        let Some(new_outlines) = subs.folder_outlines_mut(new_folder) else {
+            return Err(Error::set_error(
+                "Cannot move subscription to",
+                &new_folder.clone().unwrap_or_default().to_string(),
+                "The folder doesn't exist.",
+            ));
+        };
+        if Self::contains_sub(new_outlines, &moved_subscription) {
+            return Err(duplicate_err);
         }
+        new_outlines.push(moved_subscription);
+
+        Ok(subs)
+    }
+
+    fn move_subscription(
+        &self,
+        sub_id: &SubscriptionId,
+        new_folder: &NewFolder,
+    ) -> Result<Self, Error> {
+        let Some((old_folder, subscription)) = self.find_subscription_by_id(sub_id) else {
+            return Err(Error::set_error(
+                "Cannot move subscription",
+                sub_id.as_ref(),
+                "It doesn't exist.",
+            ));
+        };
+        let new_folder = new_folder
+            .as_ref()
+            .map(|new_folder_id| {
+                self.find_folder_path(new_folder_id).ok_or_else(|| {
+                    Error::set_error(
+                        "Cannot move subscription to",
+                        new_folder_id.as_ref(),
+                        "The folder doesn't exist.",
+                    )
+                })
+            })
+            .transpose()?;
+
+        self.move_outline(&subscription, &old_folder, &new_folder)
+    }
+}
+
+trait MoveSubscriptions {
+    fn move_subscriptions(
+        &self,
+        sub_ids: &[SubscriptionId],
+        new_folder: &NewFolder,
+    ) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+// NOTE all or nothing. the first failed move leaves the subscriptions untouched
+impl MoveSubscriptions for Subscriptions {
+    fn move_subscriptions(
+        &self,
+        sub_ids: &[SubscriptionId],
+        new_folder: &NewFolder,
+    ) -> Result<Self, Error> {
+        sub_ids.iter().try_fold(self.clone(), |subs, sub_id| {
+            subs.move_subscription(sub_id, new_folder)
+        })
     }
 }
 
 trait AddFeed {
-    fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error>
+    fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error>
     where
         Self: Sized;
 }
 
 impl AddFeed for Subscriptions {
-    fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error> {
+    fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
+        let mut subs = self.clone();
+        let (feeds, new_entries_count) = subs.feeds.add_feed(sub_link, body)?;
+
+        subs.feeds = feeds;
+        Ok((subs, new_entries_count))
+    }
+}
+
+trait FindFeed {
+    fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error>;
+}
+
+impl FindFeed for Subscriptions {
+    fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error> {
+        self.feeds.find_feed(sub_link)
+    }
+}
+
+trait UpdateFeed {
+    fn update_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        response: Response<Vec<u8>>,
+    ) -> Result<(Self, NewEntriesCount), Error>
+    where
+        Self: Sized;
+}
+
+impl UpdateFeed for Subscriptions {
+    // NOTE 304 Not Modified means no new entries, there's no body to parse
+    // NOTE any other status but a success is an error, its body and validators are not kept
+    fn update_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        mut response: Response<Vec<u8>>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
+        if HttpCache::is_not_modified(&response) {
+            return Ok((self.clone(), 0));
+        }
+        if !response.status().is_success() {
+            return Err(Error::set_error(
+                "Cannot update feed",
+                sub_link.as_ref(),
+                &format!("The server answered {}.", u16::from(response.status())),
+            ));
+        }
+
+        let http_cache = HttpCache::from_response(&response);
+        let (mut subs, new_entries_count) =
+            self.add_feed(sub_link, response.take_body().unwrap_or_default())?;
+
+        if http_cache.is_empty() {
+            subs.http_cache.remove(sub_link);
+        } else {
+            subs.http_cache.insert(sub_link.clone(), http_cache);
+        }
+        if let Ok(feed) = subs.feeds.find_feed(sub_link) {
+            let feed_outline = Self::feed_outline(sub_link, &feed);
+            Self::fill_outline_metadata(&mut subs.subs.body.outlines, &feed_outline);
+        }
+        Ok((subs, new_entries_count))
+    }
+}
+
+trait ListSubscriptionLinks {
+    fn list_subscription_links(&self) -> Vec<SubscriptionLink>;
+    fn list_folder_subscription_links(
+        &self,
+        folder_id: &FolderId,
+    ) -> Result<Vec<SubscriptionLink>, Error>;
+}
+
+impl ListSubscriptionLinks for Subscriptions {
+    fn list_subscription_links(&self) -> Vec<SubscriptionLink> {
+        fn collect(outlines: &[Outline], links: &mut Vec<SubscriptionLink>) {
+            for outline in outlines {
+                if let Some(xml_url) = &outline.xml_url {
+                    links.push(SubscriptionLink(xml_url.to_string()));
+                }
+                collect(&outline.outlines, links);
+            }
+        }
+
+        let mut links = Vec::new();
+        collect(&self.subs.body.outlines, &mut links);
+        links
+    }
+
+    // NOTE subfolders are listed too
+    fn list_folder_subscription_links(
+        &self,
+        folder_id: &FolderId,
+    ) -> Result<Vec<SubscriptionLink>, Error> {
+        self.find_folder_path(folder_id)
+            .and_then(|folder_path| Self::find_folder(&self.subs.body.outlines, &folder_path.0))
+            .map(|folder| {
+                Self::list_subscriptions(&folder.outlines)
+                    .iter()
+                    .filter_map(|sub| sub.xml_url.as_deref())
+                    .map(SubscriptionLink::from)
+                    .collect()
+            })
+            .ok_or_else(|| {
+                Error::set_error(
+                    "Cannot list subscriptions in folder",
+                    folder_id.as_ref(),
+                    "It doesn't exist.",
+                )
+            })
+    }
+}
+
+trait MarkEntries {
+    fn mark_entry(&self, entry_id: &EntryId, read_status: &ReadStatus) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error>
+    where
+        Self: Sized;
+    fn mark_all_read(&self) -> Self
+    where
+        Self: Sized;
+}
+
+impl MarkEntries for Subscriptions {
+    fn mark_entry(&self, entry_id: &EntryId, read_status: &ReadStatus) -> Result<Self, Error> {
+        let mut subs = self.clone();
+
+        subs.feeds = subs.feeds.mark_entry(entry_id, read_status)?;
+        Ok(subs)
+    }
+
+    fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error> {
+        let mut subs = self.clone();
+
+        subs.feeds = subs.feeds.toggle_star(entry_id)?;
+        Ok(subs)
+    }
+
+    fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error> {
+        let mut subs = self.clone();
+
+        subs.feeds = subs.feeds.mark_feed_read(sub_link)?;
+        Ok(subs)
+    }
+
+    fn mark_all_read(&self) -> Self {
         let mut subs = self.clone();
-        let feeds = subs.feeds.add_feed(body)?;
 
-        subs.feeds = feeds;
-        Ok(subs)
+        subs.feeds = subs.feeds.mark_all_read();
+        subs
+    }
+}
+
+trait MarkFolderRead {
+    fn mark_folder_read(&self, folder_id: &FolderId) -> Result<Self, Error>
+    where
+        Self: Sized;
+}
+
+// NOTE subscriptions in the folder which haven't been fetched yet have nothing to mark
+// NOTE subfolders are marked too
+impl MarkFolderRead for Subscriptions {
+    fn mark_folder_read(&self, folder_id: &FolderId) -> Result<Self, Error> {
+        let mut subs = self.clone();
+        let does_not_exist_err = Error::set_error(
+            "Cannot mark as read folder",
+            folder_id.as_ref(),
+            "It doesn't exist.",
+        );
+
+        let Some(folder) = self
+            .find_folder_path(folder_id)
+            .and_then(|folder_path| Self::find_folder(&self.subs.body.outlines, &folder_path.0))
+        else {
+            return Err(does_not_exist_err);
+        };
+
+        for sub_link in Self::list_subscriptions(&folder.outlines)
+            .iter()
+            .filter_map(|sub| sub.xml_url.as_deref())
+        {
+            if let Ok(feeds) = subs.feeds.mark_feed_read(&SubscriptionLink::from(sub_link)) {
+                subs.feeds = feeds;
+            }
+        }
+        Ok(subs)
+    }
+}
+
+trait CountFolderUnread {
+    fn count_outlines_unread(&self, outlines: &[Outline]) -> usize;
+    fn count_folder_unread(&self, folder_id: &FolderId) -> Result<usize, Error>;
+    fn folder_unread_counts(&self) -> Vec<(FolderId, usize)>;
+}
+
+// NOTE a folder counts the unread entries of its subfolders too
+impl CountFolderUnread for Subscriptions {
+    fn count_outlines_unread(&self, outlines: &[Outline]) -> usize {
+        Self::list_subscriptions(outlines)
+            .iter()
+            .filter_map(|sub| sub.xml_url.as_deref())
+            .map(|sub_link| {
+                self.feeds
+                    .count_feed_unread(&SubscriptionLink::from(sub_link))
+            })
+            .sum()
+    }
+
+    fn count_folder_unread(&self, folder_id: &FolderId) -> Result<usize, Error> {
+        self.find_folder_path(folder_id)
+            .and_then(|folder_path| Self::find_folder(&self.subs.body.outlines, &folder_path.0))
+            .map(|folder| self.count_outlines_unread(&folder.outlines))
+            .ok_or_else(|| {
+                Error::set_error(
+                    "Cannot count unread entries in folder",
+                    folder_id.as_ref(),
+                    "It doesn't exist.",
+                )
+            })
+    }
+
+    // NOTE parents come before their subfolders
+    fn folder_unread_counts(&self) -> Vec<(FolderId, usize)> {
+        fn collect(
+            subs: &Subscriptions,
+            parent: &FolderPath,
+            outlines: &[Outline],
+            counts: &mut Vec<(FolderId, usize)>,
+        ) {
+            for folder in outlines.iter().filter(|outline| outline.xml_url.is_none()) {
+                let folder_path = parent.join(&FolderName::from(folder.text.as_str()));
+                if let Some(folder_id) = subs.find_folder_id(&folder_path) {
+                    counts.push((folder_id, subs.count_outlines_unread(&folder.outlines)));
+                }
+                collect(subs, &folder_path, &folder.outlines, counts);
+            }
+        }
+
+        let mut counts = Vec::new();
+        collect(
+            self,
+            &FolderPath::default(),
+            &self.subs.body.outlines,
+            &mut counts,
+        );
+        counts
+    }
+}
+
+impl Subscriptions {
+    pub fn import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error> {
+        Self::import_subscriptions(self, opml_file_content, import_mode, nested_folders)
+    }
+
+    pub fn preview_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<ImportPreview, Error> {
+        <Self as PreviewImport>::preview_import(
+            self,
+            opml_file_content,
+            import_mode,
+            nested_folders,
+        )
+    }
+
+    pub fn confirm_import(
+        &self,
+        opml_file_content: &OpmlFileContent,
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+        selection: &[SubscriptionLink],
+    ) -> Result<(Self, ImportReport), Error> {
+        <Self as PreviewImport>::confirm_import(
+            self,
+            opml_file_content,
+            import_mode,
+            nested_folders,
+            selection,
+        )
+    }
+
+    pub fn import_outlines(
+        &self,
+        outlines: &[Outline],
+        import_mode: &ImportMode,
+        nested_folders: &NestedFolders,
+    ) -> Result<(Self, ImportReport), Error> {
+        <Self as ImportSubscriptions>::import_outlines(self, outlines, import_mode, nested_folders)
+    }
+
+    pub fn export(&self, opml_file_name: &OpmlFileName) -> Result<String, Error> {
+        Self::export_subscriptions(self, opml_file_name)
+    }
+
+    pub fn export_outlines(
+        opml_file_name: &OpmlFileName,
+        outlines: &[Outline],
+    ) -> Result<String, Error> {
+        <Self as ExportSubscriptions>::export_outlines(opml_file_name, outlines)
+    }
+
+    pub(super) fn folder_outlines_mut(
+        &mut self,
+        folder_path: &Option<FolderPath>,
+    ) -> Option<&mut Vec<Outline>> {
+        <Self as SubscriptionHelpers>::folder_outlines_mut(self, folder_path)
+    }
+
+    pub fn find_folder_id(&self, folder_path: &FolderPath) -> Option<FolderId> {
+        <Self as FindNodes>::find_folder_id(self, folder_path)
+    }
+
+    pub fn find_folder_path(&self, folder_id: &FolderId) -> Option<FolderPath> {
+        <Self as FindNodes>::find_folder_path(self, folder_id)
+    }
+
+    pub fn find_subscription_by_id(
+        &self,
+        sub_id: &SubscriptionId,
+    ) -> Option<(Option<FolderPath>, Subscription)> {
+        <Self as FindNodes>::find_subscription_by_id(self, sub_id)
+    }
+
+    pub fn find_subscription_link(
+        &self,
+        sub_id: &SubscriptionId,
+    ) -> Result<SubscriptionLink, Error> {
+        <Self as FindNodes>::find_subscription_link(self, sub_id)
+    }
+
+    pub fn add_folder(
+        &self,
+        parent_id: &Option<FolderId>,
+        folder_name: &FolderName,
+    ) -> Result<Self, Error> {
+        <Self as AddFolder>::add_folder(self, parent_id, folder_name)
+    }
+
+    pub fn delete_folder(
+        &self,
+        folder_id: &FolderId,
+        folder_subs: &FolderSubscriptions,
+    ) -> Result<Self, Error> {
+        <Self as DeleteFolder>::delete_folder(self, folder_id, folder_subs)
+    }
+
+    pub fn rename_folder(
+        &self,
+        folder_id: &FolderId,
+        new_folder_name: &NewFolderName,
+    ) -> Result<Self, Error> {
+        <Self as RenameFolder>::rename_folder(self, folder_id, new_folder_name)
+    }
+
+    pub fn add_subscription(
+        &self,
+        folder_id: &Option<FolderId>,
+        sub_title: &SubscriptionTitle,
+        sub_link: &SubscriptionLink,
+    ) -> Result<Self, Error> {
+        <Self as AddSubscription>::add_subscription(self, folder_id, sub_title, sub_link)
+    }
+
+    pub fn delete_subscription(&self, sub_id: &SubscriptionId) -> Self {
+        <Self as DeleteSubscription>::delete_subscription(self, sub_id)
+    }
+
+    pub fn rename_subscription(
+        &self,
+        sub_id: &SubscriptionId,
+        new_sub_name: &NewSubscriptionName,
+    ) -> Result<Self, Error> {
+        <Self as RenameSubscription>::rename_subscription(self, sub_id, new_sub_name)
+    }
+
+    pub fn move_subscription(
+        &self,
+        sub_id: &SubscriptionId,
+        new_folder: &NewFolder,
+    ) -> Result<Self, Error> {
+        <Self as MoveSubscription>::move_subscription(self, sub_id, new_folder)
+    }
+
+    pub fn move_subscriptions(
+        &self,
+        sub_ids: &[SubscriptionId],
+        new_folder: &NewFolder,
+    ) -> Result<Self, Error> {
+        <Self as MoveSubscriptions>::move_subscriptions(self, sub_ids, new_folder)
+    }
+
+    pub fn add_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        body: Vec<u8>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
+        <Self as AddFeed>::add_feed(self, sub_link, body)
+    }
+
+    pub fn feed_outline(sub_link: &SubscriptionLink, feed: &Feed) -> Outline {
+        <Self as OutlineMetadata>::feed_outline(sub_link, feed)
+    }
+
+    pub fn find_feed(&self, sub_link: &SubscriptionLink) -> Result<Feed, Error> {
+        <Self as FindFeed>::find_feed(self, sub_link)
+    }
+
+    pub fn update_feed(
+        &self,
+        sub_link: &SubscriptionLink,
+        response: Response<Vec<u8>>,
+    ) -> Result<(Self, NewEntriesCount), Error> {
+        <Self as UpdateFeed>::update_feed(self, sub_link, response)
+    }
+
+    pub fn list_subscription_links(&self) -> Vec<SubscriptionLink> {
+        <Self as ListSubscriptionLinks>::list_subscription_links(self)
+    }
+
+    pub fn list_folder_subscription_links(
+        &self,
+        folder_id: &FolderId,
+    ) -> Result<Vec<SubscriptionLink>, Error> {
+        <Self as ListSubscriptionLinks>::list_folder_subscription_links(self, folder_id)
+    }
+
+    pub fn mark_entry(&self, entry_id: &EntryId, read_status: &ReadStatus) -> Result<Self, Error> {
+        <Self as MarkEntries>::mark_entry(self, entry_id, read_status)
+    }
+
+    pub fn toggle_star(&self, entry_id: &EntryId) -> Result<Self, Error> {
+        <Self as MarkEntries>::toggle_star(self, entry_id)
+    }
+
+    pub fn mark_feed_read(&self, sub_link: &SubscriptionLink) -> Result<Self, Error> {
+        <Self as MarkEntries>::mark_feed_read(self, sub_link)
+    }
+
+    pub fn mark_folder_read(&self, folder_id: &FolderId) -> Result<Self, Error> {
+        <Self as MarkFolderRead>::mark_folder_read(self, folder_id)
+    }
+
+    pub fn count_folder_unread(&self, folder_id: &FolderId) -> Result<usize, Error> {
+        <Self as CountFolderUnread>::count_folder_unread(self, folder_id)
+    }
+
+    pub fn folder_unread_counts(&self) -> Vec<(FolderId, usize)> {
+        <Self as CountFolderUnread>::folder_unread_counts(self)
+    }
+
+    pub fn mark_all_read(&self) -> Self {
+        <Self as MarkEntries>::mark_all_read(self)
+    }
+}
+
+#[cfg(test)]
+mod import_export {
+    use super::*;
+    use crate::fixtures::{add_folder, atom_link, model_with_account, model_with_feed, rss_link};
+    use crate::{Account, AccountType, Accounts};
+    use crate::{App, Effect, Event, FileError, FileOperation, FileResponse, Model};
+    use assert_matches::assert_matches;
+    use crux_core::App as _;
+    use opml::OPML;
+
+    #[test]
+    fn import_subscriptions() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+
+        let example_import_opml = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
+        let example_subs = r#"<opml version="2.0"><head><title>Subscriptions.opml</title><dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated><ownerName>Crab News</ownerName></head><body><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/><outline text="Group Name" title="Group Name"><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/></outline></body></opml>"#;
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account.clone(),
+                example_import_opml,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        let added_subs = model.accounts.acct[account_index].subs.clone();
+        let subs_feeds = added_subs.feeds.clone();
+
+        let expected_subs = Subscriptions {
+            subs: OPML::from_str(example_subs).unwrap(),
+            feeds: subs_feeds,
+            http_cache: HashMap::new(),
+            folder_ids: added_subs.folder_ids.clone(),
+        };
+
+        assert_eq!(added_subs, expected_subs);
+        assert!(added_subs
+            .find_folder_id(&FolderPath::from("Group Name"))
+            .is_some());
+    }
+
+    #[test]
+    fn fail_import_for_invalid_xml() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let invalid_xml_opml = r#"<?xml version="2.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                invalid_xml_opml,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error = "Failed to process XML file";
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn fail_import_for_invalid_opml_version() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let invalid_version_opml =r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="0.1"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                invalid_version_opml,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error = "Unsupported OPML version: \"0.1\"";
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn fail_import_for_body_has_no_outlines() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let invalid_body_opml = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> </body> </opml>"#.to_string();
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                invalid_body_opml,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error = "OPML body has no <outline> elements";
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    const MERGE_IMPORT_OPML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> </head> <body> <outline text="Feed Name" title="Feed Name" type="rss" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" type="rss" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#;
+
+    fn import_outline(sub_link: &str) -> Outline {
+        Outline {
+            text: "Feed Name".to_string(),
+            title: Some("Feed Name".to_string()),
+            r#type: Some("rss".to_string()),
+            xml_url: Some(sub_link.to_string()),
+            ..Outline::default()
+        }
+    }
+
+    fn merge_import(
+        app: &App,
+        model: &mut Model,
+        account: &Account,
+        import_mode: ImportMode,
+    ) -> Vec<Outline> {
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account.clone(),
+                MERGE_IMPORT_OPML.to_string(),
+                import_mode,
+                NestedFolders::Flatten,
+            ),
+            model,
+            &(),
+        );
+        model.accounts.acct[0].subs.subs.body.outlines.clone()
+    }
+
+    #[test]
+    fn replace_reports_all_added() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let _ = app.update(
+            Event::AddNewFolder(account.clone(), None, FolderName::from("Tech")),
+            &mut model,
+            &(),
+        );
+        let outlines = merge_import(&app, &mut model, &account, ImportMode::Replace);
+
+        assert_eq!(outlines.len(), 2);
+        assert!(!outlines.iter().any(|outline| outline.text == "Tech"));
+        assert_eq!(
+            model.import_report.added,
+            vec![
+                import_outline("https://example.com/atom.xml"),
+                import_outline("https://example.com/rss.xml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn replace_reports_duplicates_in_the_file() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let duplicated_opml = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> </head> <body> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" type="rss" xmlUrl="https://example.com/rss.xml"/> </outline> <outline text="Other Group" title="Other Group"> <outline text="Feed Name" title="Feed Name" type="rss" xmlUrl="https://example.com/rss.xml"/> </outline> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" type="rss" xmlUrl="https://example.com/atom.xml"/> </outline> </body> </opml>"#;
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                duplicated_opml.to_string(),
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+        let subs = &model.accounts.acct[0].subs;
+        let outlines = &subs.subs.body.outlines;
+
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(outlines[0].text, "Group Name");
+        assert_eq!(
+            outlines[0].outlines,
+            vec![
+                import_outline("https://example.com/rss.xml"),
+                import_outline("https://example.com/atom.xml"),
+            ]
+        );
+        assert!(outlines[1].outlines.is_empty());
+        assert_eq!(subs.folder_ids.len(), 2);
+        assert_eq!(
+            model.import_report,
+            ImportReport {
+                added: vec![
+                    import_outline("https://example.com/rss.xml"),
+                    import_outline("https://example.com/atom.xml"),
+                ],
+                skipped: vec![],
+                conflicting: vec![import_outline("https://example.com/rss.xml")],
+            }
+        );
+    }
+
+    #[test]
+    fn merge_skips_duplicates() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let _ = app.update(
+            Event::AddSubscription(
+                account.clone(),
+                None,
+                SubscriptionTitle::from("Feed Name"),
+                SubscriptionLink::from("https://example.com/atom.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+        let outlines = merge_import(&app, &mut model, &account, ImportMode::Merge);
+
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(outlines[1].text, "Group Name");
+        assert_eq!(
+            outlines[1].outlines,
+            vec![import_outline("https://example.com/rss.xml")]
+        );
+        assert_eq!(
+            model.import_report,
+            ImportReport {
+                added: vec![import_outline("https://example.com/rss.xml")],
+                skipped: vec![import_outline("https://example.com/atom.xml")],
+                conflicting: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn merge_reports_conflicts() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let _ = app.update(
+            Event::AddNewFolder(account.clone(), None, FolderName::from("Tech")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::AddSubscription(
+                account.clone(),
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Tech")),
+                SubscriptionTitle::from("Feed Name"),
+                SubscriptionLink::from("https://example.com/atom.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+        let outlines = merge_import(&app, &mut model, &account, ImportMode::Merge);
+
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(outlines[0].outlines.len(), 1);
+        assert_eq!(
+            model.import_report.conflicting,
+            vec![import_outline("https://example.com/atom.xml")]
+        );
+    }
+
+    #[test]
+    fn merge_into_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let folder_id = add_folder(&app, &mut model, &account, None, "Imported");
+
+        let outlines = merge_import(
+            &app,
+            &mut model,
+            &account,
+            ImportMode::MergeIntoFolder(folder_id),
+        );
+
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].text, "Imported");
+        assert_eq!(
+            outlines[0].outlines,
+            vec![
+                import_outline("https://example.com/atom.xml"),
+                import_outline("https://example.com/rss.xml"),
+            ]
+        );
+        assert_eq!(model.import_report.added.len(), 2);
+    }
+
+    #[test]
+    fn merge_into_nested_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let parent_id = add_folder(&app, &mut model, &account, None, "Tech");
+        let folder_id = add_folder(&app, &mut model, &account, Some(parent_id), "Imported");
+
+        let outlines = merge_import(
+            &app,
+            &mut model,
+            &account,
+            ImportMode::MergeIntoFolder(folder_id),
+        );
+
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].outlines[0].text, "Imported");
+        assert_eq!(
+            outlines[0].outlines[0].outlines,
+            vec![
+                import_outline("https://example.com/atom.xml"),
+                import_outline("https://example.com/rss.xml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_merge_into_missing_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let folder_id = FolderId::from("missing-folder");
+
+        let outlines = merge_import(
+            &app,
+            &mut model,
+            &account,
+            ImportMode::MergeIntoFolder(folder_id.clone()),
+        );
+
+        assert!(outlines.is_empty());
+        assert_eq!(
+            model.notification.message,
+            format!("Cannot import into folder \"{folder_id}\". It doesn't exist.")
+        );
+    }
+
+    #[test]
+    fn export_subscriptions() {
+        let app = App;
+        let mut model = Model::default();
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+
+        let opml_file_name = "Subscriptions.opml".to_string();
+        let example_subs = r#"<opml version="2.0"><head><title>Subscriptions.opml</title></head><body><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/><outline text="Group Name" title="Group Name"><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/></outline></body></opml>"#;
+        model.accounts.acct[account_index].subs.subs = OPML::from_str(example_subs).unwrap();
+
+        let mut cmd = app.update(
+            Event::ExportSubscriptions(account, opml_file_name.clone()),
+            &mut model,
+            &(),
+        );
+        let request = assert_matches!(
+            cmd.effects().next(),
+            Some(Effect::File(request)) => request
+        );
+        let FileOperation::Save { file_name, bytes } = request.operation;
+        let exported_content = String::from_utf8(bytes).unwrap();
+        let exported_opml = OPML::from_str(&exported_content).unwrap();
+        let exported_head = exported_opml.head.unwrap();
+
+        assert_eq!(file_name, opml_file_name);
+        assert!(exported_content.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><opml"#));
+        assert_eq!(exported_head.title, Some(opml_file_name));
+        assert_eq!(exported_head.owner_name, Some("Crab News".to_string()));
+        assert_eq!(
+            exported_opml.body,
+            model.accounts.acct[account_index].subs.subs.body
+        );
+    }
+
+    #[test]
+    fn export_subscriptions_notification() {
+        let app = App;
+        let mut model = Model::default();
+        let opml_file_name = "Subscriptions.opml".to_string();
+
+        let _ = app.update(
+            Event::SubscriptionsExported(Ok(FileResponse::Saved {
+                file_name: opml_file_name,
+            })),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.notification.title, "Subscriptions Exported");
+        assert_eq!(
+            model.notification.message,
+            "Subscriptions successfully exported to \"Subscriptions.opml\"."
+        );
+    }
+
+    #[test]
+    fn fail_export_subscriptions() {
+        let app = App;
+        let mut model = Model::default();
+
+        let _ = app.update(
+            Event::SubscriptionsExported(Err(FileError::Io {
+                message: "Permission denied".to_string(),
+            })),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.notification.title, "Export Error");
+        assert_eq!(model.notification.message, "Permission denied");
+    }
+
+    #[test]
+    fn replace_drops_feed_data_of_links_no_longer_subscribed() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let atom_only = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0"><head><title>Subscriptions.opml</title></head><body><outline text="Atom Name" xmlUrl="{}"/></body></opml>"#,
+            atom_link()
+        );
+        let both = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0"><head><title>Subscriptions.opml</title></head><body><outline text="Feed Name" xmlUrl="{}"/><outline text="Atom Name" xmlUrl="{}"/></body></opml>"#,
+            rss_link(),
+            atom_link()
+        );
+
+        let _ = app.update(
+            Event::MarkEntryRead(account.clone(), EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account.clone(),
+                both,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.accounts.acct[0].subs.feeds.feeds.len(), 1);
+        assert_eq!(model.accounts.acct[0].subs.feeds.status.len(), 1);
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                atom_only,
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        let subs = &model.accounts.acct[0].subs;
+
+        assert!(subs.feeds.feeds.is_empty());
+        assert!(subs.feeds.status.is_empty());
+        assert_eq!(app.view(&model).smart_feeds.all_unread_count, 0);
+    }
+
+    #[test]
+    fn merge_skips_links_written_differently() {
+        let app = App;
+        let (mut model, account) = model_with_feed(&app);
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0"><head><title>Subscriptions.opml</title></head><body><outline text="Feed Name" xmlUrl=" https://EXAMPLE.com/rss.xml"/></body></opml>"#;
+
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                opml.to_string(),
+                ImportMode::Merge,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            model.accounts.acct[0].subs.list_subscription_links(),
+            vec![rss_link()]
+        );
+        assert!(model.import_report.added.is_empty());
+        assert_eq!(model.import_report.skipped.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod import_preview {
+    use super::*;
+    use crate::fixtures::{atom_link, model_with_subscription};
+    use crate::{App, Event};
+    use crux_core::App as _;
+
+    const PREVIEW_IMPORT_OPML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> </head> <body> <outline text="Feed Name" type="rss" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name"> <outline text="Feed Name" type="rss" xmlUrl="https://example.com/rss.xml"/> <outline text="Broken Feed" type="rss" xmlUrl="not a link"/> </outline> <outline text="Bookmark" type="link" url="https://example.com/"/> </body> </opml>"#;
+
+    fn preview_node(text: &str, xml_url: Option<&str>, status: ImportStatus) -> ImportPreviewNode {
+        ImportPreviewNode {
+            text: text.to_string(),
+            xml_url: xml_url.map(str::to_string),
+            status,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn preview_flags_duplicates_and_invalid_outlines() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &atom_link());
+        let subs = model.accounts.acct[0].subs.clone();
+
+        let _ = app.update(
+            Event::PreviewImport(
+                account,
+                PREVIEW_IMPORT_OPML.to_string(),
+                ImportMode::Merge,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        let expected_nodes = vec![
+            preview_node(
+                "Feed Name",
+                Some("https://example.com/atom.xml"),
+                ImportStatus::Duplicate,
+            ),
+            ImportPreviewNode {
+                children: vec![
+                    preview_node(
+                        "Feed Name",
+                        Some("https://example.com/rss.xml"),
+                        ImportStatus::New,
+                    ),
+                    preview_node("Broken Feed", Some("not a link"), ImportStatus::Invalid),
+                ],
+                ..preview_node("Group Name", None, ImportStatus::Folder)
+            },
+            preview_node("Bookmark", None, ImportStatus::Invalid),
+        ];
+        let preview = model.import_preview.clone().unwrap();
+
+        assert_eq!(preview.nodes, expected_nodes);
+        assert_eq!(preview.report.added.len(), 1);
+        assert_eq!(preview.report.skipped.len(), 1);
+        assert_eq!(model.accounts.acct[0].subs, subs);
+    }
+
+    #[test]
+    fn fail_preview_for_invalid_opml() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &atom_link());
+
+        let _ = app.update(
+            Event::PreviewImport(
+                account,
+                "not opml".to_string(),
+                ImportMode::Merge,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(model.import_preview, None);
+        assert_eq!(model.notification.title, "Import Error");
     }
-}
 
-trait FindFeed {
-    fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error>;
-}
+    #[test]
+    fn confirm_imports_selected_subscriptions() {
+        let app = App;
+        let (mut model, account) = model_with_subscription(&app, &atom_link());
 
-impl FindFeed for Subscriptions {
-    fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error> {
-        self.feeds.find_feed(feed_title)
+        let _ = app.update(
+            Event::PreviewImport(
+                account.clone(),
+                PREVIEW_IMPORT_OPML.to_string(),
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+            ),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::ConfirmImport(
+                account,
+                PREVIEW_IMPORT_OPML.to_string(),
+                ImportMode::Replace,
+                NestedFolders::Flatten,
+                vec![
+                    SubscriptionLink::from("https://example.com/rss.xml"),
+                    SubscriptionLink::from("not a link"),
+                ],
+            ),
+            &mut model,
+            &(),
+        );
+
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].text, "Group Name");
+        assert_eq!(
+            outlines[0].outlines[0].xml_url.as_deref(),
+            Some("https://example.com/rss.xml")
+        );
+        assert_eq!(outlines[0].outlines.len(), 1);
+        assert_eq!(model.import_report.added.len(), 1);
+        assert_eq!(model.import_preview, None);
     }
 }
 
-impl Subscriptions {
-    pub fn import(&self, opml_file_content: &OpmlFileContent) -> Result<Self, Error> {
-        Self::import_subscriptions(self, opml_file_content)
-    }
+#[cfg(test)]
+mod nested_import {
+    use super::*;
+    use crate::AccountType;
+    use crate::{App, Event, Model};
+    use crux_core::App as _;
 
-    pub fn export(&self, opml_file_name: &OpmlFileName) -> Result<Self, Error> {
-        Self::export_subscriptions(self, opml_file_name)
-    }
+    const NESTED_IMPORT_OPML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> </head> <body> <outline text="News"> <outline text="World" xmlUrl="https://example.com/world.xml"/> <outline text="Tech"> <outline text="Gadgets" xmlUrl="https://example.com/gadgets.xml"/> <outline text="Rust"> <outline text="Crates" xmlUrl="https://example.com/crates.xml"/> </outline> </outline> </outline> <outline text="Blogs"> <outline text="Personal"> <outline text="Diary" xmlUrl="https://example.com/diary.xml"/> </outline> </outline> </body> </opml>"#;
 
-    pub fn add_folder(&self, folder_name: &FolderName) -> Result<Self, Error> {
-        <Self as AddFolder>::add_folder(self, folder_name)
-    }
+    // NOTE empty folders at every level, and a link that isn't one
+    const EMPTY_FOLDERS_OPML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> </head> <body> <outline text="News"> <outline text="World" xmlUrl="https://example.com/world.xml"/> <outline text="Empty"/> </outline> <outline text="Blank"/> <outline text="Broken" xmlUrl="not a link"/> </body> </opml>"#;
 
-    pub fn delete_folder(&self, folder_name: &FolderName) -> Self {
-        <Self as DeleteFolder>::delete_folder(self, folder_name)
-    }
+    fn import_opml(opml: &str, nested_folders: NestedFolders) -> Model {
+        let app = App;
+        let mut model = Model::default();
 
-    pub fn rename_folder(
-        &self,
-        old_folder_name: &OldFolderName,
-        new_folder_name: &NewFolderName,
-    ) -> Result<Self, Error> {
-        <Self as RenameFolder>::rename_folder(self, old_folder_name, new_folder_name)
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let _ = app.update(
+            Event::ImportSubscriptions(
+                account,
+                opml.to_string(),
+                ImportMode::Merge,
+                nested_folders,
+            ),
+            &mut model,
+            &(),
+        );
+
+        model
     }
 
-    pub fn add_subscription(
-        &self,
-        folder_name: &Option<FolderName>,
-        sub_title: &SubscriptionTitle,
-        sub_link: &SubscriptionLink,
-    ) -> Result<Self, Error> {
-        <Self as AddSubscription>::add_subscription(self, folder_name, sub_title, sub_link)
+    fn import_nested(nested_folders: NestedFolders) -> Model {
+        import_opml(NESTED_IMPORT_OPML, nested_folders)
     }
 
-    pub fn delete_subscription(
-        &self,
-        folder_name: &Option<FolderName>,
-        sub_title: &SubscriptionTitle,
-    ) -> Self {
-        <Self as DeleteSubscription>::delete_subscription(self, folder_name, sub_title)
+    fn folder_texts(model: &Model) -> Vec<(String, Vec<String>)> {
+        model.accounts.acct[0]
+            .subs
+            .subs
+            .body
+            .outlines
+            .iter()
+            .map(|folder| {
+                (
+                    folder.text.to_string(),
+                    folder
+                        .outlines
+                        .iter()
+                        .map(|sub| sub.text.to_string())
+                        .collect(),
+                )
+            })
+            .collect()
     }
 
-    pub fn rename_subscription(
-        &self,
-        folder_name: &Option<FolderName>,
-        sub_link: &SubscriptionLink,
-        old_sub_name: &OldSubscriptionName,
-        new_sub_name: &NewSubscriptionName,
-    ) -> Result<Self, Error> {
-        <Self as RenameSubscription>::rename_subscription(
-            self,
-            folder_name,
-            sub_link,
-            old_sub_name,
-            new_sub_name,
-        )
+    #[test]
+    fn flatten_nested_folders() {
+        let model = import_nested(NestedFolders::Flatten);
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+        let folders = outlines
+            .iter()
+            .map(|folder| {
+                (
+                    folder.text.as_str(),
+                    folder
+                        .outlines
+                        .iter()
+                        .map(|sub| sub.text.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            folders,
+            vec![
+                ("News", vec!["World"]),
+                ("News / Tech", vec!["Gadgets"]),
+                ("News / Tech / Rust", vec!["Crates"]),
+                ("Blogs / Personal", vec!["Diary"]),
+            ]
+        );
+        assert_eq!(model.import_report.added.len(), 4);
     }
 
-    pub fn move_subscription(
-        &self,
-        subscription: &Subscription,
-        old_folder: &OldFolder,
-        new_folder: &NewFolder,
-    ) -> Result<Self, Error> {
-        <Self as MoveSubscription>::move_subscription(self, subscription, old_folder, new_folder)
+    #[test]
+    fn fail_import_for_nested_folders() {
+        let model = import_nested(NestedFolders::Reject);
+
+        assert!(model.accounts.acct[0].subs.subs.body.outlines.is_empty());
+        assert_eq!(model.notification.title, "Import Error");
+        assert_eq!(
+            model.notification.message,
+            "Cannot import folder \"News / Tech\". Folders can't be nested."
+        );
     }
 
-    pub fn add_feed(&self, body: Vec<u8>) -> Result<Self, Error> {
-        <Self as AddFeed>::add_feed(self, body)
+    #[test]
+    fn keep_nested_folders() {
+        let model = import_nested(NestedFolders::Keep);
+        let subs = &model.accounts.acct[0].subs;
+        let rust = FolderPath::from(vec![
+            FolderName::from("News"),
+            FolderName::from("Tech"),
+            FolderName::from("Rust"),
+        ]);
+
+        assert_eq!(subs.subs.body.outlines.len(), 2);
+        assert_eq!(
+            subs.find_subscription_by_id(&SubscriptionId::from_link(
+                "https://example.com/crates.xml"
+            ))
+            .map(|(folder_path, sub)| (folder_path, sub.text)),
+            Some((Some(rust), "Crates".to_string()))
+        );
+        assert_eq!(model.import_report.added.len(), 4);
     }
 
-    pub fn find_feed(&self, feed_title: &SubscriptionTitle) -> Result<Feed, Error> {
-        <Self as FindFeed>::find_feed(self, feed_title)
+    #[test]
+    fn drop_empty_folders_and_invalid_links() {
+        for nested_folders in [
+            NestedFolders::Flatten,
+            NestedFolders::Reject,
+            NestedFolders::Keep,
+        ] {
+            let model = import_opml(EMPTY_FOLDERS_OPML, nested_folders);
+
+            assert_eq!(
+                folder_texts(&model),
+                vec![("News".to_string(), vec!["World".to_string()])]
+            );
+            assert_eq!(model.import_report.added.len(), 1);
+            assert_eq!(model.notification.title, "");
+        }
     }
 }
 
 #[cfg(test)]
-mod import_export {
+mod outline_metadata {
     use super::*;
-    use crate::{Account, AccountType, Accounts};
-    use crate::{App, Event, Model};
-    // use chrono::prelude::Local;
+    use crate::fixtures::{add_folder, add_subscription, fetched, model_with_account, rss_link};
+    use crate::Account;
+    use crate::{App, Effect, Event, FileOperation, Model};
+    use assert_matches::assert_matches;
     use crux_core::App as _;
-    use opml::OPML;
 
-    #[test]
-    fn import_subscriptions() {
-        let app = App;
-        let mut model = Model::default();
-        let account = Account::new(&AccountType::Local);
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-
-        let example_import_opml = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
-        let example_subs = r#"<opml version="2.0"><head><title>Subscriptions.opml</title><dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated><ownerName>Crab News</ownerName></head><body><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/><outline text="Group Name" title="Group Name"><outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/></outline></body></opml>"#;
+    const METADATA_RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><language>en-gb</language><category>Tech</category><category>Rust</category><item><guid>https://example.com/one</guid><title>One</title></item></channel></rss>"#;
 
-        let _ = app.update(
-            Event::ImportSubscriptions(account.clone(), example_import_opml),
-            &mut model,
-            &(),
-        );
+    fn expected_outline(text: &str) -> Outline {
+        Outline {
+            text: text.to_string(),
+            title: Some("Feed Name".to_string()),
+            r#type: Some("rss".to_string()),
+            version: Some("RSS2".to_string()),
+            xml_url: Some(rss_link().to_string()),
+            html_url: Some("https://example.com/".to_string()),
+            description: Some("An example feed".to_string()),
+            language: Some("en-gb".to_string()),
+            category: Some("Tech,Rust".to_string()),
+            ..Outline::default()
+        }
+    }
 
-        let added_subs = model.accounts.acct[account_index].subs.clone();
-        let subs_feeds = added_subs.feeds.clone();
+    fn model_with_fetched_feed(app: &App) -> (Model, Account) {
+        let (mut model, account) = model_with_account(app);
 
-        let expected_subs = Subscriptions {
-            subs: OPML::from_str(example_subs).unwrap(),
-            feeds: subs_feeds,
-        };
+        add_folder(app, &mut model, &account, None, "Group Name");
+        add_subscription(app, &mut model, &account, None, "My Feed", &rss_link());
+        fetched(app, &mut model, &account, &rss_link(), METADATA_RSS);
 
-        assert_eq!(added_subs, expected_subs);
+        (model, account)
     }
 
     #[test]
-    fn fail_import_for_invalid_xml() {
+    fn fill_metadata_from_fetched_feed() {
         let app = App;
-        let mut model = Model::default();
-        let account = Account::new(&AccountType::Local);
+        let (model, _) = model_with_fetched_feed(&app);
 
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let invalid_xml_opml = r#"<?xml version="2.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
+        assert_eq!(
+            model.accounts.acct[0].subs.subs.body.outlines[1],
+            expected_outline("My Feed")
+        );
+    }
+
+    #[test]
+    fn rename_subscription_keeps_metadata() {
+        let app = App;
+        let (mut model, account) = model_with_fetched_feed(&app);
 
         let _ = app.update(
-            Event::ImportSubscriptions(account, invalid_xml_opml),
+            Event::RenameSubscription(
+                account,
+                SubscriptionId::from(&rss_link()),
+                NewSubscriptionName::from("Renamed Feed"),
+            ),
             &mut model,
             &(),
         );
-        let actual_error = model.notification.message;
-        let expected_error = "Failed to process XML file";
 
-        assert_eq!(actual_error, expected_error);
+        assert_eq!(
+            model.accounts.acct[0].subs.subs.body.outlines[1],
+            expected_outline("Renamed Feed")
+        );
     }
 
     #[test]
-    fn fail_import_for_invalid_opml_version() {
+    fn move_subscription_keeps_metadata() {
         let app = App;
-        let mut model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let invalid_version_opml =r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="0.1"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/atom.xml"/> <outline text="Group Name" title="Group Name"> <outline text="Feed Name" title="Feed Name" description="" type="rss" version="RSS" htmlUrl="https://example.com/" xmlUrl="https://example.com/rss.xml"/> </outline> </body> </opml>"#.to_string();
-
+        let (mut model, account) = model_with_fetched_feed(&app);
         let _ = app.update(
-            Event::ImportSubscriptions(account, invalid_version_opml),
+            Event::MoveSubscription(
+                account,
+                SubscriptionId::from(&rss_link()),
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Group Name")),
+            ),
             &mut model,
             &(),
         );
-        let actual_error = model.notification.message;
-        let expected_error = "Unsupported OPML version: \"0.1\"";
 
-        assert_eq!(actual_error, expected_error);
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].outlines, vec![expected_outline("My Feed")]);
     }
 
     #[test]
-    fn fail_import_for_body_has_no_outlines() {
+    fn export_subscription_metadata() {
         let app = App;
-        let mut model = Model::default();
-        let account = Account::new(&AccountType::Local);
+        let (mut model, account) = model_with_fetched_feed(&app);
 
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let invalid_body_opml = r#"<?xml version="1.0" encoding="ISO-8859-1"?> <opml version="2.0"> <head> <title>Subscriptions.opml</title> <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated> <ownerName>Crab News</ownerName> </head> <body> </body> </opml>"#.to_string();
-
-        let _ = app.update(
-            Event::ImportSubscriptions(account, invalid_body_opml),
+        let mut cmd = app.update(
+            Event::ExportSubscriptions(account, "Subscriptions.opml".to_string()),
             &mut model,
             &(),
         );
-        let actual_error = model.notification.message;
-        let expected_error = "OPML body has no <outline> elements";
+        let request = assert_matches!(
+            cmd.effects().next(),
+            Some(Effect::File(request)) => request
+        );
+        let FileOperation::Save { bytes, .. } = request.operation;
+        let exported_opml = OPML::from_str(&String::from_utf8(bytes).unwrap()).unwrap();
 
-        assert_eq!(actual_error, expected_error);
+        assert_eq!(exported_opml.body.outlines[1], expected_outline("My Feed"));
     }
-
-    // #[test]
-    // fn export_subscriptions() {
-    //     let app = App;
-    //     let mut model: Model = Model::default();
-    //     let account = Account::new(&AccountType::Local);
-    //     let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-    //     let account_index = Accounts::find_by_index(&model.accounts, &account);
-
-    //     let opml_file_name = "Subscriptions.opml".to_string();
-    //     let date_created = Local::now().format("%Y - %a %b %e %T").to_string();
-    //     let example_subs = format!("<opml version=\"2.0\"><head><title>{:?}</title><dateCreated>{}</dateCreated><ownerName>Crab News</ownerName><ownerId>https://github.com/crab-apps/crab-news</ownerId></head><body><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/atom.xml\"/><outline text=\"Group Name\" title=\"Group Name\"><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/rss.xml\"/></outline></body></opml>", opml_file_name, date_created.clone());
-
-    //     model.accounts.acct[account_index].subs = Subscriptions {
-    //         subs: OPML::from_str(&example_subs).unwrap(),
-    //         feeds: Feeds::default(),
-    //     };
-    //     let imported_content = model.accounts.acct[account_index].subs.clone();
-
-    //     let _ = app.update(
-    //         Event::ExportSubscriptions(account, opml_file_name),
-    //         &mut model,
-    //         &(),
-    //     );
-
-    //     let exported_content = Subscriptions {
-    //         subs: OPML::from_str(&opml_content).unwrap(),
-    //         feeds: Feeds::default(),
-    //     };
-
-    //     assert_eq!(exported_content, imported_content);
-    // }
-
-    // FIXME broke after export initial refactor to remove std::io
-    // #[test]
-    // fn export_subscriptions_notification() {
-    //     let app = App;
-    //     let mut model: Model = Model::default();
-    //     let account = Account::new(&AccountType::Local);
-    //     let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-    //     let account_index = Accounts::find_by_index(&model.accounts, &account);
-
-    //     let opml_file_name = "Subscriptions.opml".to_string();
-    //     let date_created = Local::now().format("%Y - %a %b %e %T").to_string();
-    //     let example_subs = format!("<opml version=\"2.0\"><head><title>{:?}</title><dateCreated>{}</dateCreated><ownerName>Crab News</ownerName><ownerId>https://github.com/crab-apps/crab-news</ownerId></head><body><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/atom.xml\"/><outline text=\"Group Name\" title=\"Group Name\"><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/rss.xml\"/></outline></body></opml>", opml_title, date_created.clone());
-
-    //     model.accounts.acct[account_index].subs = Subscriptions {
-    //         subs: OPML::from_str(&example_subs).unwrap(),
-    //         feeds: Feeds::default(),
-    //     };
-
-    //     let _ = app.update(
-    //         Event::ExportSubscriptions(account, opml_file_name),
-    //         &mut model,
-    //         &(),
-    //     );
-
-    //     let actual_notification = model.notification.message;
-    //     let expected_notification = "Subscriptions successfully exported";
-
-    //     assert_eq!(actual_notification, expected_notification);
-    // }
-
-    // TODO once shell is implemented, check failures
-    // #[test]
-    // fn fail_export_subscriptions() {
-    //     let app = CrabNews;
-    //     let mut model: Model = Model::default();
-    //     let date_created = Some(Local::now().format("%Y - %a %b %e %T").to_string());
-    //     let opml_content = format!("{} - Subscriptions.opml", date_created.clone().unwrap());
-    //     let example_subs = format!("<opml version=\"2.0\"><head><title>{}</title><dateCreated>{}</dateCreated><ownerName>Crab News</ownerName><ownerId>https://github.com/crab-apps/crab-news</ownerId></head><body><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/atom.xml\"/><outline text=\"Group Name\" title=\"Group Name\"><outline text=\"Feed Name\" title=\"Feed Name\" description=\"\" type=\"rss\" version=\"RSS\" htmlUrl=\"https://example.com/\" xmlUrl=\"https://example.com/rss.xml\"/></outline></body></opml>", opml_content, date_created.unwrap());
-
-    //     model.subscriptions = Subscriptions {
-    //         opml: OPML::from_str(&example_subs).unwrap(),
-    //     };
-    //     let imported_content = model.subscriptions.clone();
-
-    //     let _ = app.update(
-    //         Event::ExportSubscriptions(opml_content.clone()),
-    //         &mut model, &()
-    //     );
-
-    //     // TODO use proper Shell/WASM/crate functionality to File operations
-    //     let mut exported_file = std::fs::File::open(opml_content.clone()).unwrap();
-    //     let exported_content = Subscriptions {
-    //         opml: OPML::from_reader(&mut exported_file).unwrap(),
-    //     };
-
-    //     assert_eq!(exported_content, imported_content);
 }
 
 #[cfg(test)]
 mod folder {
     use super::*;
+    use crate::fixtures::{add_subscription, fetched, folder_id, model_with_folders};
+    use crate::fixtures::{EXAMPLE_ATOM, EXAMPLE_RSS};
     use crate::{Account, AccountType, Accounts};
     use crate::{App, Event, Model};
     use crux_core::App as _;
@@ -737,18 +2787,22 @@ mod folder {
     fn add_new_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Added Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("Added Folder".to_string());
         let added_folder = &Outline {
             text: folder_name.to_string(),
             title: Some(folder_name.to_string()),
             ..Outline::default()
         };
 
-        let _ = app.update(Event::AddNewFolder(account, folder_name), &mut model, &());
+        let _ = app.update(
+            Event::AddNewFolder(account, None, folder_name),
+            &mut model,
+            &(),
+        );
 
         let does_contain_new_folder = model.accounts.acct[account_index]
             .subs
@@ -764,12 +2818,12 @@ mod folder {
     fn add_two_new_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name_one = FolderName("Added Folder One".to_string());
-        let folder_name_two = FolderName("Added Folder Two".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name_one = FolderName::from("Added Folder One".to_string());
+        let folder_name_two = FolderName::from("Added Folder Two".to_string());
         let added_folder_one = &Outline {
             text: folder_name_one.to_string(),
             title: Some(folder_name_one.to_string()),
@@ -782,100 +2836,286 @@ mod folder {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name_one),
+            Event::AddNewFolder(account.clone(), None, folder_name_one),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::AddNewFolder(account, None, folder_name_two),
+            &mut model,
+            &(),
+        );
+
+        let does_contain_folder_one = model.accounts.acct[account_index]
+            .subs
+            .subs
+            .body
+            .outlines
+            .contains(added_folder_one);
+        let does_contain_folder_two = model.accounts.acct[account_index]
+            .subs
+            .subs
+            .body
+            .outlines
+            .contains(added_folder_two);
+
+        assert!((does_contain_folder_one && does_contain_folder_two));
+    }
+
+    #[test]
+    fn fail_add_new_folder() {
+        let app = App;
+        let mut model: Model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let folder_name = FolderName::from("Added Folder".to_string());
+
+        let _ = app.update(
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
+            &mut model,
+            &(),
+        );
+        let actual_error = model.notification.message;
+        let expected_error = format!("Cannot add new folder \"{folder_name}\". It already exists.");
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn delete_folder() {
+        let app = App;
+        let mut model: Model = Model::default();
+
+        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let deleted_folder = &Outline {
+            text: "Deleted Folder".to_string(),
+            title: Some("Deleted Folder".to_string()),
+            ..Outline::default()
+        };
+
+        let _ = app.update(
+            Event::AddNewFolder(
+                account.clone(),
+                None,
+                FolderName::from(deleted_folder.text.to_string()),
+            ),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::DeleteFolder(
+                account.clone(),
+                model.accounts.acct[account_index]
+                    .subs
+                    .find_folder_id(&FolderPath::from(deleted_folder.text.to_string()))
+                    .unwrap(),
+                FolderSubscriptions::Delete,
+            ),
+            &mut model,
+            &(),
+        );
+
+        let does_not_contain_deleted_folder = !model.accounts.acct[account_index]
+            .subs
+            .subs
+            .body
+            .outlines
+            .contains(deleted_folder);
+
+        assert!(does_not_contain_deleted_folder);
+    }
+
+    fn model_with_folder_subs(app: &App) -> (Model, Account) {
+        let (mut model, account, folder_ids) =
+            model_with_folders(app, &["Deleted Folder", "Other Folder"]);
+
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(folder_ids[0].clone()),
+            "Folder Sub",
+            &SubscriptionLink::from("https://example.com/folder.xml"),
+        );
+        // NOTE same name as the folder, must survive the folder's deletion
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            None,
+            "Deleted Folder",
+            &SubscriptionLink::from("https://example.com/root.xml"),
+        );
+
+        (model, account)
+    }
+
+    #[test]
+    fn delete_folder_with_subscriptions() {
+        let app = App;
+        let (mut model, account) = model_with_folder_subs(&app);
+
+        let _ = app.update(
+            Event::DeleteFolder(
+                account,
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Deleted Folder"))
+                    .unwrap(),
+                FolderSubscriptions::Delete,
+            ),
+            &mut model,
+            &(),
+        );
+
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+
+        assert_eq!(
+            outlines,
+            &vec![
+                Subscriptions::set_test_folder("Other Folder"),
+                Subscriptions::set_test_sub("Deleted Folder", "https://example.com/root.xml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_folder_moves_subscriptions_to_root() {
+        let app = App;
+        let (mut model, account) = model_with_folder_subs(&app);
+
+        let _ = app.update(
+            Event::DeleteFolder(
+                account,
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Deleted Folder"))
+                    .unwrap(),
+                FolderSubscriptions::MoveTo(None),
+            ),
             &mut model,
             &(),
         );
+
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+
+        assert_eq!(outlines.len(), 3);
+        assert_eq!(
+            outlines[2],
+            Subscriptions::set_test_sub("Folder Sub", "https://example.com/folder.xml")
+        );
+    }
+
+    #[test]
+    fn delete_folder_moves_subscriptions_to_folder() {
+        let app = App;
+        let (mut model, account) = model_with_folder_subs(&app);
+
         let _ = app.update(
-            Event::AddNewFolder(account, folder_name_two),
+            Event::DeleteFolder(
+                account,
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from("Deleted Folder"))
+                    .unwrap(),
+                FolderSubscriptions::MoveTo(
+                    model.accounts.acct[0]
+                        .subs
+                        .find_folder_id(&FolderPath::from("Other Folder")),
+                ),
+            ),
             &mut model,
             &(),
         );
 
-        let does_contain_folder_one = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .contains(added_folder_one);
-        let does_contain_folder_two = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .contains(added_folder_two);
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
 
-        assert!((does_contain_folder_one && does_contain_folder_two));
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(
+            outlines[0].outlines,
+            vec![Subscriptions::set_test_sub(
+                "Folder Sub",
+                "https://example.com/folder.xml"
+            )]
+        );
     }
 
     #[test]
-    fn fail_add_new_folder() {
+    fn delete_folder_drops_feed_data_of_its_subscriptions() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("Added Folder".to_string());
+        let (mut model, account) = model_with_folder_subs(&app);
+        let folder_link = SubscriptionLink::from("https://example.com/folder.xml");
+        let root_link = SubscriptionLink::from("https://example.com/root.xml");
 
+        fetched(&app, &mut model, &account, &folder_link, EXAMPLE_RSS);
+        fetched(&app, &mut model, &account, &root_link, EXAMPLE_ATOM);
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::ToggleStar(account.clone(), EntryId::from("https://example.com/one")),
             &mut model,
             &(),
         );
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::DeleteFolder(
+                account,
+                folder_id(&model, &FolderPath::from("Deleted Folder")),
+                FolderSubscriptions::Delete,
+            ),
             &mut model,
             &(),
         );
-        let actual_error = model.notification.message;
-        let expected_error = format!("Cannot add new folder \"{folder_name}\". It already exists.");
 
-        assert_eq!(actual_error, expected_error);
+        let subs = &model.accounts.acct[0].subs;
+        let smart_feeds = app.view(&model).smart_feeds;
+
+        assert_eq!(subs.feeds.feeds.len(), 1);
+        assert_eq!(
+            subs.feeds.feeds.keys().collect::<Vec<_>>(),
+            vec![&SubscriptionId::from(&root_link)]
+        );
+        assert!(subs.feeds.status.is_empty());
+        assert_eq!(smart_feeds.all_unread_count, 1);
+        assert_eq!(smart_feeds.starred_count, 0);
     }
 
     #[test]
-    fn delete_folder() {
+    fn fail_delete_missing_folder() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let deleted_folder = &Outline {
-            text: "Deleted Folder".to_string(),
-            title: Some("Deleted Folder".to_string()),
-            ..Outline::default()
-        };
+        let (mut model, account) = model_with_folder_subs(&app);
+        let subs = model.accounts.acct[0].subs.clone();
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), FolderName(deleted_folder.text.to_string())),
-            &mut model,
-            &(),
-        );
-        let _ = app.update(
-            Event::DeleteFolder(account.clone(), FolderName(deleted_folder.text.to_string())),
+            Event::DeleteFolder(
+                account,
+                FolderId::from("missing-folder"),
+                FolderSubscriptions::Delete,
+            ),
             &mut model,
             &(),
         );
 
-        let does_not_contain_deleted_folder = !model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .contains(deleted_folder);
-
-        assert!(does_not_contain_deleted_folder);
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(
+            model.notification.message,
+            "Cannot delete folder \"missing-folder\". It doesn't exist."
+        );
     }
 
     #[test]
     fn rename_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
         let rename_folder = &Outline {
             text: "Rename Folder".to_string(),
             title: Some("Rename Folder".to_string()),
@@ -889,14 +3129,21 @@ mod folder {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), FolderName(rename_folder.text.to_string())),
+            Event::AddNewFolder(
+                account.clone(),
+                None,
+                FolderName::from(rename_folder.text.to_string()),
+            ),
             &mut model,
             &(),
         );
         let _ = app.update(
             Event::RenameFolder(
                 account.clone(),
-                OldFolderName(rename_folder.text.to_string()),
+                model.accounts.acct[account_index]
+                    .subs
+                    .find_folder_id(&FolderPath::from(rename_folder.text.to_string()))
+                    .unwrap(),
                 NewFolderName(expected_folder.text.to_string()),
             ),
             &mut model,
@@ -917,9 +3164,9 @@ mod folder {
     fn fail_rename_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
         let test_folder = &Outline {
             text: "Expected Folder".to_string(),
             title: Some("Expected Folder".to_string()),
@@ -927,14 +3174,21 @@ mod folder {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), FolderName(test_folder.text.to_string())),
+            Event::AddNewFolder(
+                account.clone(),
+                None,
+                FolderName::from(test_folder.text.to_string()),
+            ),
             &mut model,
             &(),
         );
         let _ = app.update(
             Event::RenameFolder(
                 account.clone(),
-                OldFolderName(test_folder.text.to_string()),
+                model.accounts.acct[0]
+                    .subs
+                    .find_folder_id(&FolderPath::from(test_folder.text.to_string()))
+                    .unwrap(),
                 NewFolderName(test_folder.text.to_string()),
             ),
             &mut model,
@@ -954,7 +3208,7 @@ mod folder {
 #[cfg(test)]
 mod add_subscription {
     use super::*;
-    use crate::{Account, AccountType, Accounts};
+    use crate::{AccountType, Accounts};
     use crate::{App, Event, Model};
     use crux_core::App as _;
     use opml::Outline;
@@ -963,10 +3217,10 @@ mod add_subscription {
     fn add_new_subscription_to_root() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
         let sub_title = SubscriptionTitle("New Sub Root".to_string());
         let sub_link = SubscriptionLink("https://example.com/atom.xml".to_string());
         let expected_sub = &Outline {
@@ -995,11 +3249,11 @@ mod add_subscription {
     fn add_new_subscription_to_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("New Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("New Sub Folder".to_string());
         let sub_title = SubscriptionTitle("New Sub Folder".to_string());
         let sub_link = SubscriptionLink("https://example.com/atom.xml".to_string());
         let expected_sub = &Outline {
@@ -1009,17 +3263,15 @@ mod add_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                Some(folder_name.clone()),
-                sub_title,
-                sub_link,
-            ),
+            Event::AddSubscription(account.clone(), folder_id.clone(), sub_title, sub_link),
             &mut model,
             &(),
         );
@@ -1041,9 +3293,9 @@ mod add_subscription {
     fn fail_add_new_subscription_to_root() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
         let sub_title = SubscriptionTitle("New Sub Root".to_string());
         let sub_link = SubscriptionLink("https://example.com/atom.xml".to_string());
         let test_subscription = &Outline {
@@ -1075,10 +3327,10 @@ mod add_subscription {
     fn fail_add_new_subscription_to_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("New Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let folder_name = FolderName::from("New Sub Folder".to_string());
         let sub_title = SubscriptionTitle("New Sub Folder".to_string());
         let sub_link = SubscriptionLink("https://example.com/atom.xml".to_string());
         let test_subscription = &Outline {
@@ -1088,14 +3340,17 @@ mod add_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 sub_title.clone(),
                 sub_link.clone(),
             ),
@@ -1103,7 +3358,7 @@ mod add_subscription {
             &(),
         );
         let _ = app.update(
-            Event::AddSubscription(account.clone(), Some(folder_name), sub_title, sub_link),
+            Event::AddSubscription(account.clone(), folder_id, sub_title, sub_link),
             &mut model,
             &(),
         );
@@ -1120,19 +3375,22 @@ mod add_subscription {
 #[cfg(test)]
 mod delete_subscription {
     use super::*;
-    use crate::{Account, AccountType, Accounts};
+    use crate::fixtures::{add_subscription, atom_link, fetched, model_with_account, rss_link};
+    use crate::fixtures::{EXAMPLE_ATOM, EXAMPLE_RSS};
+    use crate::{AccountType, Accounts};
     use crate::{App, Event, Model};
     use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
     use opml::Outline;
 
     #[test]
     fn delete_subscription_from_root() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
         let deleted_sub = &Outline {
             text: "Deleted Sub Root".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1152,8 +3410,7 @@ mod delete_subscription {
         let _ = app.update(
             Event::DeleteSubscription(
                 account.clone(),
-                None,
-                SubscriptionTitle(deleted_sub.text.to_string()),
+                SubscriptionId::from_link(deleted_sub.xml_url.as_deref().unwrap()),
             ),
             &mut model,
             &(),
@@ -1173,11 +3430,11 @@ mod delete_subscription {
     fn delete_subscription_from_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Deleted Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("Deleted Sub Folder".to_string());
         let deleted_sub = &Outline {
             text: "Sub Name".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1185,14 +3442,17 @@ mod delete_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(deleted_sub.text.to_string()),
                 SubscriptionLink(deleted_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1202,8 +3462,7 @@ mod delete_subscription {
         let _ = app.update(
             Event::DeleteSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(deleted_sub.text.to_string()),
+                SubscriptionId::from_link(deleted_sub.xml_url.as_deref().unwrap()),
             ),
             &mut model,
             &(),
@@ -1226,11 +3485,11 @@ mod delete_subscription {
     fn delete_subscription_from_folder_with_multi_subs() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Deleted Multi Subs".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("Deleted Multi Subs".to_string());
         let delete_sub = &Outline {
             text: "Deleted Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1243,14 +3502,17 @@ mod delete_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(delete_sub.text.to_string()),
                 SubscriptionLink(delete_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1260,7 +3522,7 @@ mod delete_subscription {
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(expected_sub.text.to_string()),
                 SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1270,8 +3532,7 @@ mod delete_subscription {
         let _ = app.update(
             Event::DeleteSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(delete_sub.text.to_string()),
+                SubscriptionId::from_link(delete_sub.xml_url.as_deref().unwrap()),
             ),
             &mut model,
             &(),
@@ -1299,12 +3560,60 @@ mod delete_subscription {
 
         assert!((does_folder_not_contain_deleted_sub && does_folder_contain_expected_sub));
     }
+
+    #[test]
+    fn delete_subscription_drops_its_feed_data() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let response = ResponseBuilder::ok()
+            .header("ETag", r#""abc123""#)
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+
+        add_subscription(&app, &mut model, &account, None, "Feed Name", &rss_link());
+        add_subscription(&app, &mut model, &account, None, "Atom Name", &atom_link());
+        let _ = app.update(
+            Event::SetFeed(account.clone(), rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+        fetched(&app, &mut model, &account, &atom_link(), EXAMPLE_ATOM);
+        let _ = app.update(
+            Event::ToggleStar(account.clone(), EntryId::from("https://example.com/one")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::MarkEntryRead(account.clone(), EntryId::from("https://example.com/two")),
+            &mut model,
+            &(),
+        );
+        let _ = app.update(
+            Event::DeleteSubscription(account, SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+
+        let subs = &model.accounts.acct[0].subs;
+        let smart_feeds = app.view(&model).smart_feeds;
+
+        assert_eq!(subs.feeds.feeds.len(), 1);
+        assert_eq!(
+            subs.feeds.feeds.keys().collect::<Vec<_>>(),
+            vec![&SubscriptionId::from(&atom_link())]
+        );
+        assert!(subs.feeds.status.is_empty());
+        assert!(subs.http_cache.is_empty());
+        assert_eq!(subs.feeds.count_unread(), 1);
+        assert_eq!(smart_feeds.all_unread_count, 1);
+        assert_eq!(smart_feeds.starred_count, 0);
+    }
 }
 
 #[cfg(test)]
 mod rename_subscription {
     use super::*;
-    use crate::{Account, AccountType, Accounts};
+    use crate::{AccountType, Accounts};
     use crate::{App, Event, Model};
     use crux_core::App as _;
     use opml::Outline;
@@ -1313,10 +3622,10 @@ mod rename_subscription {
     fn rename_subscription_in_root() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
         let rename_sub = &Outline {
             text: "Old Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1341,9 +3650,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                None,
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(expected_sub.text.to_string()),
             ),
             &mut model,
@@ -1364,9 +3671,9 @@ mod rename_subscription {
     fn fail_rename_subscription_in_root() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
+        let account = model.accounts.acct[0].clone();
         let rename_sub = &Outline {
             text: "Old Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1386,9 +3693,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                None,
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(rename_sub.text.to_string()),
             ),
             &mut model,
@@ -1408,11 +3713,11 @@ mod rename_subscription {
     fn rename_subscription_in_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Renamed Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("Renamed Sub Folder".to_string());
         let rename_sub = &Outline {
             text: "Old Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1425,14 +3730,17 @@ mod rename_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(rename_sub.text.to_string()),
                 SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1442,9 +3750,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(expected_sub.text.to_string()),
             ),
             &mut model,
@@ -1468,10 +3774,10 @@ mod rename_subscription {
     fn fail_rename_subscription_in_folder() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("Renamed Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let folder_name = FolderName::from("Renamed Sub Folder".to_string());
         let rename_sub = &Outline {
             text: "Old Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
@@ -1479,14 +3785,17 @@ mod rename_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(rename_sub.text.to_string()),
                 SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1496,9 +3805,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(rename_sub.text.to_string()),
             ),
             &mut model,
@@ -1518,14 +3825,14 @@ mod rename_subscription {
     fn rename_subscription_in_folder_with_multi_subs() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Renamed Multi Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+        let folder_name = FolderName::from("Renamed Multi Sub Folder".to_string());
         let untouched_sub = &Outline {
             text: "Untouched Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
+            xml_url: Some("https://example.com/rss.xml".to_string()),
             ..Outline::default()
         };
         let rename_sub = &Outline {
@@ -1540,14 +3847,17 @@ mod rename_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(untouched_sub.text.to_string()),
                 SubscriptionLink(untouched_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1557,9 +3867,9 @@ mod rename_subscription {
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+                folder_id.clone(),
+                SubscriptionTitle(rename_sub.text.to_string()),
+                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
             ),
             &mut model,
             &(),
@@ -1567,9 +3877,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(expected_sub.text.to_string()),
             ),
             &mut model,
@@ -1603,13 +3911,13 @@ mod rename_subscription {
     fn fail_rename_subscription_in_folder_with_multi_subs() {
         let app = App;
         let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
 
         let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("Renamed Multi Sub Folder".to_string());
+        let account = model.accounts.acct[0].clone();
+        let folder_name = FolderName::from("Renamed Multi Sub Folder".to_string());
         let untouched_sub = &Outline {
             text: "Untouched Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
+            xml_url: Some("https://example.com/rss.xml".to_string()),
             ..Outline::default()
         };
         let rename_sub = &Outline {
@@ -1619,14 +3927,17 @@ mod rename_subscription {
         };
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account.clone(), None, folder_name.clone()),
             &mut model,
             &(),
         );
+        let folder_id = model.accounts.acct[0]
+            .subs
+            .find_folder_id(&FolderPath::from(folder_name.clone()));
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(untouched_sub.text.to_string()),
                 SubscriptionLink(untouched_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1636,7 +3947,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
+                folder_id.clone(),
                 SubscriptionTitle(rename_sub.text.to_string()),
                 SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
             ),
@@ -1646,9 +3957,7 @@ mod rename_subscription {
         let _ = app.update(
             Event::RenameSubscription(
                 account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionLink(rename_sub.xml_url.clone().unwrap().clone()),
-                OldSubscriptionName(rename_sub.text.to_string()),
+                SubscriptionId::from_link(rename_sub.xml_url.as_deref().unwrap()),
                 NewSubscriptionName(rename_sub.text.to_string()),
             ),
             &mut model,
@@ -1668,48 +3977,56 @@ mod rename_subscription {
 #[cfg(test)]
 mod move_subscription {
     use super::*;
-    use crate::{Account, AccountType, Accounts};
+    use crate::fixtures::{add_subscription, model_with_folders};
+    use crate::{Account, Accounts};
     use crate::{App, Event, Model};
     use crux_core::App as _;
     use opml::Outline;
 
-    #[test]
-    fn move_subscription_from_root_to_folder() {
-        let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Move Sub To Folder".to_string());
-        let expected_sub = &Outline {
+    fn moved_sub() -> Outline {
+        Outline {
             text: "Moved Sub".to_string(),
             xml_url: Some("https://example.com/atom.xml".to_string()),
             ..Outline::default()
-        };
+        }
+    }
 
-        let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
-            &mut model,
-            &(),
-        );
+    fn moved_sub_id() -> SubscriptionId {
+        SubscriptionId::from_link("https://example.com/atom.xml")
+    }
+
+    fn add_moved_sub(app: &App, model: &mut Model, account: &Account, folder_id: NewFolder) {
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                None,
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+                folder_id,
+                SubscriptionTitle(moved_sub().text.to_string()),
+                SubscriptionLink(moved_sub().xml_url.unwrap()),
             ),
-            &mut model,
+            model,
             &(),
         );
+    }
+
+    fn folder_contains_sub(model: &Model, folder_name: &str) -> bool {
+        model.accounts.acct[0]
+            .subs
+            .subs
+            .body
+            .outlines
+            .iter()
+            .any(|outline| outline.text == folder_name && outline.outlines.contains(&moved_sub()))
+    }
+
+    #[test]
+    fn move_subscription_from_root_to_folder() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_folders(&app, &["Move Sub To Folder"]);
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
+
+        add_moved_sub(&app, &mut model, &account, None);
         let _ = app.update(
-            Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                None,
-                Some(folder_name.clone()),
-            ),
+            Event::MoveSubscription(account, moved_sub_id(), Some(folder_ids[0].clone())),
             &mut model,
             &(),
         );
@@ -1719,332 +4036,623 @@ mod move_subscription {
             .subs
             .body
             .outlines
-            .contains(expected_sub);
+            .contains(&moved_sub());
+
+        assert!(does_root_not_contain_sub && folder_contains_sub(&model, "Move Sub To Folder"));
+    }
+
+    #[test]
+    fn fail_move_subscription_from_root_to_folder() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_folders(&app, &["Move Sub To Folder"]);
+
+        add_moved_sub(&app, &mut model, &account, Some(folder_ids[0].clone()));
+        let _ = app.update(
+            Event::MoveSubscription(account, moved_sub_id(), Some(folder_ids[0].clone())),
+            &mut model,
+            &(),
+        );
+
+        let actual_error = model.notification.message;
+        let expected_error = format!(
+            "Cannot move subscription to \"{}\". It already exists.",
+            moved_sub().text
+        );
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn move_subscription_from_folder_to_root() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_folders(&app, &["Move Sub To Root"]);
+        let account_index = Accounts::find_by_index(&model.accounts, &account).unwrap();
 
-        let does_folder_contain_sub = model.accounts.acct[account_index]
+        add_moved_sub(&app, &mut model, &account, Some(folder_ids[0].clone()));
+        let _ = app.update(
+            Event::MoveSubscription(account, moved_sub_id(), None),
+            &mut model,
+            &(),
+        );
+
+        let does_root_contain_sub = model.accounts.acct[account_index]
             .subs
             .subs
             .body
             .outlines
-            .iter()
-            .any(|outline| {
-                outline.text == folder_name.to_string() && outline.outlines.contains(expected_sub)
-            });
+            .contains(&moved_sub());
 
-        assert!(does_root_not_contain_sub && does_folder_contain_sub);
+        assert!(does_root_contain_sub && !folder_contains_sub(&model, "Move Sub To Root"));
     }
 
     #[test]
-    fn fail_move_subscription_from_root_to_folder() {
+    fn fail_move_subscription_from_folder_to_root() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
+        let (mut model, account, _) = model_with_folders(&app, &["Move Sub To Root"]);
 
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("Move Sub To Folder".to_string());
-        let expected_sub = &Outline {
-            text: "Moved Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
-            ..Outline::default()
-        };
+        add_moved_sub(&app, &mut model, &account, None);
+        let _ = app.update(
+            Event::MoveSubscription(account, moved_sub_id(), None),
+            &mut model,
+            &(),
+        );
+
+        let actual_error = model.notification.message;
+        let expected_error = format!(
+            "Cannot move subscription to \"{}\". It already exists.",
+            moved_sub().text
+        );
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    #[test]
+    fn move_subscription_from_folder_to_folder() {
+        let app = App;
+        let (mut model, account, folder_ids) =
+            model_with_folders(&app, &["Folder One", "Folder Two"]);
+
+        add_moved_sub(&app, &mut model, &account, Some(folder_ids[0].clone()));
+        let _ = app.update(
+            Event::MoveSubscription(account, moved_sub_id(), Some(folder_ids[1].clone())),
+            &mut model,
+            &(),
+        );
+
+        assert!(!folder_contains_sub(&model, "Folder One"));
+        assert!(folder_contains_sub(&model, "Folder Two"));
+    }
+
+    #[test]
+    fn fail_move_subscription_from_folder_to_folder() {
+        let app = App;
+        let (mut model, account, folder_ids) =
+            model_with_folders(&app, &["Folder One", "Folder Two"]);
+
+        add_moved_sub(&app, &mut model, &account, Some(folder_ids[1].clone()));
+        let _ = app.update(
+            Event::MoveSubscription(account, moved_sub_id(), Some(folder_ids[1].clone())),
+            &mut model,
+            &(),
+        );
+
+        let actual_error = model.notification.message;
+        let expected_error = format!(
+            "Cannot move subscription to \"{}\". It already exists.",
+            moved_sub().text
+        );
+
+        assert_eq!(actual_error, expected_error);
+    }
+
+    fn model_with_batch(app: &App) -> (Model, Account, Vec<FolderId>) {
+        let (mut model, account, folder_ids) =
+            model_with_folders(app, &["Old Folder", "New Folder"]);
+
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            None,
+            "Root Sub",
+            &SubscriptionLink::from("https://example.com/root.xml"),
+        );
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(folder_ids[0].clone()),
+            "Folder Sub",
+            &SubscriptionLink::from("https://example.com/folder.xml"),
+        );
+
+        (model, account, folder_ids)
+    }
+
+    #[test]
+    fn fail_move_subscription_to_missing_folder() {
+        let app = App;
+        let (mut model, account, _) = model_with_batch(&app);
+        let subs = model.accounts.acct[0].subs.clone();
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::MoveSubscription(
+                account,
+                SubscriptionId::from_link("https://example.com/root.xml"),
+                Some(FolderId::from("missing-folder")),
+            ),
             &mut model,
             &(),
         );
+
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(
+            model.notification.message,
+            "Cannot move subscription to \"missing-folder\". The folder doesn't exist."
+        );
+    }
+
+    #[test]
+    fn fail_move_missing_subscription() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_batch(&app);
+        let subs = model.accounts.acct[0].subs.clone();
+
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                None,
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+            Event::MoveSubscription(
+                account,
+                SubscriptionId::from_link("https://example.com/gone.xml"),
+                Some(folder_ids[1].clone()),
             ),
             &mut model,
             &(),
         );
+
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(
+            model.notification.message,
+            "Cannot move subscription \"https://example.com/gone.xml\". It doesn't exist."
+        );
+    }
+
+    #[test]
+    fn move_subscriptions_in_batch() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_batch(&app);
+        let root_sub = Subscriptions::set_test_sub("Root Sub", "https://example.com/root.xml");
+        let folder_sub =
+            Subscriptions::set_test_sub("Folder Sub", "https://example.com/folder.xml");
+
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+            Event::MoveSubscriptions(
+                account,
+                vec![
+                    SubscriptionId::from_link("https://example.com/root.xml"),
+                    SubscriptionId::from_link("https://example.com/folder.xml"),
+                ],
+                Some(folder_ids[1].clone()),
             ),
             &mut model,
             &(),
         );
+
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
+
+        assert_eq!(outlines.len(), 2);
+        assert!(outlines[0].outlines.is_empty());
+        assert_eq!(outlines[1].outlines, vec![root_sub, folder_sub]);
+    }
+
+    #[test]
+    fn fail_move_subscriptions_in_batch() {
+        let app = App;
+        let (mut model, account, folder_ids) = model_with_batch(&app);
+        let subs = model.accounts.acct[0].subs.clone();
+
         let _ = app.update(
-            Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                None,
-                Some(folder_name.clone()),
+            Event::MoveSubscriptions(
+                account,
+                vec![
+                    SubscriptionId::from_link("https://example.com/root.xml"),
+                    SubscriptionId::from_link("https://example.com/gone.xml"),
+                ],
+                Some(folder_ids[1].clone()),
             ),
             &mut model,
             &(),
         );
 
-        let actual_error = model.notification.message;
-        let expected_error = format!(
-            "Cannot move subscription to \"{}\". It already exists.",
-            expected_sub.text
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(model.notification.title, "Subscription Error");
+    }
+}
+
+#[cfg(test)]
+mod nested_folders {
+    use super::*;
+    use crate::fixtures::{add_subscription, folder_id, model_with_nested_folder, rss_link};
+    use crate::{Account, App, Event, Model};
+    use crux_core::App as _;
+    use crux_http::testing::ResponseBuilder;
+
+    const TWO_ENTRIES_RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><item><guid>https://example.com/one</guid><title>One</title></item><item><guid>https://example.com/two</guid><title>Two</title></item></channel></rss>"#;
+
+    fn engineering() -> FolderPath {
+        FolderPath::from("Engineering")
+    }
+
+    fn rust() -> FolderPath {
+        engineering().join(&FolderName::from("Rust"))
+    }
+
+    fn model_with_nested_subscription(app: &App) -> (Model, Account) {
+        let (mut model, account, folder_id) = model_with_nested_folder(app);
+
+        add_subscription(
+            app,
+            &mut model,
+            &account,
+            Some(folder_id),
+            "Feed Name",
+            &rss_link(),
         );
 
-        assert_eq!(actual_error, expected_error);
+        (model, account)
+    }
+
+    fn rust_folder(model: &Model) -> &Outline {
+        &model.accounts.acct[0].subs.subs.body.outlines[0].outlines[0]
     }
 
     #[test]
-    fn move_subscription_from_folder_to_root() {
+    fn add_subscription_to_nested_folder() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
+        let (model, _) = model_with_nested_subscription(&app);
 
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_name = FolderName("Move Sub To Root".to_string());
-        let expected_sub = &Outline {
-            text: "Moved Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
-            ..Outline::default()
-        };
+        assert_eq!(rust_folder(&model).text, "Rust");
+        assert_eq!(
+            rust_folder(&model).outlines[0].xml_url,
+            Some(rss_link().to_string())
+        );
+        assert_eq!(model.notification.title, "");
+    }
+
+    #[test]
+    fn allow_same_folder_name_under_different_parents() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::AddNewFolder(account, None, FolderName::from("Rust")),
             &mut model,
             &(),
         );
+
+        assert_eq!(model.accounts.acct[0].subs.subs.body.outlines.len(), 2);
+        assert_eq!(model.notification.title, "");
+    }
+
+    #[test]
+    fn fail_add_folder_to_missing_parent() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+            Event::AddNewFolder(
+                account,
+                Some(FolderId::from("missing-folder")),
+                FolderName::from("Puzzles"),
             ),
             &mut model,
             &(),
         );
+
+        assert_eq!(
+            model.notification.message,
+            "Cannot add new folder to \"missing-folder\". The folder doesn't exist."
+        );
+    }
+
+    #[test]
+    fn fail_add_duplicate_nested_folder() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
+
         let _ = app.update(
-            Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                Some(folder_name.clone()),
-                None,
+            Event::AddNewFolder(
+                account,
+                Some(folder_id(&model, &engineering())),
+                FolderName::from("Rust"),
             ),
             &mut model,
             &(),
         );
 
-        let does_root_contain_sub = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .contains(expected_sub);
-
-        let does_folder_not_contain_sub = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .iter()
-            .all(|outline| {
-                outline.text == folder_name.to_string() || !outline.outlines.contains(expected_sub)
-            });
-
-        assert!(does_root_contain_sub && does_folder_not_contain_sub);
+        assert_eq!(
+            model.notification.message,
+            "Cannot add new folder \"Engineering / Rust\". It already exists."
+        );
     }
 
     #[test]
-    fn fail_move_subscription_from_folder_to_root() {
+    fn rename_nested_folder() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_name = FolderName("Move Sub To Root".to_string());
-        let expected_sub = &Outline {
-            text: "Moved Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
-            ..Outline::default()
-        };
+        let (mut model, account) = model_with_nested_subscription(&app);
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_name.clone()),
+            Event::RenameFolder(
+                account,
+                folder_id(&model, &rust()),
+                NewFolderName::from("Go"),
+            ),
             &mut model,
             &(),
         );
+
+        assert_eq!(rust_folder(&model).text, "Go");
+        assert_eq!(rust_folder(&model).outlines.len(), 1);
+    }
+
+    #[test]
+    fn move_subscription_out_of_nested_folder() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                Some(folder_name.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+            Event::MoveSubscription(
+                account,
+                SubscriptionId::from(&rss_link()),
+                Some(folder_id(&model, &engineering())),
             ),
             &mut model,
             &(),
         );
+        let folder = &model.accounts.acct[0].subs.subs.body.outlines[0];
+
+        assert!(folder.outlines[0].outlines.is_empty());
+        assert_eq!(folder.outlines[1].xml_url, Some(rss_link().to_string()));
+    }
+
+    #[test]
+    fn delete_folder_with_subfolders() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
+
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                None,
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+            Event::DeleteFolder(
+                account,
+                folder_id(&model, &engineering()),
+                FolderSubscriptions::Delete,
             ),
             &mut model,
             &(),
         );
+
+        assert!(model.accounts.acct[0].subs.subs.body.outlines.is_empty());
+    }
+
+    #[test]
+    fn delete_folder_moves_subfolders() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
+
         let _ = app.update(
-            Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                Some(folder_name.clone()),
-                None,
+            Event::DeleteFolder(
+                account,
+                folder_id(&model, &engineering()),
+                FolderSubscriptions::MoveTo(None),
             ),
             &mut model,
             &(),
         );
+        let outlines = &model.accounts.acct[0].subs.subs.body.outlines;
 
-        let actual_error = model.notification.message;
-        let expected_error = format!(
-            "Cannot move subscription to \"{}\". It already exists.",
-            expected_sub.text
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].text, "Rust");
+        assert_eq!(
+            outlines[0].outlines[0].xml_url,
+            Some(rss_link().to_string())
         );
-
-        assert_eq!(actual_error, expected_error);
     }
 
     #[test]
-    fn move_subscription_from_folder_to_folder() {
+    fn fail_move_subscriptions_into_deleted_subfolder() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let account_index = Accounts::find_by_index(&model.accounts, &account);
-        let folder_one = FolderName("Folder One".to_string());
-        let folder_two = FolderName("Folder Two".to_string());
-        let expected_sub = &Outline {
-            text: "Moved Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
-            ..Outline::default()
-        };
+        let (mut model, account) = model_with_nested_subscription(&app);
+        let subs = model.accounts.acct[0].subs.clone();
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_one.clone()),
+            Event::DeleteFolder(
+                account,
+                folder_id(&model, &engineering()),
+                FolderSubscriptions::MoveTo(Some(folder_id(&model, &rust()))),
+            ),
             &mut model,
             &(),
         );
-        let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_two.clone()),
-            &mut model,
-            &(),
+
+        assert_eq!(model.accounts.acct[0].subs, subs);
+        assert_eq!(
+            model.notification.message,
+            "Cannot move subscriptions to \"Engineering / Rust\". The folder is being deleted."
         );
+    }
+
+    #[test]
+    fn roll_up_unread_counts_and_mark_subfolders_read() {
+        let app = App;
+        let (mut model, account) = model_with_nested_subscription(&app);
+        let response = ResponseBuilder::ok()
+            .body(TWO_ENTRIES_RSS.as_bytes().to_vec())
+            .build();
+
         let _ = app.update(
-            Event::AddSubscription(
-                account.clone(),
-                Some(folder_one.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
-            ),
+            Event::SetFeed(account.clone(), rss_link(), Ok(response)),
             &mut model,
             &(),
         );
+
+        assert_eq!(
+            model.accounts.acct[0].subs.folder_unread_counts(),
+            vec![
+                (folder_id(&model, &engineering()), 2),
+                (folder_id(&model, &rust()), 2)
+            ]
+        );
+
         let _ = app.update(
-            Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                Some(folder_one.clone()),
-                Some(folder_two.clone()),
-            ),
+            Event::MarkFolderRead(account, folder_id(&model, &engineering())),
             &mut model,
             &(),
         );
 
-        let does_folder_one_not_contain_sub = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .iter()
-            .any(|outline| {
-                outline.text == folder_one.to_string() && !outline.outlines.contains(expected_sub)
-            });
+        assert_eq!(
+            model.accounts.acct[0]
+                .subs
+                .count_folder_unread(&folder_id(&model, &engineering()))
+                .unwrap(),
+            0
+        );
+    }
+}
 
-        let does_folder_two_contain_sub = model.accounts.acct[account_index]
-            .subs
-            .subs
-            .body
-            .outlines
-            .iter()
-            .any(|outline| {
-                outline.text == folder_two.to_string() && outline.outlines.contains(expected_sub)
-            });
+#[cfg(test)]
+mod stable_ids {
+    use super::*;
+    use crate::fixtures::{add_folder, folder_id, model_with_nested_folder, rss_link};
+    use crate::{Account, App, Event, Model};
+    use crux_core::App as _;
+
+    fn nested_path(parent: &str, folder_name: &str) -> FolderPath {
+        FolderPath::from(parent).join(&FolderName::from(folder_name))
+    }
+
+    fn model_with_folder_tree(app: &App) -> (Model, Account) {
+        let (mut model, account, _) = model_with_nested_folder(app);
 
-        assert!(does_folder_one_not_contain_sub && does_folder_two_contain_sub);
+        add_folder(app, &mut model, &account, None, "Reading");
+
+        (model, account)
     }
 
     #[test]
-    fn fail_move_subscription_from_folder_to_folder() {
+    fn keep_folder_ids_through_parent_rename() {
         let app = App;
-        let mut model: Model = Model::default();
-        let account = Account::new(&AccountType::Local);
-
-        let _ = app.update(Event::CreateAccount(AccountType::Local), &mut model, &());
-        let folder_one = FolderName("Folder One".to_string());
-        let folder_two = FolderName("Folder Two".to_string());
-        let expected_sub = &Outline {
-            text: "Moved Sub".to_string(),
-            xml_url: Some("https://example.com/atom.xml".to_string()),
-            ..Outline::default()
-        };
+        let (mut model, account) = model_with_folder_tree(&app);
+        let engineering_id = folder_id(&model, &FolderPath::from("Engineering"));
+        let rust_id = folder_id(&model, &nested_path("Engineering", "Rust"));
 
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_one.clone()),
+            Event::RenameFolder(
+                account,
+                engineering_id.clone(),
+                NewFolderName::from("Programming"),
+            ),
             &mut model,
             &(),
         );
+        let subs = &model.accounts.acct[0].subs;
+
+        assert_eq!(
+            subs.find_folder_path(&engineering_id),
+            Some(FolderPath::from("Programming"))
+        );
+        assert_eq!(
+            subs.find_folder_path(&rust_id),
+            Some(nested_path("Programming", "Rust"))
+        );
+        assert_eq!(subs.folder_ids.len(), 3);
+    }
+
+    #[test]
+    fn keep_folder_ids_of_moved_subfolders() {
+        let app = App;
+        let (mut model, account) = model_with_folder_tree(&app);
+        let rust_id = folder_id(&model, &nested_path("Engineering", "Rust"));
+        let reading_id = folder_id(&model, &FolderPath::from("Reading"));
+
         let _ = app.update(
-            Event::AddNewFolder(account.clone(), folder_two.clone()),
+            Event::DeleteFolder(
+                account,
+                folder_id(&model, &FolderPath::from("Engineering")),
+                FolderSubscriptions::MoveTo(Some(reading_id)),
+            ),
             &mut model,
             &(),
         );
+        let subs = &model.accounts.acct[0].subs;
+
+        assert_eq!(
+            subs.find_folder_path(&rust_id),
+            Some(nested_path("Reading", "Rust"))
+        );
+        assert_eq!(subs.folder_ids.len(), 2);
+    }
+
+    #[test]
+    fn keep_subscription_id_through_rename_and_move() {
+        let app = App;
+        let (mut model, account) = model_with_folder_tree(&app);
+        let sub_id = SubscriptionId::from(&rss_link());
+
         let _ = app.update(
             Event::AddSubscription(
                 account.clone(),
-                Some(folder_one.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+                None,
+                SubscriptionTitle::from("Feed Name"),
+                rss_link(),
             ),
             &mut model,
             &(),
         );
         let _ = app.update(
-            Event::AddSubscription(
+            Event::RenameSubscription(
                 account.clone(),
-                Some(folder_two.clone()),
-                SubscriptionTitle(expected_sub.text.to_string()),
-                SubscriptionLink(expected_sub.xml_url.clone().unwrap().clone()),
+                sub_id.clone(),
+                NewSubscriptionName::from("Renamed Feed"),
             ),
             &mut model,
             &(),
         );
         let _ = app.update(
             Event::MoveSubscription(
-                account.clone(),
-                expected_sub.clone(),
-                Some(folder_one.clone()),
-                Some(folder_two.clone()),
+                account,
+                sub_id.clone(),
+                Some(folder_id(&model, &nested_path("Engineering", "Rust"))),
             ),
             &mut model,
             &(),
         );
+        let (folder_path, subscription) = model.accounts.acct[0]
+            .subs
+            .find_subscription_by_id(&sub_id)
+            .unwrap();
 
-        let actual_error = model.notification.message;
-        let expected_error = format!(
-            "Cannot move subscription to \"{}\". It already exists.",
-            expected_sub.text
+        assert_eq!(folder_path, Some(nested_path("Engineering", "Rust")));
+        assert_eq!(subscription.text, "Renamed Feed");
+        assert_eq!(model.notification.title, "");
+    }
+
+    #[test]
+    fn normalise_subscription_id_from_link() {
+        assert_eq!(
+            SubscriptionId::from_link(" https://Example.com/rss.xml"),
+            SubscriptionId::from_link("https://example.com/rss.xml")
         );
+    }
 
-        assert_eq!(actual_error, expected_error);
+    #[test]
+    fn fail_get_feed_for_missing_subscription() {
+        let app = App;
+        let (mut model, account) = model_with_folder_tree(&app);
+
+        let _ = app.update(
+            Event::GetFeed(account, SubscriptionId::from(&rss_link())),
+            &mut model,
+            &(),
+        );
+
+        assert_eq!(
+            model.notification.message,
+            "Cannot find subscription \"https://example.com/rss.xml\". It doesn't exist."
+        );
     }
 }
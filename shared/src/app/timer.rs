@@ -0,0 +1,41 @@
+// NOTE - port to the shell's timer, in the style of crux_time.
+// NOTE - Start resolves once, with Finished when the time is up or Cleared when cancelled.
+use super::{Effect, Event};
+
+use crux_core::{capability::Operation, command::RequestBuilder, Command};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TimerId(pub u64);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TimerOperation {
+    Start { id: TimerId, millis: u64 },
+    Clear { id: TimerId },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TimerOutput {
+    Finished { id: TimerId },
+    Cleared { id: TimerId },
+}
+
+impl Operation for TimerOperation {
+    type Output = TimerOutput;
+}
+
+pub struct Timer;
+
+impl Timer {
+    pub fn start(
+        id: TimerId,
+        millis: u64,
+    ) -> RequestBuilder<Effect, Event, impl Future<Output = TimerOutput>> {
+        Command::request_from_shell(TimerOperation::Start { id, millis })
+    }
+
+    pub fn clear(id: TimerId) -> Command<Effect, Event> {
+        Command::notify_shell(TimerOperation::Clear { id }).into()
+    }
+}
@@ -0,0 +1,419 @@
+// NOTE - adds a subscription only once its link is fetched and parsed as a feed.
+// NOTE - the outline is filled from the feed's metadata and the feed is kept, so no refetch.
+use super::discovery::FEED_SCHEMES;
+use super::{FolderId, NewEntriesCount, SubscriptionId, SubscriptionLink, Subscriptions};
+
+use crux_http::Response;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum SubscriptionError {
+    #[error("Cannot add subscription \"{link}\". It isn't a valid link.")]
+    InvalidLink { link: String },
+    #[error("Cannot add subscription \"{link}\". {reason}")]
+    Unreachable { link: String, reason: String },
+    #[error("Cannot add subscription \"{link}\". The server answered {status}.")]
+    HttpStatus { link: String, status: u16 },
+    #[error("Cannot add subscription \"{link}\". It isn't a feed.")]
+    NotAFeed { link: String },
+    #[error("Cannot add subscription \"{link}\". You are already subscribed.")]
+    Duplicate { link: String },
+    #[error("Cannot add subscription to folder \"{folder}\". It doesn't exist.")]
+    FolderNotFound { folder: String },
+}
+
+trait AddValidatedSubscription {
+    fn validate_link(&self, sub_link: &SubscriptionLink) -> Result<(), SubscriptionError>;
+    fn add_validated_subscription(
+        &self,
+        folder_id: &Option<FolderId>,
+        sub_link: &SubscriptionLink,
+        response: crux_http::Result<Response<Vec<u8>>>,
+    ) -> Result<(Self, NewEntriesCount), SubscriptionError>
+    where
+        Self: Sized;
+}
+
+// NOTE adding a duplicate link should always fail no matter where it exists
+// NOTE duplicates are checked before fetching, and again once the fetch is back
+impl AddValidatedSubscription for Subscriptions {
+    fn validate_link(&self, sub_link: &SubscriptionLink) -> Result<(), SubscriptionError> {
+        let link = sub_link.to_string();
+
+        // NOTE only http and https links are fetched, the same as discovered feeds
+        if !Url::parse(sub_link.as_ref()).is_ok_and(|url| FEED_SCHEMES.contains(&url.scheme())) {
+            return Err(SubscriptionError::InvalidLink { link });
+        }
+        if self
+            .find_subscription_by_id(&SubscriptionId::from(sub_link))
+            .is_some()
+        {
+            return Err(SubscriptionError::Duplicate { link });
+        }
+        Ok(())
+    }
+
+    fn add_validated_subscription(
+        &self,
+        folder_id: &Option<FolderId>,
+        sub_link: &SubscriptionLink,
+        response: crux_http::Result<Response<Vec<u8>>>,
+    ) -> Result<(Self, NewEntriesCount), SubscriptionError> {
+        let mut subs = self.clone();
+        let link = sub_link.to_string();
+
+        self.validate_link(sub_link)?;
+        let response = response.map_err(|error| SubscriptionError::Unreachable {
+            link: link.clone(),
+            reason: error.to_string(),
+        })?;
+        if !response.status().is_success() {
+            return Err(SubscriptionError::HttpStatus {
+                link,
+                status: response.status().into(),
+            });
+        }
+        let feed = feed_rs::parser::parse(response.body().map(Vec::as_slice).unwrap_or_default())
+            .map_err(|_| SubscriptionError::NotAFeed { link: link.clone() })?;
+        let outline = Self::feed_outline(sub_link, &feed);
+
+        let folder_not_found = || SubscriptionError::FolderNotFound {
+            folder: folder_id.clone().unwrap_or_default().to_string(),
+        };
+        let folder_path = folder_id
+            .as_ref()
+            .map(|folder_id| {
+                subs.find_folder_path(folder_id)
+                    .ok_or_else(folder_not_found)
+            })
+            .transpose()?;
+        subs.folder_outlines_mut(&folder_path)
+            .ok_or_else(folder_not_found)?
+            .push(outline);
+
+        // NOTE the body already parsed, so storing the feed can't fail
+        subs.update_feed(sub_link, response)
+            .map_err(|_| SubscriptionError::NotAFeed { link })
+    }
+}
+
+impl Subscriptions {
+    pub fn validate_link(&self, sub_link: &SubscriptionLink) -> Result<(), SubscriptionError> {
+        <Self as AddValidatedSubscription>::validate_link(self, sub_link)
+    }
+
+    pub fn add_validated_subscription(
+        &self,
+        folder_id: &Option<FolderId>,
+        sub_link: &SubscriptionLink,
+        response: crux_http::Result<Response<Vec<u8>>>,
+    ) -> Result<(Self, NewEntriesCount), SubscriptionError> {
+        <Self as AddValidatedSubscription>::add_validated_subscription(
+            self, folder_id, sub_link, response,
+        )
+    }
+}
+
+#[cfg(test)]
+mod validated_subscription {
+    use super::*;
+    use crate::fixtures::{add_folder, atom_link, model_with_account, rss_link};
+    use crate::fixtures::{EXAMPLE_ATOM, EXAMPLE_RSS};
+    use crate::{Account, App, Event, Model};
+    use crux_core::App as _;
+    use crux_http::http::StatusCode;
+    use crux_http::testing::ResponseBuilder;
+    use opml::Outline;
+
+    fn validated(
+        app: &App,
+        model: &mut Model,
+        account: &Account,
+        folder_id: Option<FolderId>,
+        sub_link: SubscriptionLink,
+        body: &str,
+    ) {
+        let response = ResponseBuilder::ok().body(body.as_bytes().to_vec()).build();
+        let _ = app.update(
+            Event::SetValidatedSubscription(account.clone(), folder_id, sub_link, Ok(response)),
+            model,
+            &(),
+        );
+    }
+
+    #[test]
+    fn fetch_before_adding() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        let mut cmd = app.update(
+            Event::AddValidatedSubscription(account, None, rss_link()),
+            &mut model,
+            &(),
+        );
+        let request = cmd.effects().next().unwrap().expect_http();
+
+        assert_eq!(request.operation.url, rss_link().to_string());
+        assert!(model.accounts.acct[0].subs.subs.body.outlines.is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_link() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let sub_link = SubscriptionLink::from("not a link");
+
+        let mut cmd = app.update(
+            Event::AddValidatedSubscription(account, None, sub_link.clone()),
+            &mut model,
+            &(),
+        );
+
+        assert!(cmd
+            .effects()
+            .all(|effect| !matches!(effect, crate::Effect::Http(_))));
+        assert_eq!(
+            model.subscription_error,
+            Some(SubscriptionError::InvalidLink {
+                link: sub_link.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn reject_link_not_fetched_over_http() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        for sub_link in ["file:///etc/passwd", "ftp://example.com/rss.xml"] {
+            let sub_link = SubscriptionLink::from(sub_link);
+            let mut cmd = app.update(
+                Event::AddValidatedSubscription(account.clone(), None, sub_link.clone()),
+                &mut model,
+                &(),
+            );
+
+            assert!(cmd
+                .effects()
+                .all(|effect| !matches!(effect, crate::Effect::Http(_))));
+            assert_eq!(
+                model.subscription_error,
+                Some(SubscriptionError::InvalidLink {
+                    link: sub_link.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn fill_outline_from_feed_metadata() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        validated(&app, &mut model, &account, None, rss_link(), EXAMPLE_RSS);
+
+        let expected = Outline {
+            text: "Feed Name".to_string(),
+            title: Some("Feed Name".to_string()),
+            r#type: Some("rss".to_string()),
+            version: Some("RSS2".to_string()),
+            xml_url: Some(rss_link().to_string()),
+            html_url: Some("https://example.com/".to_string()),
+            description: Some("An example feed".to_string()),
+            ..Outline::default()
+        };
+
+        assert_eq!(
+            model.accounts.acct[0].subs.subs.body.outlines,
+            vec![expected]
+        );
+        assert!(model.accounts.acct[0].subs.find_feed(&rss_link()).is_ok());
+        assert_eq!(model.new_entries_count, 2);
+        assert_eq!(model.subscription_error, None);
+    }
+
+    #[test]
+    fn fill_atom_outline_with_website_link() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        validated(&app, &mut model, &account, None, atom_link(), EXAMPLE_ATOM);
+
+        let outline = &model.accounts.acct[0].subs.subs.body.outlines[0];
+
+        assert_eq!(outline.version.as_deref(), Some("Atom"));
+        assert_eq!(
+            outline.html_url.as_deref(),
+            Some("https://example.com/blog/")
+        );
+        assert_eq!(outline.description.as_deref(), Some("An atom feed"));
+    }
+
+    #[test]
+    fn add_to_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let folder_id = Some(add_folder(&app, &mut model, &account, None, "Tech"));
+        validated(
+            &app,
+            &mut model,
+            &account,
+            folder_id,
+            rss_link(),
+            EXAMPLE_RSS,
+        );
+
+        let folder = &model.accounts.acct[0].subs.subs.body.outlines[0];
+
+        assert_eq!(folder.outlines.len(), 1);
+        assert_eq!(folder.outlines[0].xml_url, Some(rss_link().to_string()));
+    }
+
+    #[test]
+    fn reject_non_feed() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        validated(
+            &app,
+            &mut model,
+            &account,
+            None,
+            rss_link(),
+            "<html><body>Not a feed</body></html>",
+        );
+
+        assert!(model.accounts.acct[0].subs.subs.body.outlines.is_empty());
+        assert_eq!(
+            model.subscription_error,
+            Some(SubscriptionError::NotAFeed {
+                link: rss_link().to_string()
+            })
+        );
+        assert_eq!(model.notification.title, "Subscription Error");
+    }
+
+    #[test]
+    fn reject_unreachable_link() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        let _ = app.update(
+            Event::SetValidatedSubscription(
+                account,
+                None,
+                rss_link(),
+                Err(crux_http::HttpError::Io("connection refused".to_string())),
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert!(matches!(
+            model.subscription_error,
+            Some(SubscriptionError::Unreachable { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_error_status() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let response = ResponseBuilder::with_status(StatusCode::NotFound)
+            .body(EXAMPLE_RSS.as_bytes().to_vec())
+            .build();
+
+        let _ = app.update(
+            Event::SetValidatedSubscription(account, None, rss_link(), Ok(response)),
+            &mut model,
+            &(),
+        );
+
+        assert!(model.accounts.acct[0].subs.subs.body.outlines.is_empty());
+        assert_eq!(
+            model.subscription_error,
+            Some(SubscriptionError::HttpStatus {
+                link: rss_link().to_string(),
+                status: 404
+            })
+        );
+        assert_eq!(
+            model.notification.message,
+            "Cannot add subscription \"https://example.com/rss.xml\". The server answered 404."
+        );
+    }
+
+    #[test]
+    fn reject_duplicate_before_fetching() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        validated(&app, &mut model, &account, None, rss_link(), EXAMPLE_RSS);
+
+        let mut cmd = app.update(
+            Event::AddValidatedSubscription(
+                account,
+                None,
+                SubscriptionLink::from("https://EXAMPLE.com/rss.xml"),
+            ),
+            &mut model,
+            &(),
+        );
+
+        assert!(cmd
+            .effects()
+            .all(|effect| !matches!(effect, crate::Effect::Http(_))));
+        assert!(matches!(
+            model.subscription_error,
+            Some(SubscriptionError::Duplicate { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_duplicate_in_any_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+        let folder_id = Some(add_folder(&app, &mut model, &account, None, "Tech"));
+        validated(
+            &app,
+            &mut model,
+            &account,
+            folder_id,
+            rss_link(),
+            EXAMPLE_RSS,
+        );
+        validated(&app, &mut model, &account, None, rss_link(), EXAMPLE_RSS);
+
+        assert_eq!(model.accounts.acct[0].subs.subs.body.outlines.len(), 1);
+        assert_eq!(
+            model.subscription_error,
+            Some(SubscriptionError::Duplicate {
+                link: rss_link().to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn reject_missing_folder() {
+        let app = App;
+        let (mut model, account) = model_with_account(&app);
+
+        validated(
+            &app,
+            &mut model,
+            &account,
+            Some(FolderId::from("missing")),
+            rss_link(),
+            EXAMPLE_RSS,
+        );
+
+        assert_eq!(
+            model.subscription_error,
+            Some(SubscriptionError::FolderNotFound {
+                folder: "missing".to_string()
+            })
+        );
+    }
+}
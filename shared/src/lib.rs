@@ -1,3 +1,6 @@
+// uniffi's generated scaffolding trips this lint and can't be annotated directly
+#![allow(clippy::large_const_arrays)]
+
 pub mod app;
 
 #[macro_use]
@@ -2,7 +2,7 @@
 #[macro_export]
 macro_rules! define_newtype {
     ($name:ident) => {
-        #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
+        #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
         pub struct $name(String);
 
         impl std::fmt::Display for $name {
@@ -0,0 +1,1202 @@
+// NOTE - this is the driven adapter for the core's storage port, for Rust shells.
+// NOTE - the core sends each change on its own, so only the rows it touches are written.
+use anyhow::{anyhow, Result};
+use feed_rs::model::{Entry, Feed, Person};
+use opml::Outline;
+use opml::OPML;
+use shared::{
+    Account, AccountId, AccountType, Accounts, EntryId, EntryStatus, Feeds, FolderId, FolderName,
+    FolderPath, HttpCache, ReadStatus, StarStatus, StorageError, StorageOperation, StorageResponse,
+    StorageResult, Subscription, SubscriptionId, SubscriptionLink,
+};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
+    FromRow, Sqlite, SqliteConnection, Transaction,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database at `url` and brings its schema up to date.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        // NOTE one connection keeps in-memory databases alive. SQLite has a single writer anyway.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        MIGRATOR.run(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// Fulfils a `StorageOperation` requested by the core.
+    pub async fn handle(&self, operation: StorageOperation) -> StorageResult {
+        let result = match operation {
+            StorageOperation::Load => self.load().await.map(StorageResponse::Loaded),
+            StorageOperation::SaveAccount(account_id, account_type, name) => self
+                .save_account(&account_id, &account_type, &name)
+                .await
+                .map(|()| StorageResponse::Saved),
+            StorageOperation::DeleteAccount(account_id) => self
+                .delete_account(&account_id)
+                .await
+                .map(|()| StorageResponse::Saved),
+            StorageOperation::SaveSubscriptions(account_id, subs, folder_ids) => self
+                .save_subscriptions(&account_id, &subs, &folder_ids)
+                .await
+                .map(|()| StorageResponse::Saved),
+            StorageOperation::SaveFeed(account_id, sub, feed, http_cache) => self
+                .save_feed(&account_id, &sub, &feed, &http_cache)
+                .await
+                .map(|()| StorageResponse::Saved),
+            StorageOperation::SaveEntryStatus(account_id, status) => self
+                .save_entry_status(&account_id, &status)
+                .await
+                .map(|()| StorageResponse::Saved),
+        };
+
+        result.map_err(|error| StorageError::Io {
+            message: error.to_string(),
+        })
+    }
+
+    async fn load(&self) -> Result<Accounts> {
+        let mut conn = self.pool.acquire().await?;
+        let mut accounts = Accounts::default();
+        let rows: Vec<(i64, String, Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT id, name, alias, uid FROM accounts ORDER BY id")
+                .fetch_all(&mut *conn)
+                .await?;
+
+        for (account_id, name, alias, uid) in rows {
+            let mut account = Account::new(&account_type(&name)?);
+
+            if let Some(alias) = alias {
+                account.name = alias;
+            }
+            if let Some(uid) = uid {
+                account.id = AccountId::from(uid);
+            }
+            account.subs.subs.body.outlines = load_outlines(&mut conn, account_id).await?;
+            account.subs.folder_ids = load_folder_ids(&mut conn, account_id).await?;
+            account.subs.http_cache = load_http_cache(&mut conn, account_id).await?;
+            account.subs.feeds = load_feeds(&mut conn, account_id).await?;
+            account.subs.feeds.status = load_entry_status(&mut conn, account_id).await?;
+            accounts.acct.push(account);
+        }
+
+        Ok(accounts)
+    }
+
+    async fn save_account(
+        &self,
+        account_id: &AccountId,
+        account_type: &AccountType,
+        name: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO accounts (name, alias, uid) VALUES (?, ?, ?)
+            ON CONFLICT (uid) DO UPDATE SET name = excluded.name, alias = excluded.alias",
+        )
+        .bind(account_type_name(account_type)?)
+        .bind(name)
+        .bind(account_id.as_ref())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // NOTE deleting an account cascades to everything it holds
+    async fn delete_account(&self, account_id: &AccountId) -> Result<()> {
+        sqlx::query("DELETE FROM accounts WHERE uid = ?")
+            .bind(account_id.as_ref())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // NOTE subscriptions keep the feed and validators they were fetched with, by link.
+    // NOTE feeds no subscription links to any more go, and so does the status of their entries.
+    async fn save_subscriptions(
+        &self,
+        account_id: &AccountId,
+        subs: &OPML,
+        folder_ids: &HashMap<FolderId, FolderPath>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let account_id = find_account_id(&mut tx, account_id).await?;
+        let fetched: Vec<FetchedRow> = sqlx::query_as(
+            "SELECT xml_url, etag, last_modified, feed_id FROM subscriptions WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let fetched: HashMap<String, FetchedRow> = fetched
+            .into_iter()
+            .map(|row| (row.xml_url.clone(), row))
+            .collect();
+
+        // NOTE deleting folders cascades to their subscriptions
+        sqlx::query("DELETE FROM subscriptions WHERE account_id = ?")
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM folders WHERE account_id = ?")
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+        save_outlines(&mut tx, account_id, subs, folder_ids, &fetched).await?;
+
+        if delete_unsubscribed_feeds(&mut tx, account_id).await? {
+            prune_entry_status(&mut tx, account_id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // NOTE the feed replaces the one its subscription was fetched with, entries and all.
+    // NOTE feed rows are keyed by the subscription, as feeds can share their ids.
+    // NOTE like the core, the status of entries a replaced feed dropped goes.
+    async fn save_feed(
+        &self,
+        account_id: &AccountId,
+        sub: &Subscription,
+        feed: &Feed,
+        http_cache: &HttpCache,
+    ) -> Result<()> {
+        let Some(xml_url) = sub.xml_url.as_deref() else {
+            return Err(anyhow!(
+                "Cannot save feed of \"{}\" without a link",
+                sub.text
+            ));
+        };
+        let feed_key = SubscriptionId::from_link(xml_url);
+        let mut tx = self.pool.begin().await?;
+        let account_id = find_account_id(&mut tx, account_id).await?;
+        let stored_id: Option<String> = sqlx::query_scalar(
+            "SELECT feed_id FROM subscriptions WHERE account_id = ? AND xml_url = ?",
+        )
+        .bind(account_id)
+        .bind(xml_url)
+        .fetch_optional(&mut *tx)
+        .await?
+        .flatten();
+        let stored_position: Option<i64> =
+            sqlx::query_scalar("SELECT position FROM feeds WHERE account_id = ? AND id = ?")
+                .bind(account_id)
+                .bind(stored_id.as_deref().unwrap_or(feed_key.as_ref()))
+                .fetch_optional(&mut *tx)
+                .await?;
+        let position = match stored_position {
+            Some(position) => position,
+            None => {
+                sqlx::query_scalar(
+                    "SELECT COALESCE(MAX(position) + 1, 0) FROM feeds WHERE account_id = ?",
+                )
+                .bind(account_id)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+
+        // NOTE deleting the stored feed cascades to its entries
+        sqlx::query("DELETE FROM feeds WHERE account_id = ? AND id = ?")
+            .bind(account_id)
+            .bind(feed_key.as_ref())
+            .execute(&mut *tx)
+            .await?;
+        insert_feed(&mut tx, account_id, feed_key.as_ref(), position, feed).await?;
+
+        sqlx::query(
+            "UPDATE subscriptions SET html_url = ?, title = ?, TEXT = ?, description = ?, TYPE = ?, version = ?, category = ?, language = ?, etag = ?, last_modified = ?, feed_id = ?
+            WHERE account_id = ? AND xml_url = ?",
+        )
+        .bind(&sub.html_url)
+        .bind(&sub.title)
+        .bind(&sub.text)
+        .bind(&sub.description)
+        .bind(&sub.r#type)
+        .bind(&sub.version)
+        .bind(&sub.category)
+        .bind(&sub.language)
+        .bind(&http_cache.etag)
+        .bind(&http_cache.last_modified)
+        .bind(feed_key.as_ref())
+        .bind(account_id)
+        .bind(xml_url)
+        .execute(&mut *tx)
+        .await?;
+
+        // NOTE rows stored under the feed's own id go once no subscription links to them
+        let dropped_feeds = delete_unsubscribed_feeds(&mut tx, account_id).await?;
+        if stored_position.is_some() || dropped_feeds {
+            prune_entry_status(&mut tx, account_id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn save_entry_status(
+        &self,
+        account_id: &AccountId,
+        status: &HashMap<EntryId, EntryStatus>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let account_id = find_account_id(&mut tx, account_id).await?;
+
+        for (entry_id, entry_status) in status {
+            // NOTE the core doesn't track entries that are unread and unstarred
+            if *entry_status == EntryStatus::default() {
+                sqlx::query("DELETE FROM entry_status WHERE account_id = ? AND entry_id = ?")
+                    .bind(account_id)
+                    .bind(entry_id.as_ref())
+                    .execute(&mut *tx)
+                    .await?;
+                continue;
+            }
+
+            sqlx::query(
+                "INSERT INTO entry_status (account_id, entry_id, read_status, starred_status) VALUES (?, ?, ?, ?)
+                ON CONFLICT (account_id, entry_id) DO UPDATE SET read_status = excluded.read_status, starred_status = excluded.starred_status",
+            )
+            .bind(account_id)
+            .bind(entry_id.as_ref())
+            .bind(read_status_name(&entry_status.read))
+            .bind(starred_status_name(&entry_status.star))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+async fn find_account_id(tx: &mut Transaction<'_, Sqlite>, account_id: &AccountId) -> Result<i64> {
+    sqlx::query_scalar("SELECT id FROM accounts WHERE uid = ?")
+        .bind(account_id.as_ref())
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| anyhow!("Cannot find account \"{account_id}\""))
+}
+
+// NOTE feeds no subscription links to any more go. true if any did.
+async fn delete_unsubscribed_feeds(
+    tx: &mut Transaction<'_, Sqlite>,
+    account_id: i64,
+) -> Result<bool> {
+    let dropped_feeds = sqlx::query(
+        "DELETE FROM feeds WHERE account_id = ? AND id NOT IN
+        (SELECT feed_id FROM subscriptions WHERE account_id = ? AND feed_id IS NOT NULL)",
+    )
+    .bind(account_id)
+    .bind(account_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(dropped_feeds.rows_affected() > 0)
+}
+
+// NOTE entries no feed holds any more have no status to keep
+async fn prune_entry_status(tx: &mut Transaction<'_, Sqlite>, account_id: i64) -> Result<()> {
+    sqlx::query(
+        "DELETE FROM entry_status WHERE account_id = ? AND entry_id NOT IN
+        (SELECT id FROM entries WHERE account_id = ?)",
+    )
+    .bind(account_id)
+    .bind(account_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+// NOTE accounts.name is constrained to these by the schema. accounts.alias is the display name
+// NOTE and accounts.uid the core's AccountId.
+fn account_type_name(account_type: &AccountType) -> Result<&'static str> {
+    match account_type {
+        AccountType::Local => Ok("Local"),
+        AccountType::Apple => Ok("iCloud"),
+        AccountType::Google => Ok("Google Sync"),
+        AccountType::Microsoft => Ok("Live 365"),
+        AccountType::Canonical => Ok("Ubuntu One"),
+        other => Err(anyhow!("Unsupported account type {other:?}")),
+    }
+}
+
+fn account_type(name: &str) -> Result<AccountType> {
+    match name {
+        "Local" => Ok(AccountType::Local),
+        "iCloud" => Ok(AccountType::Apple),
+        "Google Sync" => Ok(AccountType::Google),
+        "Live 365" => Ok(AccountType::Microsoft),
+        "Ubuntu One" => Ok(AccountType::Canonical),
+        other => Err(anyhow!("Unsupported account type \"{other}\"")),
+    }
+}
+
+#[derive(FromRow, Clone)]
+struct SubscriptionRow {
+    parent_folder: Option<String>,
+    position: i64,
+    xml_url: String,
+    html_url: Option<String>,
+    title: Option<String>,
+    text: String,
+    description: Option<String>,
+    kind: Option<String>,
+    version: Option<String>,
+    is_comment: Option<bool>,
+    is_breakpoint: Option<bool>,
+    created: Option<String>,
+    category: Option<String>,
+    language: Option<String>,
+    url: Option<String>,
+}
+
+impl From<SubscriptionRow> for Outline {
+    fn from(row: SubscriptionRow) -> Self {
+        Outline {
+            text: row.text,
+            r#type: row.kind,
+            is_comment: row.is_comment,
+            is_breakpoint: row.is_breakpoint,
+            created: row.created,
+            category: row.category,
+            xml_url: Some(row.xml_url),
+            description: row.description,
+            html_url: row.html_url,
+            language: row.language,
+            title: row.title,
+            version: row.version,
+            url: row.url,
+            ..Outline::default()
+        }
+    }
+}
+
+type FolderRows<'a> = Vec<(FolderPath, Option<FolderPath>, usize, &'a Outline)>;
+type SubscriptionRows<'a> = Vec<(Option<FolderPath>, usize, &'a Outline)>;
+
+// NOTE folders are listed before their children, so parent folders exist when these are saved
+fn flatten_outlines<'a>(
+    parent: Option<&FolderPath>,
+    outlines: &'a [Outline],
+    folders: &mut FolderRows<'a>,
+    subscriptions: &mut SubscriptionRows<'a>,
+) {
+    for (position, outline) in outlines.iter().enumerate() {
+        if outline.xml_url.is_some() {
+            subscriptions.push((parent.cloned(), position, outline));
+            continue;
+        }
+
+        let folder_name = FolderName::from(outline.text.as_str());
+        let folder_path = match parent {
+            Some(parent) => parent.join(&folder_name),
+            None => FolderPath::from(folder_name),
+        };
+        folders.push((folder_path.clone(), parent.cloned(), position, outline));
+        flatten_outlines(
+            Some(&folder_path),
+            &outline.outlines,
+            folders,
+            subscriptions,
+        );
+    }
+}
+
+// NOTE folder paths are stored as JSON arrays of names
+fn folder_path_key(folder_path: Option<&FolderPath>) -> Result<Option<String>> {
+    Ok(folder_path.map(serde_json::to_string).transpose()?)
+}
+
+// NOTE what a subscription was fetched with: its validators and feed
+#[derive(FromRow)]
+struct FetchedRow {
+    xml_url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    feed_id: Option<String>,
+}
+
+async fn save_outlines(
+    tx: &mut Transaction<'_, Sqlite>,
+    account_id: i64,
+    subs: &OPML,
+    folder_ids: &HashMap<FolderId, FolderPath>,
+    fetched: &HashMap<String, FetchedRow>,
+) -> Result<()> {
+    let (mut folders, mut subscriptions) = (Vec::new(), Vec::new());
+    flatten_outlines(None, &subs.body.outlines, &mut folders, &mut subscriptions);
+
+    for (folder_path, parent, position, folder) in folders {
+        sqlx::query(
+            "INSERT INTO folders (account_id, id, path, name, parent_folder, position) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(account_id)
+        .bind(
+            folder_ids
+                .iter()
+                .find(|(_, path)| **path == folder_path)
+                .map(|(folder_id, _)| folder_id.as_ref()),
+        )
+        .bind(folder_path_key(Some(&folder_path))?)
+        .bind(&folder.text)
+        .bind(folder_path_key(parent.as_ref())?)
+        .bind(position as i64)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    for (parent, position, sub) in subscriptions {
+        let parent_folder = folder_path_key(parent.as_ref())?;
+        save_subscription(
+            tx,
+            account_id,
+            parent_folder.as_deref(),
+            position,
+            sub,
+            sub.xml_url
+                .as_ref()
+                .and_then(|xml_url| fetched.get(xml_url)),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn save_subscription(
+    tx: &mut Transaction<'_, Sqlite>,
+    account_id: i64,
+    parent_folder: Option<&str>,
+    position: usize,
+    sub: &Outline,
+    fetched: Option<&FetchedRow>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO subscriptions (account_id, parent_folder, position, xml_url, html_url, title, TEXT, description, TYPE, version, is_comment, is_breakpoint, created, category, language, url, etag, last_modified, feed_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(account_id)
+    .bind(parent_folder)
+    .bind(position as i64)
+    .bind(&sub.xml_url)
+    .bind(&sub.html_url)
+    .bind(&sub.title)
+    .bind(&sub.text)
+    .bind(&sub.description)
+    .bind(&sub.r#type)
+    .bind(&sub.version)
+    .bind(sub.is_comment)
+    .bind(sub.is_breakpoint)
+    .bind(&sub.created)
+    .bind(&sub.category)
+    .bind(&sub.language)
+    .bind(&sub.url)
+    .bind(fetched.and_then(|row| row.etag.as_deref()))
+    .bind(fetched.and_then(|row| row.last_modified.as_deref()))
+    .bind(fetched.and_then(|row| row.feed_id.as_deref()))
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(FromRow)]
+struct FolderRow {
+    path: String,
+    name: String,
+    parent_folder: Option<String>,
+    position: i64,
+}
+
+fn build_outlines(
+    parent_folder: Option<&str>,
+    folders: &[FolderRow],
+    subs: &[SubscriptionRow],
+) -> Vec<Outline> {
+    let mut outlines: Vec<(i64, Outline)> = subs
+        .iter()
+        .filter(|sub| sub.parent_folder.as_deref() == parent_folder)
+        .map(|sub| (sub.position, sub.clone().into()))
+        .collect();
+
+    for folder in folders
+        .iter()
+        .filter(|folder| folder.parent_folder.as_deref() == parent_folder)
+    {
+        outlines.push((
+            folder.position,
+            Outline {
+                text: folder.name.clone(),
+                title: Some(folder.name.clone()),
+                outlines: build_outlines(Some(&folder.path), folders, subs),
+                ..Outline::default()
+            },
+        ));
+    }
+    outlines.sort_by_key(|(position, _)| *position);
+
+    outlines.into_iter().map(|(_, outline)| outline).collect()
+}
+
+async fn load_outlines(conn: &mut SqliteConnection, account_id: i64) -> Result<Vec<Outline>> {
+    let folders: Vec<FolderRow> = sqlx::query_as(
+        "SELECT path, name, parent_folder, position FROM folders WHERE account_id = ?",
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let subs: Vec<SubscriptionRow> = sqlx::query_as(
+        "SELECT parent_folder, position, xml_url, html_url, title, TEXT AS text, description, TYPE AS kind, version, is_comment, is_breakpoint, created, category, language, url
+        FROM subscriptions WHERE account_id = ? ORDER BY position",
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(build_outlines(None, &folders, &subs))
+}
+
+async fn load_folder_ids(
+    conn: &mut SqliteConnection,
+    account_id: i64,
+) -> Result<HashMap<FolderId, FolderPath>> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, path FROM folders WHERE account_id = ? AND id IS NOT NULL")
+            .bind(account_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    rows.into_iter()
+        .map(|(id, path)| Ok((FolderId::from(id), serde_json::from_str(&path)?)))
+        .collect()
+}
+
+async fn load_http_cache(
+    conn: &mut SqliteConnection,
+    account_id: i64,
+) -> Result<HashMap<SubscriptionLink, HttpCache>> {
+    let rows: Vec<(String, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT xml_url, etag, last_modified FROM subscriptions
+        WHERE account_id = ? AND (etag IS NOT NULL OR last_modified IS NOT NULL)",
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(xml_url, etag, last_modified)| {
+            (
+                SubscriptionLink::from(xml_url),
+                HttpCache {
+                    etag,
+                    last_modified,
+                },
+            )
+        })
+        .collect())
+}
+
+fn authors(persons: &[Person]) -> String {
+    persons
+        .iter()
+        .map(|person| person.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn insert_feed(
+    tx: &mut Transaction<'_, Sqlite>,
+    account_id: i64,
+    feed_key: &str,
+    position: i64,
+    feed: &Feed,
+) -> Result<()> {
+    let raw = serde_json::to_string(&Feed {
+        entries: Vec::new(),
+        ..feed.clone()
+    })?;
+
+    sqlx::query(
+        "INSERT INTO feeds (id, account_id, position, title, home, logo, icon, published, updated, authors, description, language, rights, ttl, feed_type, raw)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(feed_key)
+    .bind(account_id)
+    .bind(position)
+    .bind(feed.title.as_ref().map(|t| t.content.as_str()).unwrap_or_default())
+    .bind(feed.links.first().map(|l| l.href.as_str()).unwrap_or_default())
+    .bind(feed.logo.as_ref().map(|i| i.uri.as_str()))
+    .bind(feed.icon.as_ref().map(|i| i.uri.as_str()))
+    .bind(feed.published.map(|d| d.to_rfc3339()).unwrap_or_default())
+    .bind(feed.updated.map(|d| d.to_rfc3339()).unwrap_or_default())
+    .bind(authors(&feed.authors))
+    .bind(feed.description.as_ref().map(|t| t.content.as_str()))
+    .bind(&feed.language)
+    .bind(feed.rights.as_ref().map(|t| t.content.as_str()))
+    .bind(feed.ttl)
+    .bind(format!("{:?}", feed.feed_type))
+    .bind(raw)
+    .execute(&mut **tx)
+    .await?;
+
+    save_entries(tx, account_id, feed_key, &feed.entries).await
+}
+
+// NOTE duplicated entries keep their first copy, as lookups in the core do
+async fn save_entries(
+    tx: &mut Transaction<'_, Sqlite>,
+    account_id: i64,
+    feed_id: &str,
+    entries: &[Entry],
+) -> Result<()> {
+    let mut saved_entries = HashSet::new();
+
+    for (position, entry) in entries.iter().enumerate() {
+        if !saved_entries.insert(entry.id.as_str()) {
+            continue;
+        }
+
+        sqlx::query(
+            "INSERT INTO entries (id, account_id, feed_id, position, title, authors, published, updated, summary, source, rights, language, base, raw)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.id)
+        .bind(account_id)
+        .bind(feed_id)
+        .bind(position as i64)
+        .bind(entry.title.as_ref().map(|t| t.content.as_str()).unwrap_or_default())
+        .bind(authors(&entry.authors))
+        .bind(entry.published.map(|d| d.to_rfc3339()).unwrap_or_default())
+        .bind(entry.updated.map(|d| d.to_rfc3339()).unwrap_or_default())
+        .bind(entry.summary.as_ref().map(|t| t.content.as_str()).unwrap_or_default())
+        .bind(&entry.source)
+        .bind(entry.rights.as_ref().map(|t| t.content.as_str()))
+        .bind(&entry.language)
+        .bind(&entry.base)
+        .bind(serde_json::to_string(entry)?)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+fn read_status_name(read_status: &ReadStatus) -> &'static str {
+    match read_status {
+        ReadStatus::Unread => "unread",
+        ReadStatus::Read => "read",
+    }
+}
+
+fn starred_status_name(star_status: &StarStatus) -> &'static str {
+    match star_status {
+        StarStatus::Unstarred => "unstarred",
+        StarStatus::Starred => "starred",
+    }
+}
+
+// NOTE each subscription gets its own copy, also of rows stored under a feed's shared id
+async fn load_feeds(conn: &mut SqliteConnection, account_id: i64) -> Result<Feeds> {
+    let rows: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT s.xml_url, f.id, f.raw FROM subscriptions AS s
+        JOIN feeds AS f ON f.account_id = s.account_id AND f.id = s.feed_id
+        WHERE s.account_id = ?",
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut feeds = Feeds::default();
+
+    for (xml_url, feed_key, raw) in rows {
+        let mut feed: Feed = serde_json::from_str(&raw)?;
+        let entries: Vec<String> = sqlx::query_scalar(
+            "SELECT raw FROM entries WHERE account_id = ? AND feed_id = ? ORDER BY position",
+        )
+        .bind(account_id)
+        .bind(&feed_key)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for raw in entries {
+            feed.entries.push(serde_json::from_str(&raw)?);
+        }
+        feeds
+            .feeds
+            .insert(SubscriptionId::from_link(&xml_url), feed);
+    }
+
+    Ok(feeds)
+}
+
+async fn load_entry_status(
+    conn: &mut SqliteConnection,
+    account_id: i64,
+) -> Result<HashMap<EntryId, EntryStatus>> {
+    let rows: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT entry_id, read_status, starred_status FROM entry_status WHERE account_id = ?",
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(entry_id, read_status, starred_status)| {
+            let status = EntryStatus {
+                read: match read_status.as_str() {
+                    "read" => ReadStatus::Read,
+                    _ => ReadStatus::Unread,
+                },
+                star: match starred_status.as_str() {
+                    "starred" => StarStatus::Starred,
+                    _ => StarStatus::Unstarred,
+                },
+            };
+            (EntryId::from(entry_id), status)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod sqlite {
+    use super::*;
+    use shared::fixtures::EXAMPLE_RSS;
+    use shared::{AccountName, SubscriptionId};
+
+    fn example_accounts() -> Accounts {
+        let mut accounts = Accounts::default();
+        let mut local = Account::new(&AccountType::Local);
+        let mut apple = Account::new(&AccountType::Apple);
+
+        local.name = "Personal".to_string();
+        local
+            .subs
+            .subs
+            .add_feed("Root Feed", "https://example.com/atom.xml");
+        local.subs.subs.body.outlines.push(Outline {
+            text: "Group Name".to_string(),
+            title: Some("Group Name".to_string()),
+            ..Outline::default()
+        });
+        local.subs.subs.body.outlines[1].outlines.push(Outline {
+            text: "Feed Name".to_string(),
+            title: Some("Feed Name".to_string()),
+            r#type: Some("rss".to_string()),
+            version: Some("RSS".to_string()),
+            html_url: Some("https://example.com/".to_string()),
+            xml_url: Some("https://example.com/rss.xml".to_string()),
+            ..Outline::default()
+        });
+        // a nested folder with the same name as its parent
+        local.subs.subs.body.outlines[1].outlines.push(Outline {
+            text: "Group Name".to_string(),
+            title: Some("Group Name".to_string()),
+            outlines: vec![Outline {
+                text: "Nested Feed".to_string(),
+                xml_url: Some("https://example.com/nested.xml".to_string()),
+                ..Outline::default()
+            }],
+            ..Outline::default()
+        });
+        local.subs.feeds.feeds.insert(
+            SubscriptionId::from_link("https://example.com/rss.xml"),
+            feed_rs::parser::parse(EXAMPLE_RSS.as_bytes()).unwrap(),
+        );
+        local.subs.http_cache.insert(
+            SubscriptionLink::from("https://example.com/rss.xml"),
+            HttpCache {
+                etag: Some("\"abc123\"".to_string()),
+                last_modified: Some("Sun, 19 Oct 2025 09:00:00 GMT".to_string()),
+            },
+        );
+        local.subs.feeds.status.insert(
+            EntryId::from("https://example.com/one"),
+            EntryStatus {
+                read: ReadStatus::Read,
+                star: StarStatus::Starred,
+            },
+        );
+
+        let group_name = FolderPath::from("Group Name");
+        let nested_group_name = group_name.join(&FolderName::from("Group Name"));
+        local
+            .subs
+            .folder_ids
+            .insert(FolderId::from("group-name"), group_name.clone());
+        local
+            .subs
+            .folder_ids
+            .insert(FolderId::from("nested-group-name"), nested_group_name);
+
+        // same folder and feed in another account
+        apple.subs.subs.body.outlines = local.subs.subs.body.outlines.clone();
+        apple.subs.feeds = local.subs.feeds.clone();
+        apple
+            .subs
+            .folder_ids
+            .insert(FolderId::from("apple-group-name"), group_name);
+
+        accounts.acct.push(local);
+        accounts.acct.push(apple);
+        accounts
+    }
+
+    // NOTE saves accounts the way the core does, one change at a time
+    async fn save_accounts(storage: &SqliteStorage, accounts: &Accounts) {
+        for account in &accounts.acct {
+            let mut operations = vec![
+                StorageOperation::SaveAccount(
+                    account.id.clone(),
+                    account.account_type.clone(),
+                    account.name.clone(),
+                ),
+                StorageOperation::SaveSubscriptions(
+                    account.id.clone(),
+                    Box::new(account.subs.subs.clone()),
+                    account.subs.folder_ids.clone(),
+                ),
+            ];
+            for sub_link in account.subs.list_subscription_links() {
+                let Ok(feed) = account.subs.find_feed(&sub_link) else {
+                    continue;
+                };
+                let (_, sub) = account
+                    .subs
+                    .find_subscription_by_id(&SubscriptionId::from(&sub_link))
+                    .unwrap();
+                operations.push(StorageOperation::SaveFeed(
+                    account.id.clone(),
+                    Box::new(sub),
+                    Box::new(feed),
+                    account
+                        .subs
+                        .http_cache
+                        .get(&sub_link)
+                        .cloned()
+                        .unwrap_or_default(),
+                ));
+            }
+            operations.push(StorageOperation::SaveEntryStatus(
+                account.id.clone(),
+                account.subs.feeds.status.clone(),
+            ));
+
+            for operation in operations {
+                assert_eq!(storage.handle(operation).await, Ok(StorageResponse::Saved));
+            }
+        }
+    }
+
+    async fn load(storage: &SqliteStorage) -> Accounts {
+        match storage.handle(StorageOperation::Load).await {
+            Ok(StorageResponse::Loaded(accounts)) => accounts,
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn load_empty_storage() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+
+        let loaded = storage.handle(StorageOperation::Load).await;
+
+        assert_eq!(loaded, Ok(StorageResponse::Loaded(Accounts::default())));
+    }
+
+    #[tokio::test]
+    async fn save_and_load_accounts() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn delete_account_keeps_other_accounts() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        let deleted = accounts.acct.remove(0);
+        let _ = storage
+            .handle(StorageOperation::DeleteAccount(deleted.id))
+            .await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn save_and_load_accounts_of_same_type() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let accounts = Accounts::default()
+            .create_named(&AccountType::Local, &AccountName::from("Work"))
+            .and_then(|accounts| {
+                accounts.create_named(&AccountType::Local, &AccountName::from("Personal"))
+            })
+            .unwrap();
+
+        save_accounts(&storage, &accounts).await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn save_renamed_account() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        accounts.acct[0].name = "Work".to_string();
+        let account = &accounts.acct[0];
+        let _ = storage
+            .handle(StorageOperation::SaveAccount(
+                account.id.clone(),
+                account.account_type.clone(),
+                account.name.clone(),
+            ))
+            .await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn save_subscriptions_keeps_their_feeds() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        let local = &mut accounts.acct[0];
+        local.subs.subs.body.outlines[1].outlines[0].text = "Renamed Feed".to_string();
+        let _ = storage
+            .handle(StorageOperation::SaveSubscriptions(
+                local.id.clone(),
+                Box::new(local.subs.subs.clone()),
+                local.subs.folder_ids.clone(),
+            ))
+            .await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn save_subscriptions_drops_feeds_no_longer_subscribed() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        let local = &mut accounts.acct[0];
+        local.subs.subs.body.outlines[1].outlines.remove(0);
+        local.subs.feeds = Feeds::default();
+        local.subs.http_cache.clear();
+        let _ = storage
+            .handle(StorageOperation::SaveSubscriptions(
+                local.id.clone(),
+                Box::new(local.subs.subs.clone()),
+                local.subs.folder_ids.clone(),
+            ))
+            .await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn refetched_feed_replaces_its_entries() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        let local = &mut accounts.acct[0];
+        let sub_link = SubscriptionLink::from("https://example.com/rss.xml");
+        let (_, sub) = local
+            .subs
+            .find_subscription_by_id(&SubscriptionId::from(&sub_link))
+            .unwrap();
+        let feed = local
+            .subs
+            .feeds
+            .feeds
+            .get_mut(&SubscriptionId::from(&sub_link))
+            .unwrap();
+        feed.entries.remove(0);
+        let feed = feed.clone();
+        local.subs.feeds.status.clear();
+        local.subs.http_cache.remove(&sub_link);
+        let _ = storage
+            .handle(StorageOperation::SaveFeed(
+                local.id.clone(),
+                Box::new(sub),
+                Box::new(feed),
+                HttpCache::default(),
+            ))
+            .await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn feeds_sharing_an_id_are_saved_apart() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+        let mirror_rss = r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0"><channel><title>Feed Name</title><link>https://example.com/</link><description>An example feed</description><item><guid>https://example.com/three</guid><title>Third Entry</title></item></channel></rss>"#;
+
+        let local = &mut accounts.acct[0];
+        let mirror = feed_rs::parser::parse(mirror_rss.as_bytes()).unwrap();
+        let feed_ids: HashSet<&str> = local
+            .subs
+            .feeds
+            .feeds
+            .values()
+            .map(|feed| feed.id.as_str())
+            .collect();
+        assert!(feed_ids.contains(mirror.id.as_str()));
+        local.subs.feeds.feeds.insert(
+            SubscriptionId::from_link("https://example.com/atom.xml"),
+            mirror,
+        );
+        save_accounts(&storage, &accounts).await;
+
+        assert_eq!(load(&storage).await, accounts);
+    }
+
+    #[tokio::test]
+    async fn remove_status_back_to_unread_and_unstarred() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+        let mut accounts = example_accounts();
+
+        save_accounts(&storage, &accounts).await;
+        let local = &mut accounts.acct[0];
+        local.subs.feeds.status.clear();
+        let _ = storage
+            .handle(StorageOperation::SaveEntryStatus(
+                local.id.clone(),
+                HashMap::from([(
+                    EntryId::from("https://example.com/one"),
+                    EntryStatus::default(),
+                )]),
+            ))
+            .await;
+        let status_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entry_status")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(load(&storage).await, accounts);
+        assert_eq!(status_count, 1);
+    }
+
+    #[tokio::test]
+    async fn saved_entries_show_in_views() {
+        let storage = SqliteStorage::connect("sqlite::memory:").await.unwrap();
+
+        save_accounts(&storage, &example_accounts()).await;
+        let unread_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM unread_view")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        let starred_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM starred_view")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(unread_count, 2);
+        assert_eq!(starred_count, 2);
+    }
+
+    // NOTE rows stored before tables were keyed by account, with the first schema
+    #[tokio::test]
+    async fn migrate_rows_stored_before_tables_were_keyed_by_account() {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        let first_schema = Migrator {
+            migrations: MIGRATOR
+                .iter()
+                .filter(|migration| migration.version < 20261018090000)
+                .cloned()
+                .collect::<Vec<_>>()
+                .into(),
+            ..Migrator::DEFAULT
+        };
+        first_schema.run(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO accounts (name, alias) VALUES ('Local', 'Personal');
+            INSERT INTO folders (account_id, name) VALUES (1, 'Group Name');
+            INSERT INTO subscriptions (account_id, parent_folder, xml_url, html_url, title, TEXT)
+            VALUES (1, 'Group Name', 'https://example.com/rss.xml', 'https://example.com/', 'Feed Name', 'Feed Name'),
+            (1, NULL, 'https://example.com/atom.xml', 'https://example.com/blog/', 'Root Feed', 'Root Feed');
+            INSERT INTO feeds (id, account_id, title, home, published, updated, authors)
+            VALUES ('feed', 1, 'Feed Name', 'https://example.com/', '', '', '');
+            INSERT INTO entries (id, feed_id, title, authors, published, updated, summary, read_status, starred_status)
+            VALUES ('https://example.com/one', 'feed', '', '', '', '', '', 'read', 'unstarred'),
+            ('https://example.com/two', 'feed', '', '', '', '', '', 'unread', 'unstarred'),
+            ('https://example.com/three', 'feed', '', '', '', '', '', 'unread', 'starred');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        MIGRATOR.run(&pool).await.unwrap();
+        let storage = SqliteStorage { pool };
+        let loaded = load(&storage).await;
+        let subs = &loaded.acct[0].subs;
+        let (folder_path, _) = subs
+            .find_subscription_by_id(&SubscriptionId::from("https://example.com/rss.xml"))
+            .unwrap();
+
+        assert_eq!(loaded.acct.len(), 1);
+        assert_eq!(loaded.acct[0].name, "Personal");
+        assert_eq!(folder_path, Some(FolderPath::from("Group Name")));
+        assert!(subs
+            .find_subscription_by_id(&SubscriptionId::from("https://example.com/atom.xml"))
+            .is_some_and(|(folder_path, _)| folder_path.is_none()));
+        assert!(subs
+            .find_folder_id(&FolderPath::from("Group Name"))
+            .is_some());
+        assert!(subs.feeds.feeds.is_empty());
+        assert_eq!(
+            subs.feeds.status,
+            HashMap::from([
+                (
+                    EntryId::from("https://example.com/one"),
+                    EntryStatus {
+                        read: ReadStatus::Read,
+                        star: StarStatus::Unstarred,
+                    },
+                ),
+                (
+                    EntryId::from("https://example.com/three"),
+                    EntryStatus {
+                        read: ReadStatus::Unread,
+                        star: StarStatus::Starred,
+                    },
+                ),
+            ])
+        );
+    }
+}